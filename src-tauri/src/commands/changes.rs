@@ -0,0 +1,42 @@
+use crate::db::{self, ChangeDigest, DbState, LeadChange};
+use tauri::State;
+
+/// Days of digests listed when none is given
+const DEFAULT_DIGEST_DAYS: i64 = 14;
+
+// ============================================================================
+// Change Digest Commands
+// ============================================================================
+
+/// Changes detected by research re-runs, grouped into daily digests (newest first)
+#[tauri::command]
+pub fn get_change_digests(
+    state: State<'_, DbState>,
+    days: Option<i64>,
+) -> Result<Vec<ChangeDigest>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_change_digests(&conn, days.unwrap_or(DEFAULT_DIGEST_DAYS)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_lead_changes(
+    state: State<'_, DbState>,
+    lead_id: i64,
+) -> Result<Vec<LeadChange>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_lead_changes(&conn, lead_id).map_err(|e| e.to_string())
+}
+
+/// Export the digest of one day (YYYY-MM-DD, local time) as Markdown
+#[tauri::command]
+pub fn export_change_digest_markdown(
+    state: State<'_, DbState>,
+    date: String,
+) -> Result<String, String> {
+    if chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").is_err() {
+        return Err(format!("Invalid date '{}', expected YYYY-MM-DD", date));
+    }
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let digest = db::get_change_digest(&conn, &date).map_err(|e| e.to_string())?;
+    Ok(db::digest_to_markdown(&digest))
+}
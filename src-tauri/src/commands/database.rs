@@ -1,8 +1,9 @@
 use crate::db::{
-    self, DbState, Lead, LeadWithScore, NewLead, NewPerson, ParsedLeadScore, ParsedScoringConfig,
-    Person, PersonWithCompany,
+    self, DbState, FieldSourceType, Lead, LeadQuery, LeadWithScore, NewLead, NewPerson, Page,
+    ParsedLeadScore, ParsedScoringConfig, Person, PersonQuery, PersonWithCompany,
 };
 use crate::events;
+use serde::Serialize;
 use tauri::{AppHandle, State};
 
 // ============================================================================
@@ -30,14 +31,30 @@ pub struct AdjacentLeadsResult {
     pub total: usize,
 }
 
+/// Filtered, sorted, paginated lead list. Pass the returned `nextCursor` back
+/// in `query.cursor` to fetch the following page.
+#[tauri::command]
+pub fn query_leads(
+    state: State<'_, DbState>,
+    query: LeadQuery,
+) -> Result<Page<LeadWithScore>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::query_leads(&conn, &query).map_err(|e| e.to_string())
+}
+
+/// Neighbours of a lead within the list defined by `query` (filter and sort;
+/// cursor and limit are ignored). Defaults to all leads by company name.
 #[tauri::command]
 pub fn get_adjacent_leads(
     state: State<'_, DbState>,
     current_id: i64,
+    query: Option<LeadQuery>,
 ) -> Result<AdjacentLeadsResult, String> {
+    let query = query.unwrap_or_default();
     let conn = state.conn.lock().map_err(|e| e.to_string())?;
     let (prev, next, idx, total) =
-        db::get_adjacent_leads(&conn, current_id).map_err(|e| e.to_string())?;
+        db::get_adjacent_leads(&conn, current_id, &query.filter, &query.sort)
+            .map_err(|e| e.to_string())?;
     Ok(AdjacentLeadsResult {
         prev_lead: prev,
         next_lead: next,
@@ -46,14 +63,56 @@ pub fn get_adjacent_leads(
     })
 }
 
+/// Why a lead wasn't inserted. `existing_id` is set when the company already
+/// exists, so the caller can open it or insert anyway with `force`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertLeadError {
+    pub message: String,
+    pub existing_id: Option<i64>,
+}
+
+impl From<String> for InsertLeadError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            existing_id: None,
+        }
+    }
+}
+
+/// Insert a lead. Fails with the existing lead's id when the company already
+/// exists (same website domain or near-identical name), unless `force` is
+/// set. `source_type` marks where the field values came from ("user" unless
+/// given, e.g. "import").
 #[tauri::command]
 pub fn insert_lead(
     app: AppHandle,
     state: State<'_, DbState>,
     data: NewLead,
-) -> Result<i64, String> {
+    force: Option<bool>,
+    source_type: Option<FieldSourceType>,
+) -> Result<i64, InsertLeadError> {
     let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    if !force.unwrap_or(false) {
+        let existing = db::find_matching_lead(&conn, &data.company_name, data.website.as_deref())
+            .map_err(|e| e.to_string())?;
+        if let Some(existing_id) = existing {
+            return Err(InsertLeadError {
+                message: "A lead for this company already exists".to_string(),
+                existing_id: Some(existing_id),
+            });
+        }
+    }
     let id = db::insert_lead(&conn, &data).map_err(|e| e.to_string())?;
+    db::record_inserted_fields(
+        &conn,
+        "lead",
+        id,
+        source_type.unwrap_or(FieldSourceType::User),
+        None,
+    )
+    .map_err(|e| e.to_string())?;
     drop(conn);
     events::emit_lead_created(&app, id);
     Ok(id)
@@ -135,14 +194,28 @@ pub fn get_all_people(state: State<'_, DbState>) -> Result<Vec<PersonWithCompany
     db::get_all_people(&conn).map_err(|e| e.to_string())
 }
 
+/// Filtered, sorted, paginated people list
+#[tauri::command]
+pub fn query_people(
+    state: State<'_, DbState>,
+    query: PersonQuery,
+) -> Result<Page<PersonWithCompany>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::query_people(&conn, &query).map_err(|e| e.to_string())
+}
+
+/// Neighbours of a person within the list defined by `query` (filter and sort)
 #[tauri::command]
 pub fn get_adjacent_people(
     state: State<'_, DbState>,
     current_id: i64,
+    query: Option<PersonQuery>,
 ) -> Result<AdjacentLeadsResult, String> {
+    let query = query.unwrap_or_default();
     let conn = state.conn.lock().map_err(|e| e.to_string())?;
     let (prev, next, idx, total) =
-        db::get_adjacent_people(&conn, current_id).map_err(|e| e.to_string())?;
+        db::get_adjacent_people(&conn, current_id, &query.filter, &query.sort)
+            .map_err(|e| e.to_string())?;
     Ok(AdjacentLeadsResult {
         prev_lead: prev,
         next_lead: next,
@@ -152,9 +225,22 @@ pub fn get_adjacent_people(
 }
 
 #[tauri::command]
-pub fn insert_person(state: State<'_, DbState>, data: NewPerson) -> Result<i64, String> {
+pub fn insert_person(
+    state: State<'_, DbState>,
+    data: NewPerson,
+    source_type: Option<FieldSourceType>,
+) -> Result<i64, String> {
     let conn = state.conn.lock().map_err(|e| e.to_string())?;
-    db::insert_person(&conn, &data).map_err(|e| e.to_string())
+    let id = db::insert_person(&conn, &data).map_err(|e| e.to_string())?;
+    db::record_inserted_fields(
+        &conn,
+        "person",
+        id,
+        source_type.unwrap_or(FieldSourceType::User),
+        None,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(id)
 }
 
 #[tauri::command]
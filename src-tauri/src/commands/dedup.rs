@@ -0,0 +1,72 @@
+use crate::db::{self, DbState, DuplicateGroup, MergeRecord, MergeResult};
+use crate::events;
+use tauri::{AppHandle, State};
+
+// ============================================================================
+// Deduplication Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn find_duplicate_leads(state: State<'_, DbState>) -> Result<Vec<DuplicateGroup>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::find_duplicate_leads(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn find_duplicate_people(state: State<'_, DbState>) -> Result<Vec<DuplicateGroup>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::find_duplicate_people(&conn).map_err(|e| e.to_string())
+}
+
+/// Merge duplicate leads into `primary_id`. People, scores, jobs, tags and
+/// custom fields move to the primary; the duplicates are deleted.
+#[tauri::command]
+pub fn merge_leads(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    primary_id: i64,
+    duplicate_ids: Vec<i64>,
+) -> Result<MergeResult, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let result = db::merge_leads(&conn, primary_id, &duplicate_ids).map_err(|e| e.to_string())?;
+    drop(conn);
+
+    if !result.merged_ids.is_empty() {
+        events::emit_lead_deleted(&app, result.merged_ids.clone());
+        events::emit_lead_updated(&app, primary_id);
+        events::emit_people_bulk_created(&app, primary_id);
+    }
+    Ok(result)
+}
+
+/// Merge duplicate people into `primary_id`
+#[tauri::command]
+pub fn merge_people(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    primary_id: i64,
+    duplicate_ids: Vec<i64>,
+) -> Result<MergeResult, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let result = db::merge_people(&conn, primary_id, &duplicate_ids).map_err(|e| e.to_string())?;
+    let lead_id = db::get_person_raw(&conn, primary_id)
+        .map_err(|e| e.to_string())?
+        .and_then(|p| p.lead_id);
+    drop(conn);
+
+    if !result.merged_ids.is_empty() {
+        events::emit_person_deleted(&app, result.merged_ids.clone());
+        events::emit_person_updated(&app, primary_id, lead_id);
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_merge_history(
+    state: State<'_, DbState>,
+    entity_type: String,
+    entity_id: i64,
+) -> Result<Vec<MergeRecord>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_merge_history(&conn, &entity_type, entity_id).map_err(|e| e.to_string())
+}
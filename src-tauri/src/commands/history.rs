@@ -0,0 +1,74 @@
+use crate::db::{self, DbState, FieldSources, ProfileDiff, ResearchRun, Source};
+use crate::events::{emit_lead_updated, emit_person_updated};
+use tauri::{AppHandle, State};
+
+// ============================================================================
+// Research History Commands
+// ============================================================================
+
+/// Every stored profile version of a lead ("lead") or person ("person"), newest first
+#[tauri::command]
+pub fn get_research_runs(
+    state: State<'_, DbState>,
+    entity_type: String,
+    entity_id: i64,
+) -> Result<Vec<ResearchRun>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_research_runs(&conn, &entity_type, entity_id).map_err(|e| e.to_string())
+}
+
+/// Make an earlier profile version current again (recorded as a new version)
+#[tauri::command]
+pub fn restore_research_run(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    run_id: i64,
+) -> Result<ResearchRun, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let run = db::restore_research_run(&conn, run_id).map_err(|e| e.to_string())?;
+
+    if run.entity_type == "lead" {
+        drop(conn);
+        emit_lead_updated(&app, run.entity_id);
+    } else {
+        let lead_id = db::get_person_raw(&conn, run.entity_id)
+            .map_err(|e| e.to_string())?
+            .and_then(|p| p.lead_id);
+        drop(conn);
+        emit_person_updated(&app, run.entity_id, lead_id);
+    }
+    Ok(run)
+}
+
+/// Line diff between two profile versions
+#[tauri::command]
+pub fn diff_research_runs(
+    state: State<'_, DbState>,
+    from_run_id: i64,
+    to_run_id: i64,
+) -> Result<ProfileDiff, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::diff_research_runs(&conn, from_run_id, to_run_id).map_err(|e| e.to_string())
+}
+
+/// Sources cited by research for a lead or person, newest run first
+#[tauri::command]
+pub fn get_sources(
+    state: State<'_, DbState>,
+    entity_type: String,
+    entity_id: i64,
+) -> Result<Vec<Source>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_sources(&conn, &entity_type, entity_id).map_err(|e| e.to_string())
+}
+
+/// Which sources back each enrichment field of a lead or person
+#[tauri::command]
+pub fn get_field_sources(
+    state: State<'_, DbState>,
+    entity_type: String,
+    entity_id: i64,
+) -> Result<Vec<FieldSources>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_field_sources(&conn, &entity_type, entity_id).map_err(|e| e.to_string())
+}
@@ -1,5 +1,12 @@
 use crate::db::{self, DbState, Job, JobLog};
-use tauri::State;
+use crate::events;
+use crate::jobs::archive;
+use crate::jobs::completion_handler::CompletionHandler;
+use crate::jobs::log_retention::{self, LogMaintenanceState, LogStorageReport, MaintenanceReport};
+use crate::jobs::stream_events::{self, TimelineStep};
+use crate::jobs::transcript::{self, TranscriptFormat};
+use std::path::Path;
+use tauri::{AppHandle, Manager, State};
 
 // ============================================================================
 // Job Commands
@@ -37,7 +44,39 @@ pub async fn get_job_logs_cmd(
     limit: Option<i64>,
 ) -> Result<Vec<JobLog>, String> {
     let conn = state.conn.lock().map_err(|e| e.to_string())?;
-    db::get_job_logs(&conn, &job_id, after_sequence, limit).map_err(|e| e.to_string())
+    log_retention::job_logs(&conn, &job_id, after_sequence, limit).map_err(|e| e.to_string())
+}
+
+/// Ordered steps of what the agent did during a job, with tool calls paired
+/// to their results. Jobs that predate stored events are rebuilt from their logs.
+#[tauri::command]
+pub async fn get_job_timeline(
+    state: State<'_, DbState>,
+    job_id: String,
+) -> Result<Vec<TimelineStep>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut events = db::get_job_events(&conn, &job_id).map_err(|e| e.to_string())?;
+    if events.is_empty() {
+        let logs =
+            log_retention::job_logs(&conn, &job_id, None, None).map_err(|e| e.to_string())?;
+        events = stream_events::events_from_logs(&logs);
+    }
+    Ok(stream_events::build_timeline(&events))
+}
+
+/// Readable Markdown or HTML transcript of a job: prompt, model, session,
+/// tool calls with inputs and result summaries, timing and final outputs
+#[tauri::command]
+pub async fn export_job_transcript(
+    state: State<'_, DbState>,
+    job_id: String,
+    format: TranscriptFormat,
+    redact: Option<bool>,
+) -> Result<String, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let transcript = transcript::load_transcript(&conn, &job_id, redact.unwrap_or(false))
+        .map_err(|e| e.to_string())?;
+    Ok(transcript::render(&transcript, format))
 }
 
 #[tauri::command]
@@ -54,3 +93,81 @@ pub async fn delete_job_cmd(state: State<'_, DbState>, job_id: String) -> Result
     let conn = state.conn.lock().map_err(|e| e.to_string())?;
     db::delete_job(&conn, &job_id).map_err(|e| e.to_string())
 }
+
+// ============================================================================
+// Output Archive Commands
+// ============================================================================
+
+/// Re-run the parse and database phases on a job's archived outputs, e.g. after
+/// a parser fix, without running the agent again
+#[tauri::command]
+pub async fn reprocess_job_output(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    job_id: String,
+) -> Result<(), String> {
+    let job = {
+        let conn = state.conn.lock().map_err(|e| e.to_string())?;
+        db::get_job(&conn, &job_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Job not found".to_string())?
+    };
+    if matches!(job.status.as_str(), "queued" | "running") {
+        return Err("Job is still running".to_string());
+    }
+    let archive_path = job
+        .output_archive_path
+        .ok_or_else(|| "No archived outputs for this job".to_string())?;
+    let archive = archive::read_archive(Path::new(&archive_path)).map_err(|e| e.to_string())?;
+    let outputs = archive
+        .outputs()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Archive has no primary output".to_string())?;
+
+    let handler = CompletionHandler::new(state.conn.clone(), app.clone());
+    handler
+        .reprocess(&job_id, &archive.metadata(), &outputs)
+        .map_err(|e| e.to_string())?;
+
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::update_job_status(&conn, &job_id, "completed", job.exit_code, None)
+        .map_err(|e| e.to_string())?;
+    events::emit_job_status_changed(&app, job_id, "completed".to_string(), job.exit_code);
+    Ok(())
+}
+
+/// Delete output archives past the retention period; returns how many were removed
+#[tauri::command]
+pub async fn purge_output_archives(
+    app: AppHandle,
+    state: State<'_, DbState>,
+) -> Result<usize, String> {
+    let data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let retention_days = db::get_settings(&conn)
+        .map_err(|e| e.to_string())?
+        .output_retention_days;
+    archive::purge_archives(&conn, &archive::archive_dir(&data_dir), retention_days)
+        .map_err(|e| e.to_string())
+}
+
+/// Log storage by job type, database size and the last maintenance pass
+#[tauri::command]
+pub async fn get_log_storage_report(
+    state: State<'_, DbState>,
+    maintenance: State<'_, LogMaintenanceState>,
+) -> Result<LogStorageReport, String> {
+    let last = maintenance.0.lock().map_err(|e| e.to_string())?.clone();
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    log_retention::storage_report(&conn, last).map_err(|e| e.to_string())
+}
+
+/// Apply the log retention policy and optimize the database now, including
+/// the full VACUUM that scheduled maintenance leaves out
+#[tauri::command]
+pub async fn run_log_maintenance(
+    app: AppHandle,
+    state: State<'_, DbState>,
+) -> Result<MaintenanceReport, String> {
+    log_retention::run_and_record(&state.conn, &app, true).map_err(|e| e.to_string())
+}
@@ -1,13 +1,31 @@
+mod changes;
 mod database;
+mod dedup;
+mod history;
 mod jobs;
+mod model_routing;
 mod prompts;
+mod provenance;
 mod recovery;
+mod redaction;
 mod research;
+mod search;
 mod settings;
+mod tags;
+mod views;
 
+pub use changes::*;
 pub use database::*;
+pub use dedup::*;
+pub use history::*;
 pub use jobs::*;
+pub use model_routing::*;
 pub use prompts::*;
+pub use provenance::*;
 pub use recovery::*;
+pub use redaction::*;
 pub use research::*;
+pub use search::*;
 pub use settings::*;
+pub use tags::*;
+pub use views::*;
@@ -0,0 +1,108 @@
+use crate::db::{
+    self, DbState, JobTypeModel, ModelRoutingRule, NewModelRoutingRule, ResolvedModel,
+};
+use crate::jobs::JobType;
+use crate::model_config;
+use tauri::State;
+
+/// Tiers a lead score can have
+const TIERS: &[&str] = &["hot", "warm", "nurture", "disqualified"];
+
+fn check_model(model: &str) -> Result<(), String> {
+    if model_config::is_supported_model(model) {
+        Ok(())
+    } else {
+        Err(format!("Unsupported Claude model: {model}"))
+    }
+}
+
+fn check_job_type(job_type: &str) -> Result<(), String> {
+    match JobType::parse(job_type) {
+        Some(_) => Ok(()),
+        None => Err(format!("Unknown job type: {job_type}")),
+    }
+}
+
+// ============================================================================
+// Model Routing Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_job_type_models(state: State<'_, DbState>) -> Result<Vec<JobTypeModel>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_job_type_models(&conn).map_err(|e| e.to_string())
+}
+
+/// Set the model for a job type, or clear it to use the global model
+#[tauri::command]
+pub fn set_job_type_model(
+    state: State<'_, DbState>,
+    job_type: String,
+    model: Option<String>,
+) -> Result<(), String> {
+    check_job_type(&job_type)?;
+    if let Some(model) = &model {
+        check_model(model)?;
+    }
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::set_job_type_model(&conn, &job_type, model.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_model_routing_rules(state: State<'_, DbState>) -> Result<Vec<ModelRoutingRule>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_model_routing_rules(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_model_routing_rule(
+    state: State<'_, DbState>,
+    rule: NewModelRoutingRule,
+) -> Result<i64, String> {
+    check_model(&rule.model)?;
+    if let Some(job_type) = &rule.job_type {
+        check_job_type(job_type)?;
+    }
+    if let Some(tier) = &rule.tier {
+        if !TIERS.contains(&tier.as_str()) {
+            return Err(format!("Unknown tier: {tier}"));
+        }
+    }
+    if rule.job_type.is_none() && rule.tier.is_none() && rule.industry.is_none() {
+        return Err("A routing rule needs at least one condition".to_string());
+    }
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::insert_model_routing_rule(&conn, &rule).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_model_routing_rule_enabled(
+    state: State<'_, DbState>,
+    id: i64,
+    enabled: bool,
+) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::set_model_routing_rule_enabled(&conn, id, enabled).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_model_routing_rule(state: State<'_, DbState>, id: i64) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    if db::delete_model_routing_rule(&conn, id).map_err(|e| e.to_string())? {
+        Ok(())
+    } else {
+        Err("Routing rule not found".to_string())
+    }
+}
+
+/// Model a job of `job_type` on `entity_id` would run on right now
+#[tauri::command]
+pub fn resolve_job_model(
+    state: State<'_, DbState>,
+    job_type: String,
+    entity_id: i64,
+) -> Result<ResolvedModel, String> {
+    check_job_type(&job_type)?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::resolve_job_model(&conn, &job_type, entity_id).map_err(|e| e.to_string())
+}
@@ -0,0 +1,70 @@
+use super::tags::emit_entity_updated;
+use crate::db::{self, DbState, FieldProvenance, FieldSourceType, MergePolicySetting};
+use tauri::{AppHandle, State};
+
+// ============================================================================
+// Field Provenance Commands
+// ============================================================================
+
+/// Where each enrichment field of a lead or person came from
+#[tauri::command]
+pub fn get_field_provenance(
+    state: State<'_, DbState>,
+    entity_type: String,
+    entity_id: i64,
+) -> Result<Vec<FieldProvenance>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_field_provenance(&conn, &entity_type, entity_id).map_err(|e| e.to_string())
+}
+
+/// Edit one enrichment field (e.g. "employees" or "linkedinUrl"). The value is
+/// recorded as user-entered unless `source_type` says otherwise; null clears it.
+#[tauri::command]
+pub fn update_entity_field(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    entity_type: String,
+    entity_id: i64,
+    field: String,
+    value: serde_json::Value,
+    source_type: Option<FieldSourceType>,
+) -> Result<(), String> {
+    let value = db::coerce_entity_field_value(&entity_type, &field, &value)?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::set_entity_field(
+        &conn,
+        &entity_type,
+        entity_id,
+        &field,
+        value,
+        source_type.unwrap_or(FieldSourceType::User),
+    )
+    .map_err(|e| e.to_string())?;
+    emit_entity_updated(&app, &conn, &entity_type, &[entity_id]);
+    Ok(())
+}
+
+// ============================================================================
+// Merge Policy Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_merge_policies(state: State<'_, DbState>) -> Result<Vec<MergePolicySetting>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_merge_policies(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_merge_policy(
+    state: State<'_, DbState>,
+    setting: MergePolicySetting,
+) -> Result<(), String> {
+    if setting
+        .min_confidence
+        .is_some_and(|c| !(0.0..=1.0).contains(&c))
+    {
+        return Err("Minimum confidence must be between 0 and 1".to_string());
+    }
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::set_merge_policy(&conn, &setting).map_err(|e| e.to_string())
+}
@@ -1,10 +1,12 @@
 use crate::db::DbState;
 use crate::events;
 use crate::jobs::recovery::{
-    detect_stale_jobs, detect_stuck_leads, detect_stuck_people, recover_stale_jobs,
-    recover_stuck_entities, StaleJobsResult,
+    self, detect_stale_jobs, detect_stuck_leads, detect_stuck_people, recover_stale_jobs,
+    recover_stuck_entities, HarvestReport, HarvestReportState, StaleJobsResult,
 };
-use tauri::{AppHandle, State};
+use crate::jobs::JobQueue;
+use std::collections::HashSet;
+use tauri::{AppHandle, Manager, State};
 
 /// Get all stuck entities - entities with "in_progress" status but no active job,
 /// and stale jobs that have been running too long
@@ -78,3 +80,24 @@ pub fn recover_all_stuck(app: AppHandle, state: State<'_, DbState>) -> Result<us
 
     Ok(stale_recovered + stuck_recovered)
 }
+
+/// Report of the last scan for orphaned output files (run on startup)
+#[tauri::command]
+pub fn get_harvest_report(state: State<'_, HarvestReportState>) -> Result<HarvestReport, String> {
+    state.0.lock().map(|r| r.clone()).map_err(|e| e.to_string())
+}
+
+/// Scan output directories for files left by jobs that never completed and
+/// import them, skipping jobs that are still running
+#[tauri::command]
+pub async fn harvest_orphaned_outputs(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    queue: State<'_, JobQueue>,
+) -> Result<HarvestReport, String> {
+    let data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let active: HashSet<String> = queue.get_active_jobs().await.into_iter().collect();
+    let report = recovery::harvest_orphaned_outputs(&state.conn, &app, &data_dir, &active)?;
+    recovery::publish_harvest_report(&app, &report);
+    Ok(report)
+}
@@ -0,0 +1,66 @@
+use crate::db::{self, DbState, RawCopyPolicy, RedactionRule};
+use crate::redaction::{self, RawJobText};
+use tauri::State;
+
+// ============================================================================
+// Redaction Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_redaction_rules(state: State<'_, DbState>) -> Result<Vec<RedactionRule>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_redaction_rules(&conn).map_err(|e| e.to_string())
+}
+
+/// Add a custom pattern. A capture group named `keep` is left unredacted.
+#[tauri::command]
+pub fn add_redaction_rule(
+    state: State<'_, DbState>,
+    name: String,
+    pattern: String,
+) -> Result<i64, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Rule name is required".to_string());
+    }
+    redaction::validate_pattern(&pattern).map_err(|e| format!("Invalid pattern: {}", e))?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::insert_redaction_rule(&conn, name, &pattern, false).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_redaction_rule_enabled(
+    state: State<'_, DbState>,
+    id: i64,
+    enabled: bool,
+) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::set_redaction_rule_enabled(&conn, id, enabled).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_redaction_rule(state: State<'_, DbState>, id: i64) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    if db::delete_redaction_rule(&conn, id).map_err(|e| e.to_string())? {
+        Ok(())
+    } else {
+        Err("Built-in rules can only be disabled".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn update_redaction_settings(
+    state: State<'_, DbState>,
+    enabled: bool,
+    raw_copy_policy: RawCopyPolicy,
+) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::update_redaction_settings(&conn, enabled, raw_copy_policy).map_err(|e| e.to_string())
+}
+
+/// Decrypt the locally kept originals of a job's redacted prompt and log lines
+#[tauri::command]
+pub fn get_raw_job_text(state: State<'_, DbState>, job_id: String) -> Result<RawJobText, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    redaction::reveal(&conn, &job_id).map_err(|e| e.to_string())
+}
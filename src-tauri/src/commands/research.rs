@@ -1,6 +1,8 @@
 use crate::db::{self, DbState};
 use crate::events::{emit_lead_updated, emit_person_updated};
-use crate::jobs::{EntityContext, EntityType, JobMetadata, JobQueue, JobType, StreamEvent};
+use crate::jobs::{
+    EntityContext, EntityType, JobMetadata, JobQueue, JobType, QueueMode, QueueStatus, StreamEvent,
+};
 use crate::prompts::get_default_prompt;
 use std::fs;
 use std::path::PathBuf;
@@ -63,7 +65,7 @@ pub async fn start_research(
     emit_lead_updated(&app, lead_id);
 
     // Get prompts (with fallback to defaults)
-    let (company_prompt_content, company_overview) = {
+    let (company_prompt_content, company_overview, custom_fields) = {
         let conn = state.conn.lock().map_err(|e| e.to_string())?;
         let cp = db::get_prompt_by_type(&conn, "company").map_err(|e| e.to_string())?;
         let co = db::get_prompt_by_type(&conn, "company_overview").map_err(|e| e.to_string())?;
@@ -72,7 +74,8 @@ pub async fn start_research(
         let content = cp
             .map(|p| p.content)
             .or_else(|| get_default_prompt("company").map(String::from));
-        (content, co)
+        let fields = db::get_custom_fields(&conn, Some("lead")).map_err(|e| e.to_string())?;
+        (content, co, fields)
     };
 
     let prompt_content = custom_prompt
@@ -99,16 +102,19 @@ pub async fn start_research(
     let profile_path = lead_dir.join("company_profile.md");
     let people_path = lead_dir.join("people.json");
     let enrichment_path = lead_dir.join("enrichment.json");
+    let sources_path = lead_dir.join("sources.json");
 
     // Build prompt with file paths
-    let full_prompt = build_research_prompt(
+    let mut full_prompt = build_research_prompt(
         &prompt_content,
         &lead,
         &profile_path,
         &people_path,
         &enrichment_path,
         company_overview.as_ref().map(|p| p.content.as_str()),
+        &custom_fields,
     );
+    full_prompt.push_str(&sources_section(&sources_path));
 
     // Send initial event (job_id is "pending" as actual job hasn't been created yet)
     let _ = on_event.send(StreamEvent {
@@ -129,6 +135,7 @@ pub async fn start_research(
         primary_output_path: profile_path,
         secondary_output_path: Some(people_path),
         enrichment_output_path: Some(enrichment_path),
+        sources_output_path: Some(sources_path),
     };
 
     // Clone the app handle for the callback
@@ -216,7 +223,7 @@ pub async fn start_person_research(
     emit_person_updated(&app, person_id, person.lead_id);
 
     // Get prompts (with fallback to defaults)
-    let (person_prompt_content, company_overview) = {
+    let (person_prompt_content, company_overview, custom_fields) = {
         let conn = state.conn.lock().map_err(|e| e.to_string())?;
         let pp = db::get_prompt_by_type(&conn, "person").map_err(|e| e.to_string())?;
         let co = db::get_prompt_by_type(&conn, "company_overview").map_err(|e| e.to_string())?;
@@ -225,7 +232,8 @@ pub async fn start_person_research(
         let content = pp
             .map(|p| p.content)
             .or_else(|| get_default_prompt("person").map(String::from));
-        (content, co)
+        let fields = db::get_custom_fields(&conn, Some("person")).map_err(|e| e.to_string())?;
+        (content, co, fields)
     };
 
     let prompt_content = custom_prompt
@@ -250,16 +258,19 @@ pub async fn start_person_research(
 
     let profile_path = person_dir.join("person_profile.md");
     let enrichment_path = person_dir.join("enrichment.json");
+    let sources_path = person_dir.join("sources.json");
 
     // Build prompt with file path
-    let full_prompt = build_person_research_prompt(
+    let mut full_prompt = build_person_research_prompt(
         &prompt_content,
         &person,
         lead.as_ref(),
         &profile_path,
         &enrichment_path,
         company_overview.as_ref().map(|p| p.content.as_str()),
+        &custom_fields,
     );
+    full_prompt.push_str(&sources_section(&sources_path));
 
     let full_name = format!("{} {}", person.first_name, person.last_name);
 
@@ -282,6 +293,7 @@ pub async fn start_person_research(
         primary_output_path: profile_path,
         secondary_output_path: None,
         enrichment_output_path: Some(enrichment_path),
+        sources_output_path: Some(sources_path),
     };
 
     let entity_label = full_name.clone();
@@ -380,6 +392,133 @@ pub async fn kill_job(
     }
 }
 
+/// Cancel every active job, or only those of `job_type` and/or in `job_ids`
+#[tauri::command]
+pub async fn cancel_jobs(
+    queue: State<'_, JobQueue>,
+    app: AppHandle,
+    job_type: Option<String>,
+    job_ids: Option<Vec<String>>,
+) -> Result<usize, String> {
+    Ok(queue
+        .cancel_jobs(&app, job_type.as_deref(), job_ids.as_deref())
+        .await)
+}
+
+#[tauri::command]
+pub async fn get_job_queue_status(queue: State<'_, JobQueue>) -> Result<QueueStatus, String> {
+    Ok(queue.status().await)
+}
+
+/// Set the queue to running, paused or draining
+#[tauri::command]
+pub async fn set_job_queue_mode(
+    queue: State<'_, JobQueue>,
+    app: AppHandle,
+    mode: QueueMode,
+) -> Result<QueueStatus, String> {
+    queue.set_mode(&app, mode).await;
+    Ok(queue.status().await)
+}
+
+/// Quit after stopping the job queue. Running jobs get `wait_secs` to finish
+/// before they are interrupted; 0 interrupts them right away.
+#[tauri::command]
+pub fn quit_app(app: AppHandle, wait_secs: Option<u64>) {
+    crate::jobs::shutdown::request_exit(
+        &app,
+        wait_secs.unwrap_or(crate::jobs::shutdown::SHUTDOWN_WAIT_SECS),
+    );
+}
+
+/// Message sent to the agent when its interrupted session is resumed
+const RESUME_PROMPT: &str =
+    "Your previous run was interrupted by an app shutdown before it finished. \
+Continue where you left off and write every output file the original instructions asked for.";
+
+/// Jobs interrupted by a shutdown that can be resumed
+#[tauri::command]
+pub async fn get_resumable_jobs(state: State<'_, DbState>) -> Result<Vec<db::Job>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_resumable_jobs(&conn).map_err(|e| e.to_string())
+}
+
+/// Queue a new run that continues the agent session of an interrupted job
+#[tauri::command]
+pub async fn resume_job(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    queue: State<'_, JobQueue>,
+    job_id: String,
+    on_event: Channel<StreamEvent>,
+) -> Result<ResearchResult, String> {
+    let job = {
+        let conn = state.conn.lock().map_err(|e| e.to_string())?;
+        db::get_resumable_jobs(&conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|job| job.id == job_id)
+            .ok_or_else(|| "Job is not an interrupted job that can be resumed".to_string())?
+    };
+    let session_id = job
+        .claude_session_id
+        .clone()
+        .ok_or_else(|| "Job has no agent session to resume".to_string())?;
+    let metadata = job
+        .output_path
+        .as_deref()
+        .and_then(|path| JobMetadata::from_job_row(&job.job_type, job.entity_id, path))
+        .ok_or_else(|| "Job has no output path to resume into".to_string())?;
+
+    let entity_type = match metadata.job_type {
+        JobType::CompanyResearch => Some(EntityType::Lead),
+        JobType::PersonResearch => Some(EntityType::Person),
+        _ => None,
+    };
+    if let Some(entity_type) = entity_type {
+        let conn = state.conn.lock().map_err(|e| e.to_string())?;
+        let sql = match entity_type {
+            EntityType::Lead => "UPDATE leads SET research_status = 'in_progress' WHERE id = ?1",
+            EntityType::Person => "UPDATE people SET research_status = 'in_progress' WHERE id = ?1",
+        };
+        conn.execute(sql, rusqlite::params![job.entity_id])
+            .map_err(|e| e.to_string())?;
+        match entity_type {
+            EntityType::Lead => emit_lead_updated(&app, job.entity_id),
+            EntityType::Person => {
+                if let Some(person) =
+                    db::get_person_raw(&conn, job.entity_id).map_err(|e| e.to_string())?
+                {
+                    emit_person_updated(&app, job.entity_id, person.lead_id);
+                }
+            }
+        }
+    }
+    let entity_context = entity_type.map(|entity_type| EntityContext {
+        entity_type,
+        entity_id: job.entity_id,
+        rollback_status: "pending".to_string(),
+    });
+
+    let job_id = queue
+        .resume_job(
+            app.app_handle().clone(),
+            session_id,
+            RESUME_PROMPT.to_string(),
+            job.working_dir,
+            on_event.clone(),
+            metadata,
+            job.entity_label,
+            entity_context,
+        )
+        .await?;
+
+    Ok(ResearchResult {
+        job_id,
+        status: "started".to_string(),
+    })
+}
+
 #[tauri::command]
 pub async fn get_active_jobs(queue: State<'_, JobQueue>) -> Result<Vec<String>, String> {
     Ok(queue.get_active_jobs().await)
@@ -412,10 +551,12 @@ const LEAD_ENRICHMENT_SCHEMA: &str = r#"This file should contain verified compan
   "companyLinkedinUrl": "https://linkedin.com/company/...",
   "city": "San Francisco",
   "state": "California",
-  "country": "United States"
+  "country": "United States",
+  "confidence": { "employees": 0.9, "revenue": 0.6 }
 }
 ```
 
+"confidence" gives your confidence (0.0-1.0) in each field you include.
 IMPORTANT: Only include fields with verified data. Omit fields if uncertain."#;
 
 const PERSON_ENRICHMENT_SCHEMA: &str = r#"This file should contain verified person data in JSON format. Only include fields where you have found reliable data:
@@ -425,13 +566,65 @@ const PERSON_ENRICHMENT_SCHEMA: &str = r#"This file should contain verified pers
   "title": "Senior Vice President of Sales",
   "managementLevel": "VP",
   "linkedinUrl": "https://linkedin.com/in/...",
-  "yearJoined": 2020
+  "yearJoined": 2020,
+  "confidence": { "title": 0.95, "email": 0.7 }
 }
 ```
 
 Valid managementLevel values: C-Level, VP, Director, Manager, IC
+"confidence" gives your confidence (0.0-1.0) in each field you include.
 IMPORTANT: Only include fields with verified data. Omit fields if uncertain."#;
 
+const SOURCES_JSON_SCHEMA: &str = r#"This file should list every source you relied on, as a JSON array:
+```json
+[
+  {
+    "url": "https://example.com/press/funding",
+    "title": "Example raises $40M Series B",
+    "retrievedAt": "2025-01-31",
+    "claim": "Raised a $40M Series B led by Acme Ventures",
+    "fields": ["revenue", "employees"]
+  }
+]
+```
+
+"fields" lists the enrichment data keys (including customFields keys) that the source backs. Every enrichment value should be backed by at least one source. Do not include facts you could not source."#;
+
+fn sources_section(sources_path: &std::path::Path) -> String {
+    format!(
+        "\n# Sources\n\nWrite the sources you cited to: {}\n\n{}\n",
+        sources_path.display(),
+        SOURCES_JSON_SCHEMA
+    )
+}
+
+/// Extra enrichment instructions for research-fillable custom fields
+/// (empty when there are none)
+fn custom_fields_schema(fields: &[db::CustomField]) -> String {
+    let fillable: Vec<&db::CustomField> = fields.iter().filter(|f| f.research_fillable).collect();
+    if fillable.is_empty() {
+        return String::new();
+    }
+
+    let mut schema = String::from(
+        "\nAlso include a \"customFields\" object in the enrichment JSON, keyed by the field keys below. Only include fields with verified data:\n",
+    );
+    for field in fillable {
+        let kind = match field.field_type {
+            db::CustomFieldType::Text => "string".to_string(),
+            db::CustomFieldType::Number => "number".to_string(),
+            db::CustomFieldType::Date => "date string, YYYY-MM-DD".to_string(),
+            db::CustomFieldType::Select => format!("one of: {}", field.options.join(", ")),
+        };
+        schema.push_str(&format!("- {} ({}) - {}", field.key, kind, field.label));
+        if let Some(description) = field.description.as_deref().filter(|d| !d.is_empty()) {
+            schema.push_str(&format!(": {}", description));
+        }
+        schema.push('\n');
+    }
+    schema
+}
+
 fn build_research_prompt(
     prompt: &str,
     lead: &db::Lead,
@@ -439,6 +632,7 @@ fn build_research_prompt(
     people_path: &std::path::Path,
     enrichment_path: &std::path::Path,
     company_overview: Option<&str>,
+    custom_fields: &[db::CustomField],
 ) -> String {
     let mut full_prompt = String::new();
 
@@ -484,6 +678,7 @@ fn build_research_prompt(
         enrichment_path.display(),
         LEAD_ENRICHMENT_SCHEMA
     ));
+    full_prompt.push_str(&custom_fields_schema(custom_fields));
 
     full_prompt
 }
@@ -495,6 +690,7 @@ fn build_person_research_prompt(
     profile_path: &std::path::Path,
     enrichment_path: &std::path::Path,
     company_overview: Option<&str>,
+    custom_fields: &[db::CustomField],
 ) -> String {
     let mut full_prompt = String::new();
 
@@ -536,6 +732,7 @@ fn build_person_research_prompt(
         enrichment_path.display(),
         PERSON_ENRICHMENT_SCHEMA
     ));
+    full_prompt.push_str(&custom_fields_schema(custom_fields));
 
     full_prompt
 }
@@ -595,6 +792,7 @@ pub async fn start_find_leads(
         primary_output_path: leads_path,
         secondary_output_path: None,
         enrichment_output_path: None,
+        sources_output_path: None,
     };
 
     let entity_label = format!(
@@ -764,6 +962,7 @@ pub async fn start_scoring(
         primary_output_path: score_path,
         secondary_output_path: None,
         enrichment_output_path: None,
+        sources_output_path: None,
     };
 
     let entity_label = format!("{} (Scoring)", lead.company_name);
@@ -894,6 +1093,7 @@ pub async fn start_conversation_generation(
         primary_output_path: conversation_path,
         secondary_output_path: None,
         enrichment_output_path: None,
+        sources_output_path: None,
     };
 
     let entity_label = format!("{} (Conversation)", full_name);
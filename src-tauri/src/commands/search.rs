@@ -0,0 +1,61 @@
+use crate::db::{self, DbState, LogSearchJob, LogSearchQuery, LogSearchResult, SearchHit};
+use tauri::State;
+
+// ============================================================================
+// Search Commands
+// ============================================================================
+
+/// Full-text search over company/person names, titles, research profiles,
+/// conversation topics and notes. Returns ranked hits with highlighted snippets.
+#[tauri::command]
+pub fn search(
+    state: State<'_, DbState>,
+    query: String,
+    entity_types: Option<Vec<String>>,
+    limit: Option<i64>,
+) -> Result<Vec<SearchHit>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::search(
+        &conn,
+        &query,
+        entity_types.as_deref(),
+        limit.unwrap_or(50).clamp(1, 500),
+    )
+    .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Job Log Search Commands
+// ============================================================================
+
+/// Log lines across all jobs matching the filters and full-text query,
+/// newest first, each with surrounding lines from the same job, plus how many
+/// jobs in range had their logs compacted out of the search
+#[tauri::command]
+pub fn search_job_logs(
+    state: State<'_, DbState>,
+    query: LogSearchQuery,
+) -> Result<LogSearchResult, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::search_job_logs(&conn, &query).map_err(|e| e.to_string())
+}
+
+/// Finished jobs that never called the given tool
+#[tauri::command]
+pub fn get_jobs_without_tool(
+    state: State<'_, DbState>,
+    tool_name: String,
+    job_types: Option<Vec<String>>,
+    since: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<LogSearchJob>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_jobs_without_tool(
+        &conn,
+        &tool_name,
+        &job_types.unwrap_or_default(),
+        since,
+        limit,
+    )
+    .map_err(|e| e.to_string())
+}
@@ -1,4 +1,8 @@
-use crate::db::{self, DbState, Settings};
+use crate::db::{
+    self, DbState, JobLimitOverrides, JobLimits, NotificationSettings, OrphanProcessPolicy,
+    Settings,
+};
+use crate::jobs::{JobQueue, JobType};
 use tauri::State;
 
 #[tauri::command]
@@ -35,3 +39,89 @@ pub fn update_settings(
     eprintln!("[settings] Successfully updated settings");
     Ok(())
 }
+
+#[tauri::command]
+pub fn update_output_retention(state: State<'_, DbState>, days: i64) -> Result<(), String> {
+    if days < 1 {
+        return Err("Retention must be at least one day".to_string());
+    }
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::update_output_retention_days(&conn, days).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_log_retention(
+    state: State<'_, DbState>,
+    days: i64,
+    storage_cap_mb: i64,
+) -> Result<(), String> {
+    if days < 1 {
+        return Err("Retention must be at least one day".to_string());
+    }
+    if storage_cap_mb < 1 {
+        return Err("Log storage cap must be at least 1 MB".to_string());
+    }
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::update_log_retention(&conn, days, storage_cap_mb).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_orphan_process_policy(
+    state: State<'_, DbState>,
+    policy: OrphanProcessPolicy,
+) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::update_orphan_process_policy(&conn, policy).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_job_limits(
+    state: State<'_, DbState>,
+    queue: State<'_, JobQueue>,
+    limits: JobLimits,
+) -> Result<(), String> {
+    limits.validate()?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    // Overrides of each job type must still hold with the new global limits
+    for overrides in db::get_job_limit_overrides(&conn).map_err(|e| e.to_string())? {
+        overrides.validate(&limits)?;
+    }
+    db::update_job_limits(&conn, &limits).map_err(|e| e.to_string())?;
+    // Other limits are read when each job starts
+    queue.resize(limits.max_concurrent_jobs as usize);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn update_notification_settings(
+    state: State<'_, DbState>,
+    notifications: NotificationSettings,
+) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::update_notification_settings(&conn, &notifications).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_job_limit_overrides(
+    state: State<'_, DbState>,
+) -> Result<Vec<JobLimitOverrides>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_job_limit_overrides(&conn).map_err(|e| e.to_string())
+}
+
+/// Replace the overrides of one job type; passing no values clears them
+#[tauri::command]
+pub fn set_job_limit_overrides(
+    state: State<'_, DbState>,
+    overrides: JobLimitOverrides,
+) -> Result<(), String> {
+    if JobType::parse(&overrides.job_type).is_none() {
+        return Err(format!("Unknown job type: {}", overrides.job_type));
+    }
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let global = db::get_settings(&conn)
+        .map_err(|e| e.to_string())?
+        .job_limits;
+    overrides.validate(&global)?;
+    db::set_job_limit_overrides(&conn, &overrides).map_err(|e| e.to_string())
+}
@@ -0,0 +1,199 @@
+use crate::db::{self, CustomField, CustomFieldValue, DbState, NewCustomField, Tag};
+use crate::events::{emit_lead_updated, emit_person_updated};
+use rusqlite::Connection;
+use tauri::{AppHandle, State};
+
+/// Notify the frontend that a lead or person changed
+pub(super) fn emit_entity_updated(
+    app: &AppHandle,
+    conn: &Connection,
+    entity_type: &str,
+    ids: &[i64],
+) {
+    for &id in ids {
+        if entity_type == "lead" {
+            emit_lead_updated(app, id);
+        } else {
+            let lead_id = db::get_person_raw(conn, id)
+                .ok()
+                .flatten()
+                .and_then(|p| p.lead_id);
+            emit_person_updated(app, id, lead_id);
+        }
+    }
+}
+
+// ============================================================================
+// Tag Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_tags(state: State<'_, DbState>) -> Result<Vec<Tag>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_tags(&conn).map_err(|e| e.to_string())
+}
+
+/// Create a tag; returns the existing tag's id if one has the same name
+#[tauri::command]
+pub fn create_tag(
+    state: State<'_, DbState>,
+    name: String,
+    color: Option<String>,
+) -> Result<i64, String> {
+    if name.trim().is_empty() {
+        return Err("Tag name cannot be empty".to_string());
+    }
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::insert_tag(&conn, &name, color.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_tag(
+    state: State<'_, DbState>,
+    id: i64,
+    name: String,
+    color: Option<String>,
+) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Tag name cannot be empty".to_string());
+    }
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::update_tag(&conn, id, &name, color.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_tag(state: State<'_, DbState>, id: i64) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::delete_tag(&conn, id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_entity_tags(
+    state: State<'_, DbState>,
+    entity_type: String,
+    entity_id: i64,
+) -> Result<Vec<Tag>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_entity_tags(&conn, &entity_type, entity_id).map_err(|e| e.to_string())
+}
+
+/// Replace the tags of one lead or person
+#[tauri::command]
+pub fn set_entity_tags(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    entity_type: String,
+    entity_id: i64,
+    tag_ids: Vec<i64>,
+) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::set_entity_tags(&conn, &entity_type, entity_id, &tag_ids).map_err(|e| e.to_string())?;
+    emit_entity_updated(&app, &conn, &entity_type, &[entity_id]);
+    Ok(())
+}
+
+/// Add or remove one tag on many leads or people (bulk tagging)
+#[tauri::command]
+pub fn tag_entities(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    tag_id: i64,
+    entity_type: String,
+    entity_ids: Vec<i64>,
+    add: bool,
+) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::tag_entities(&conn, tag_id, &entity_type, &entity_ids, add).map_err(|e| e.to_string())?;
+    emit_entity_updated(&app, &conn, &entity_type, &entity_ids);
+    Ok(())
+}
+
+// ============================================================================
+// Custom Field Commands
+// ============================================================================
+
+fn validate_custom_field(data: &NewCustomField) -> Result<(), String> {
+    if data.label.trim().is_empty() {
+        return Err("Field label cannot be empty".to_string());
+    }
+    if data.key.is_empty()
+        || !data
+            .key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err("Field key must use lowercase letters, digits and underscores".to_string());
+    }
+    if data.field_type == db::CustomFieldType::Select && data.options.is_empty() {
+        return Err("Select fields need at least one option".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_custom_fields(
+    state: State<'_, DbState>,
+    entity_type: Option<String>,
+) -> Result<Vec<CustomField>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_custom_fields(&conn, entity_type.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_custom_field(state: State<'_, DbState>, data: NewCustomField) -> Result<i64, String> {
+    validate_custom_field(&data)?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::insert_custom_field(&conn, &data).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_custom_field(
+    state: State<'_, DbState>,
+    id: i64,
+    data: NewCustomField,
+) -> Result<(), String> {
+    validate_custom_field(&data)?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::update_custom_field(&conn, id, &data).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_custom_field(state: State<'_, DbState>, id: i64) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::delete_custom_field(&conn, id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_custom_field_values(
+    state: State<'_, DbState>,
+    entity_type: String,
+    entity_id: i64,
+) -> Result<Vec<CustomFieldValue>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_custom_field_values(&conn, &entity_type, entity_id).map_err(|e| e.to_string())
+}
+
+/// Set a custom field on a lead or person. A null or empty value clears it.
+#[tauri::command]
+pub fn set_custom_field_value(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    field_id: i64,
+    entity_id: i64,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let field = db::get_custom_field(&conn, field_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Custom field not found".to_string())?;
+
+    let is_empty = value.is_null() || value.as_str().is_some_and(|s| s.trim().is_empty());
+    let value = if is_empty {
+        None
+    } else {
+        Some(db::coerce_custom_field_value(&field, &value)?)
+    };
+    db::set_custom_field_value(&conn, field_id, entity_id, value).map_err(|e| e.to_string())?;
+    emit_entity_updated(&app, &conn, &field.entity_type, &[entity_id]);
+    Ok(())
+}
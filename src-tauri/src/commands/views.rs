@@ -0,0 +1,212 @@
+use crate::db::{self, CustomField, DbState, LeadWithScore, NewSavedView, SavedView};
+use crate::events;
+use crate::tray;
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+
+// ============================================================================
+// Saved View Commands
+// ============================================================================
+
+fn saved_views_changed(app: &AppHandle) {
+    events::emit_saved_views_changed(app);
+    tray::refresh_menu(app);
+}
+
+#[tauri::command]
+pub fn get_saved_views(state: State<'_, DbState>) -> Result<Vec<SavedView>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_saved_views(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_saved_view(state: State<'_, DbState>, id: i64) -> Result<Option<SavedView>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_saved_view(&conn, id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_saved_view(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    data: NewSavedView,
+) -> Result<i64, String> {
+    if data.name.trim().is_empty() {
+        return Err("View name cannot be empty".to_string());
+    }
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let id = db::insert_saved_view(&conn, &data).map_err(|e| e.to_string())?;
+    drop(conn);
+    saved_views_changed(&app);
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn update_saved_view(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    id: i64,
+    data: NewSavedView,
+) -> Result<(), String> {
+    if data.name.trim().is_empty() {
+        return Err("View name cannot be empty".to_string());
+    }
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::update_saved_view(&conn, id, &data).map_err(|e| e.to_string())?;
+    drop(conn);
+    saved_views_changed(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_saved_view_pinned(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    id: i64,
+    pinned: bool,
+) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::set_saved_view_pinned(&conn, id, pinned).map_err(|e| e.to_string())?;
+    drop(conn);
+    saved_views_changed(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn reorder_saved_views(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    ids: Vec<i64>,
+) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::reorder_saved_views(&conn, &ids).map_err(|e| e.to_string())?;
+    drop(conn);
+    saved_views_changed(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_saved_view(app: AppHandle, state: State<'_, DbState>, id: i64) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::delete_saved_view(&conn, id).map_err(|e| e.to_string())?;
+    drop(conn);
+    saved_views_changed(&app);
+    Ok(())
+}
+
+/// A view that can be applied: found, and its filter and sort still valid
+fn load_view(state: &State<'_, DbState>, id: i64) -> Result<SavedView, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let view = db::get_saved_view(&conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Saved view not found".to_string())?;
+    match view.error {
+        Some(error) => Err(format!("Saved view \"{}\": {}", view.name, error)),
+        None => Ok(view),
+    }
+}
+
+/// Lead ids currently matching a saved view, in the view's sort order.
+/// Used as the target list for bulk research and scoring.
+#[tauri::command]
+pub fn get_saved_view_lead_ids(state: State<'_, DbState>, id: i64) -> Result<Vec<i64>, String> {
+    let view = load_view(&state, id)?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    db::get_lead_ids(&conn, &view.filter, &view.sort).map_err(|e| e.to_string())
+}
+
+/// Export the leads currently matching a saved view as CSV text
+#[tauri::command]
+pub fn export_saved_view_csv(state: State<'_, DbState>, id: i64) -> Result<String, String> {
+    let view = load_view(&state, id)?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let leads = db::get_filtered_leads_with_scores(&conn, &view.filter, &view.sort)
+        .map_err(|e| e.to_string())?;
+    let extras = CsvExtras {
+        fields: db::get_custom_fields(&conn, Some("lead")).map_err(|e| e.to_string())?,
+        values: db::get_custom_field_values_by_entity(&conn, "lead").map_err(|e| e.to_string())?,
+        tags: db::get_tag_names_by_entity(&conn, "lead").map_err(|e| e.to_string())?,
+    };
+    Ok(leads_to_csv(&leads, &extras))
+}
+
+const CSV_HEADER: &[&str] = &[
+    "id",
+    "company_name",
+    "website",
+    "industry",
+    "employees",
+    "employee_range",
+    "city",
+    "state",
+    "country",
+    "research_status",
+    "user_status",
+    "tier",
+    "total_score",
+    "tags",
+];
+
+/// Tags and custom field values appended to each exported row
+struct CsvExtras {
+    fields: Vec<CustomField>,
+    values: HashMap<i64, HashMap<i64, serde_json::Value>>,
+    tags: HashMap<i64, Vec<String>>,
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn leads_to_csv(leads: &[LeadWithScore], extras: &CsvExtras) -> String {
+    let mut header: Vec<String> = CSV_HEADER.iter().map(|h| h.to_string()).collect();
+    header.extend(extras.fields.iter().map(|f| csv_field(&f.key)));
+    let mut out = header.join(",");
+    out.push('\n');
+
+    for LeadWithScore { lead, score } in leads {
+        let opt = |v: &Option<String>| v.as_deref().map(csv_field).unwrap_or_default();
+        let mut fields = vec![
+            lead.id.to_string(),
+            csv_field(&lead.company_name),
+            opt(&lead.website),
+            opt(&lead.industry),
+            lead.employees.map(|e| e.to_string()).unwrap_or_default(),
+            opt(&lead.employee_range),
+            opt(&lead.city),
+            opt(&lead.state),
+            opt(&lead.country),
+            csv_field(&lead.research_status),
+            csv_field(&lead.user_status),
+            score
+                .as_ref()
+                .map(|s| csv_field(&s.tier))
+                .unwrap_or_default(),
+            score
+                .as_ref()
+                .map(|s| s.total_score.to_string())
+                .unwrap_or_default(),
+            extras
+                .tags
+                .get(&lead.id)
+                .map(|t| csv_field(&t.join("; ")))
+                .unwrap_or_default(),
+        ];
+        let values = extras.values.get(&lead.id);
+        for field in &extras.fields {
+            fields.push(match values.and_then(|v| v.get(&field.id)) {
+                Some(serde_json::Value::String(s)) => csv_field(s),
+                Some(serde_json::Value::Null) | None => String::new(),
+                Some(other) => other.to_string(),
+            });
+        }
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+
+    out
+}
@@ -0,0 +1,436 @@
+//! Change detection for re-researched leads and the daily change digest.
+//!
+//! When company research runs again for a lead that already has a profile,
+//! the new enrichment, people list and profile are compared with what was
+//! stored before and the differences are kept in `lead_changes`.
+
+use super::schema::*;
+use rusqlite::{params, Connection, Result as SqliteResult};
+use std::collections::HashSet;
+
+/// Management levels reported as leadership hires
+const EXECUTIVE_LEVELS: &[&str] = &["c-level", "vp"];
+
+/// Title words that mark an executive when the management level is missing
+const EXECUTIVE_TITLE_WORDS: &[&str] = &[
+    "ceo",
+    "cfo",
+    "cto",
+    "coo",
+    "cmo",
+    "cro",
+    "cio",
+    "vp",
+    "svp",
+    "evp",
+    "chief",
+    "president",
+];
+
+/// Phrases in new profile lines that indicate a funding event
+const FUNDING_PHRASES: &[&str] = &[
+    "raised",
+    "raises",
+    "funding round",
+    "seed round",
+    "series a",
+    "series b",
+    "series c",
+    "series d",
+    "series e",
+    "venture round",
+    "secured $",
+    "secures $",
+];
+
+/// Longest funding line quoted in a highlight
+const MAX_HIGHLIGHT_CHARS: usize = 200;
+
+// ============================================================================
+// Detection
+// ============================================================================
+
+pub fn person_change(p: &Person) -> PersonChange {
+    PersonChange {
+        person_id: Some(p.id),
+        name: format!("{} {}", p.first_name, p.last_name)
+            .trim()
+            .to_string(),
+        title: p.title.clone(),
+        management_level: p.management_level.clone(),
+    }
+}
+
+fn is_executive(p: &PersonChange) -> bool {
+    if let Some(level) = &p.management_level {
+        if EXECUTIVE_LEVELS.contains(&level.to_lowercase().as_str()) {
+            return true;
+        }
+    }
+    p.title.as_deref().is_some_and(|title| {
+        title
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|w| EXECUTIVE_TITLE_WORDS.contains(&w))
+    })
+}
+
+fn push_field_change(
+    changes: &mut Vec<FieldChange>,
+    field: &str,
+    old: Option<String>,
+    new: Option<String>,
+) {
+    let Some(new) = new.filter(|v| !v.trim().is_empty()) else {
+        return;
+    };
+    let same = old
+        .as_deref()
+        .is_some_and(|old| old.trim().eq_ignore_ascii_case(new.trim()));
+    if !same {
+        changes.push(FieldChange {
+            field: field.to_string(),
+            old,
+            new: Some(new),
+        });
+    }
+}
+
+fn format_number(v: f64) -> String {
+    if v.fract() == 0.0 {
+        format!("{}", v as i64)
+    } else {
+        v.to_string()
+    }
+}
+
+/// Enrichment fields whose stored value changed
+fn field_changes(before: &Lead, after: &Lead) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    push_field_change(
+        &mut changes,
+        "employees",
+        before.employees.map(|v| v.to_string()),
+        after.employees.map(|v| v.to_string()),
+    );
+    push_field_change(
+        &mut changes,
+        "employeeRange",
+        before.employee_range.clone(),
+        after.employee_range.clone(),
+    );
+    push_field_change(
+        &mut changes,
+        "revenue",
+        before.revenue.map(format_number),
+        after.revenue.map(format_number),
+    );
+    push_field_change(
+        &mut changes,
+        "revenueRange",
+        before.revenue_range.clone(),
+        after.revenue_range.clone(),
+    );
+    push_field_change(
+        &mut changes,
+        "industry",
+        before.industry.clone(),
+        after.industry.clone(),
+    );
+    push_field_change(
+        &mut changes,
+        "subIndustry",
+        before.sub_industry.clone(),
+        after.sub_industry.clone(),
+    );
+    push_field_change(
+        &mut changes,
+        "city",
+        before.city.clone(),
+        after.city.clone(),
+    );
+    push_field_change(
+        &mut changes,
+        "state",
+        before.state.clone(),
+        after.state.clone(),
+    );
+    push_field_change(
+        &mut changes,
+        "country",
+        before.country.clone(),
+        after.country.clone(),
+    );
+    changes
+}
+
+/// Split a Markdown profile into (heading, normalized body) pairs.
+/// Text before the first heading is reported as "Introduction".
+fn profile_sections(profile: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, Vec<&str>)> = vec![("Introduction".to_string(), Vec::new())];
+    for line in profile.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            sections.push((
+                trimmed.trim_start_matches('#').trim().to_string(),
+                Vec::new(),
+            ));
+        } else if !trimmed.is_empty() {
+            if let Some((_, body)) = sections.last_mut() {
+                body.push(trimmed);
+            }
+        }
+    }
+    sections
+        .into_iter()
+        .filter(|(heading, body)| heading != "Introduction" || !body.is_empty())
+        .map(|(heading, body)| (heading, body.join("\n")))
+        .collect()
+}
+
+fn section_changes(old: &str, new: &str, summary: &mut LeadChangeSummary) {
+    let old_sections = profile_sections(old);
+    let new_sections = profile_sections(new);
+    let find = |sections: &[(String, String)], heading: &str| {
+        sections
+            .iter()
+            .find(|(h, _)| h.eq_ignore_ascii_case(heading))
+            .map(|(_, body)| body.clone())
+    };
+
+    for (heading, body) in &new_sections {
+        match find(&old_sections, heading) {
+            None => summary.sections_added.push(heading.clone()),
+            Some(old_body) if &old_body != body => summary.sections_changed.push(heading.clone()),
+            Some(_) => {}
+        }
+    }
+    for (heading, _) in &old_sections {
+        if find(&new_sections, heading).is_none() {
+            summary.sections_removed.push(heading.clone());
+        }
+    }
+}
+
+/// Lines of the new profile mentioning funding that were not in the old one
+fn funding_lines(old: &str, new: &str) -> Vec<String> {
+    let old_lines: HashSet<&str> = old.lines().map(str::trim).collect();
+    let mut found = Vec::new();
+    for line in new.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || old_lines.contains(line) {
+            continue;
+        }
+        let lower = line.to_lowercase();
+        if FUNDING_PHRASES.iter().any(|p| lower.contains(p)) {
+            let text = line.trim_start_matches(['-', '*', ' ']);
+            let text = match text.char_indices().nth(MAX_HIGHLIGHT_CHARS) {
+                Some((i, _)) => format!("{}…", &text[..i]),
+                None => text.to_string(),
+            };
+            if !found.contains(&text) {
+                found.push(text);
+            }
+        }
+    }
+    found
+}
+
+/// Compare a lead before and after company research was written. `after` is
+/// read back inside the same transaction, so fields the merge policy kept are
+/// not reported as changed. `people_added` / `people_gone` come from matching
+/// the researched people against the lead's existing people.
+pub fn summarize_lead_changes(
+    before: &Lead,
+    after: &Lead,
+    people_added: Vec<PersonChange>,
+    people_gone: Vec<PersonChange>,
+) -> LeadChangeSummary {
+    let mut summary = LeadChangeSummary {
+        field_changes: field_changes(before, after),
+        people_added,
+        people_gone,
+        ..Default::default()
+    };
+
+    let old_profile = before.company_profile.as_deref().unwrap_or("");
+    let profile = after.company_profile.as_deref().unwrap_or("");
+    section_changes(old_profile, profile, &mut summary);
+
+    for p in summary.people_added.iter().filter(|p| is_executive(p)) {
+        summary.highlights.push(match &p.title {
+            Some(title) => format!("New executive: {} ({})", p.name, title),
+            None => format!("New executive: {}", p.name),
+        });
+    }
+    for line in funding_lines(old_profile, profile) {
+        summary.highlights.push(format!("Funding: {}", line));
+    }
+
+    summary
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+pub fn insert_lead_change(
+    conn: &Connection,
+    lead_id: i64,
+    job_id: Option<&str>,
+    summary: &LeadChangeSummary,
+) -> SqliteResult<i64> {
+    let json = serde_json::to_string(summary)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute(
+        "INSERT INTO lead_changes (lead_id, job_id, summary, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![lead_id, job_id, json, chrono::Utc::now().timestamp()],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+const LEAD_CHANGE_SELECT: &str =
+    "SELECT c.id, c.lead_id, COALESCE(l.company_name, ''), c.job_id, c.summary, c.created_at,
+            date(c.created_at, 'unixepoch', 'localtime')
+     FROM lead_changes c LEFT JOIN leads l ON l.id = c.lead_id";
+
+fn lead_change_from_row(row: &rusqlite::Row) -> SqliteResult<(LeadChange, String)> {
+    let summary: String = row.get(4)?;
+    Ok((
+        LeadChange {
+            id: row.get(0)?,
+            lead_id: row.get(1)?,
+            company_name: row.get(2)?,
+            job_id: row.get(3)?,
+            summary: serde_json::from_str(&summary).unwrap_or_default(),
+            created_at: row.get(5)?,
+        },
+        row.get(6)?,
+    ))
+}
+
+/// Changes detected for one lead, newest first
+pub fn get_lead_changes(conn: &Connection, lead_id: i64) -> SqliteResult<Vec<LeadChange>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE c.lead_id = ?1 ORDER BY c.created_at DESC, c.id DESC",
+        LEAD_CHANGE_SELECT
+    ))?;
+    let rows = stmt.query_map([lead_id], lead_change_from_row)?;
+    rows.map(|r| r.map(|(change, _)| change)).collect()
+}
+
+fn group_by_date(rows: Vec<(LeadChange, String)>) -> Vec<ChangeDigest> {
+    let mut digests: Vec<ChangeDigest> = Vec::new();
+    for (change, date) in rows {
+        match digests.last_mut() {
+            Some(digest) if digest.date == date => digest.changes.push(change),
+            _ => digests.push(ChangeDigest {
+                date,
+                changes: vec![change],
+            }),
+        }
+    }
+    digests
+}
+
+/// Daily digests for the last `days` days, newest day first
+pub fn get_change_digests(conn: &Connection, days: i64) -> SqliteResult<Vec<ChangeDigest>> {
+    let since = chrono::Utc::now().timestamp() - days.max(1) * 86_400;
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE c.created_at >= ?1 ORDER BY c.created_at DESC, c.id DESC",
+        LEAD_CHANGE_SELECT
+    ))?;
+    let rows = stmt
+        .query_map([since], lead_change_from_row)?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(group_by_date(rows))
+}
+
+/// Digest for one local date (YYYY-MM-DD)
+pub fn get_change_digest(conn: &Connection, date: &str) -> SqliteResult<ChangeDigest> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE date(c.created_at, 'unixepoch', 'localtime') = ?1
+         ORDER BY c.created_at DESC, c.id DESC",
+        LEAD_CHANGE_SELECT
+    ))?;
+    let changes = stmt
+        .query_map([date], lead_change_from_row)?
+        .map(|r| r.map(|(change, _)| change))
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(ChangeDigest {
+        date: date.to_string(),
+        changes,
+    })
+}
+
+// ============================================================================
+// Markdown Export
+// ============================================================================
+
+fn person_line(p: &PersonChange) -> String {
+    match &p.title {
+        Some(title) => format!("- {} — {}\n", p.name, title),
+        None => format!("- {}\n", p.name),
+    }
+}
+
+pub fn digest_to_markdown(digest: &ChangeDigest) -> String {
+    let mut out = format!("# Account changes — {}\n\n", digest.date);
+    if digest.changes.is_empty() {
+        out.push_str("No changes detected.\n");
+        return out;
+    }
+
+    let highlights: Vec<String> = digest
+        .changes
+        .iter()
+        .flat_map(|c| {
+            c.summary
+                .highlights
+                .iter()
+                .map(move |h| format!("- **{}**: {}\n", c.company_name, h))
+        })
+        .collect();
+    if !highlights.is_empty() {
+        out.push_str("## Highlights\n\n");
+        out.extend(highlights);
+        out.push('\n');
+    }
+
+    for change in &digest.changes {
+        let s = &change.summary;
+        out.push_str(&format!("## {}\n\n", change.company_name));
+        if !s.field_changes.is_empty() {
+            out.push_str("**Updated fields**\n\n");
+            for f in &s.field_changes {
+                out.push_str(&format!(
+                    "- {}: {} → {}\n",
+                    f.field,
+                    f.old.as_deref().unwrap_or("—"),
+                    f.new.as_deref().unwrap_or("—")
+                ));
+            }
+            out.push('\n');
+        }
+        if !s.people_added.is_empty() {
+            out.push_str("**New people**\n\n");
+            out.extend(s.people_added.iter().map(person_line));
+            out.push('\n');
+        }
+        if !s.people_gone.is_empty() {
+            out.push_str("**No longer listed**\n\n");
+            out.extend(s.people_gone.iter().map(person_line));
+            out.push('\n');
+        }
+        for (label, sections) in [
+            ("Changed sections", &s.sections_changed),
+            ("New sections", &s.sections_added),
+            ("Removed sections", &s.sections_removed),
+        ] {
+            if !sections.is_empty() {
+                out.push_str(&format!("**{}:** {}\n\n", label, sections.join(", ")));
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,696 @@
+//! Duplicate detection and merging for leads and people.
+//!
+//! Candidates are matched on normalized website domain, LinkedIn URL, email
+//! and fuzzy (Jaro-Winkler) name similarity. Merging folds duplicates into a
+//! primary record and writes a snapshot of each merged row to `merge_history`.
+
+use super::queries::{delete_leads, delete_people, get_lead, get_person_raw};
+use super::schema::*;
+use rusqlite::{params, Connection, Result as SqliteResult};
+use std::collections::HashMap;
+
+/// Minimum Jaro-Winkler similarity of normalized names to count as a match
+const NAME_SIMILARITY_THRESHOLD: f64 = 0.94;
+
+/// Legal-form suffixes ignored when comparing company names
+const COMPANY_SUFFIXES: &[&str] = &[
+    "inc",
+    "incorporated",
+    "corp",
+    "corporation",
+    "co",
+    "company",
+    "llc",
+    "ltd",
+    "limited",
+    "plc",
+    "gmbh",
+    "ag",
+    "sa",
+    "sas",
+    "bv",
+    "nv",
+    "oy",
+    "ab",
+    "as",
+    "srl",
+    "spa",
+    "pty",
+    "group",
+    "holdings",
+];
+
+// ============================================================================
+// Normalization
+// ============================================================================
+
+/// Strip scheme, `www.`, port, path and query: "https://www.Acme.com/about" -> "acme.com"
+pub fn normalize_domain(url: &str) -> Option<String> {
+    let lower = url.trim().to_lowercase();
+    let without_scheme = lower.split("://").last().unwrap_or(&lower);
+    let host = without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or("")
+        .split(':')
+        .next()
+        .unwrap_or("");
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    (host.contains('.') && !host.is_empty()).then(|| host.to_string())
+}
+
+/// "https://www.linkedin.com/in/Jane-Doe/?trk=x" -> "linkedin.com/in/jane-doe"
+pub fn normalize_linkedin_url(url: &str) -> Option<String> {
+    let lower = url.trim().to_lowercase();
+    let without_scheme = lower.split("://").last().unwrap_or(&lower);
+    let without_query = without_scheme.split(['?', '#']).next().unwrap_or("");
+    let trimmed = without_query.trim_end_matches('/');
+    let trimmed = trimmed.strip_prefix("www.").unwrap_or(trimmed);
+    // Country subdomains (de.linkedin.com) point at the same profile
+    let path = trimmed.split_once("linkedin.com/").map(|(_, path)| path)?;
+    (!path.is_empty()).then(|| format!("linkedin.com/{}", path))
+}
+
+pub fn normalize_email(email: &str) -> Option<String> {
+    let email = email.trim().to_lowercase();
+    email.contains('@').then_some(email)
+}
+
+/// Lowercase alphanumeric words with legal-form suffixes removed
+pub fn normalize_company_name(name: &str) -> String {
+    let lower = name.to_lowercase().replace('&', " and ");
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let mut end = words.len();
+    while end > 1 && COMPANY_SUFFIXES.contains(&words[end - 1]) {
+        end -= 1;
+    }
+    words[..end].join(" ")
+}
+
+pub fn normalize_person_name(first_name: &str, last_name: &str) -> String {
+    format!("{} {}", first_name, last_name)
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn names_match(a: &str, b: &str) -> bool {
+    !a.is_empty()
+        && !b.is_empty()
+        && (a == b || strsim::jaro_winkler(a, b) >= NAME_SIMILARITY_THRESHOLD)
+}
+
+// ============================================================================
+// Detection
+// ============================================================================
+
+/// Candidate row reduced to its match keys
+struct MatchKeys {
+    id: i64,
+    label: String,
+    name: String,
+    /// Exact keys, e.g. "domain:acme.com" or "email:jane@acme.com"
+    keys: Vec<String>,
+    /// Fuzzy names are only compared within the same block (lead, or first
+    /// name letters) to keep detection well below O(n^2) on large tables
+    block: String,
+}
+
+/// Minimal union-find over candidate indexes
+struct Groups {
+    parent: Vec<usize>,
+    reasons: HashMap<usize, Vec<String>>,
+}
+
+impl Groups {
+    fn new(n: usize) -> Self {
+        Groups {
+            parent: (0..n).collect(),
+            reasons: HashMap::new(),
+        }
+    }
+
+    fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        self.parent[i] = root;
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize, reason: String) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb {
+            self.parent[rb] = ra;
+            let moved = self.reasons.remove(&rb).unwrap_or_default();
+            self.reasons.entry(ra).or_default().extend(moved);
+        }
+        let reasons = self.reasons.entry(ra).or_default();
+        if !reasons.contains(&reason) {
+            reasons.push(reason);
+        }
+    }
+}
+
+fn group_candidates(entity_type: &str, candidates: Vec<MatchKeys>) -> Vec<DuplicateGroup> {
+    let mut groups = Groups::new(candidates.len());
+
+    let mut by_key: HashMap<&str, usize> = HashMap::new();
+    for (i, c) in candidates.iter().enumerate() {
+        for key in &c.keys {
+            match by_key.get(key.as_str()) {
+                Some(&first) => {
+                    let reason = key.split(':').next().unwrap_or("key").to_string();
+                    groups.union(first, i, reason);
+                }
+                None => {
+                    by_key.insert(key.as_str(), i);
+                }
+            }
+        }
+    }
+
+    let mut by_block: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, c) in candidates.iter().enumerate() {
+        if !c.block.is_empty() {
+            by_block.entry(c.block.as_str()).or_default().push(i);
+        }
+    }
+    for members in by_block.values() {
+        for (n, &a) in members.iter().enumerate() {
+            for &b in &members[n + 1..] {
+                if names_match(&candidates[a].name, &candidates[b].name) {
+                    groups.union(a, b, "name".to_string());
+                }
+            }
+        }
+    }
+
+    let mut members_by_root: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..candidates.len() {
+        let root = groups.find(i);
+        members_by_root.entry(root).or_default().push(i);
+    }
+
+    let mut result: Vec<DuplicateGroup> = members_by_root
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(root, members)| DuplicateGroup {
+            entity_type: entity_type.to_string(),
+            members: members
+                .iter()
+                .map(|&i| DuplicateCandidate {
+                    id: candidates[i].id,
+                    label: candidates[i].label.clone(),
+                })
+                .collect(),
+            reasons: groups.reasons.remove(&root).unwrap_or_default(),
+        })
+        .collect();
+    // Oldest id first in each group (the suggested primary), groups by first id
+    for group in &mut result {
+        group.members.sort_by_key(|m| m.id);
+    }
+    result.sort_by_key(|g| g.members[0].id);
+    result
+}
+
+/// Groups of leads that look like the same company
+pub fn find_duplicate_leads(conn: &Connection) -> SqliteResult<Vec<DuplicateGroup>> {
+    let mut stmt =
+        conn.prepare("SELECT id, company_name, website, company_linkedin_url FROM leads")?;
+    let candidates = stmt
+        .query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            let company_name: String = row.get(1)?;
+            let website: Option<String> = row.get(2)?;
+            let linkedin: Option<String> = row.get(3)?;
+
+            let name = normalize_company_name(&company_name);
+            let mut keys = Vec::new();
+            if let Some(domain) = website.as_deref().and_then(normalize_domain) {
+                keys.push(format!("domain:{}", domain));
+            }
+            if let Some(url) = linkedin.as_deref().and_then(normalize_linkedin_url) {
+                keys.push(format!("linkedin:{}", url));
+            }
+            Ok(MatchKeys {
+                id,
+                label: company_name,
+                block: name.chars().take(2).collect(),
+                name,
+                keys,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(group_candidates("lead", candidates))
+}
+
+/// Groups of people that look like the same person. Email and LinkedIn match
+/// across companies; fuzzy names only match within the same lead.
+pub fn find_duplicate_people(conn: &Connection) -> SqliteResult<Vec<DuplicateGroup>> {
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.first_name, p.last_name, p.email, p.linkedin_url, p.lead_id, l.company_name
+         FROM people p LEFT JOIN leads l ON l.id = p.lead_id",
+    )?;
+    let candidates = stmt
+        .query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            let first_name: String = row.get(1)?;
+            let last_name: String = row.get(2)?;
+            let email: Option<String> = row.get(3)?;
+            let linkedin: Option<String> = row.get(4)?;
+            let lead_id: Option<i64> = row.get(5)?;
+            let company: Option<String> = row.get(6)?;
+
+            let mut keys = Vec::new();
+            if let Some(email) = email.as_deref().and_then(normalize_email) {
+                keys.push(format!("email:{}", email));
+            }
+            if let Some(url) = linkedin.as_deref().and_then(normalize_linkedin_url) {
+                keys.push(format!("linkedin:{}", url));
+            }
+            let label = match company {
+                Some(company) => format!("{} {} ({})", first_name, last_name, company),
+                None => format!("{} {}", first_name, last_name),
+            };
+            Ok(MatchKeys {
+                id,
+                label,
+                name: normalize_person_name(&first_name, &last_name),
+                keys,
+                block: lead_id.map(|l| l.to_string()).unwrap_or_default(),
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(group_candidates("person", candidates))
+}
+
+/// Existing lead matching a new company by domain or fuzzy name, if any.
+/// Used to avoid inserting duplicates. Like duplicate detection, fuzzy names
+/// are only compared within the block sharing the first two letters, which
+/// SQL narrows down first so a lookup doesn't scan every lead.
+pub fn find_matching_lead(
+    conn: &Connection,
+    company_name: &str,
+    website: Option<&str>,
+) -> SqliteResult<Option<i64>> {
+    if let Some(domain) = website.and_then(normalize_domain) {
+        let mut stmt = conn.prepare(
+            "SELECT id, website FROM leads WHERE website LIKE '%' || ?1 || '%' ORDER BY id",
+        )?;
+        let mut rows = stmt.query([&domain])?;
+        while let Some(row) = rows.next()? {
+            let existing: String = row.get(1)?;
+            if normalize_domain(&existing).as_deref() == Some(domain.as_str()) {
+                return Ok(Some(row.get(0)?));
+            }
+        }
+    }
+
+    let name = normalize_company_name(company_name);
+    let block: String = name.chars().take(2).collect();
+    if block.is_empty() {
+        return Ok(None);
+    }
+    // Normalized names are alphanumeric, so the block holds no LIKE wildcards
+    // and the prefix search can use the NOCASE company name index
+    let mut stmt = conn.prepare(
+        "SELECT id, company_name FROM leads
+         WHERE company_name LIKE ?1 || '%' ORDER BY id",
+    )?;
+    let mut rows = stmt.query([&block])?;
+    while let Some(row) = rows.next()? {
+        let existing_name: String = row.get(1)?;
+        if names_match(&name, &normalize_company_name(&existing_name)) {
+            return Ok(Some(row.get(0)?));
+        }
+    }
+    Ok(None)
+}
+
+/// Existing person at a lead, matched by email or LinkedIn URL first, then by
+/// exact normalized name. A name match is skipped when both people have
+/// different emails; fuzzy look-alikes are left to duplicate review.
+pub fn find_matching_person(
+    conn: &Connection,
+    lead_id: i64,
+    first_name: &str,
+    last_name: &str,
+    email: Option<&str>,
+    linkedin_url: Option<&str>,
+) -> SqliteResult<Option<i64>> {
+    let email = email.and_then(normalize_email);
+    let linkedin = linkedin_url.and_then(normalize_linkedin_url);
+    let name = normalize_person_name(first_name, last_name);
+
+    let mut stmt = conn.prepare(
+        "SELECT id, first_name, last_name, email, linkedin_url FROM people
+         WHERE lead_id = ?1 ORDER BY id",
+    )?;
+    let mut rows = stmt.query([lead_id])?;
+    let mut name_match = None;
+
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let existing_email = row
+            .get::<_, Option<String>>(3)?
+            .as_deref()
+            .and_then(normalize_email);
+        let existing_linkedin = row
+            .get::<_, Option<String>>(4)?
+            .as_deref()
+            .and_then(normalize_linkedin_url);
+        if email.is_some() && existing_email == email {
+            return Ok(Some(id));
+        }
+        if linkedin.is_some() && existing_linkedin == linkedin {
+            return Ok(Some(id));
+        }
+        let emails_differ = email.is_some() && existing_email.is_some();
+        if name_match.is_none() && !name.is_empty() && !emails_differ {
+            let existing_name =
+                normalize_person_name(&row.get::<_, String>(1)?, &row.get::<_, String>(2)?);
+            if existing_name == name {
+                name_match = Some(id);
+            }
+        }
+    }
+    Ok(name_match)
+}
+
+// ============================================================================
+// Merging
+// ============================================================================
+
+fn record_merge<T: serde::Serialize>(
+    conn: &Connection,
+    entity_type: &str,
+    primary_id: i64,
+    merged_id: i64,
+    snapshot: &T,
+) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO merge_history (entity_type, primary_id, merged_id, snapshot, merged_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            entity_type,
+            primary_id,
+            merged_id,
+            serde_json::to_string(snapshot).unwrap_or_default(),
+            chrono::Utc::now().timestamp()
+        ],
+    )?;
+    Ok(())
+}
+
+/// Move tags, custom field values, field provenance, research history, sources and jobs
+/// from one entity to another. The primary keeps its own values where both are set.
+fn move_entity_extras(
+    conn: &Connection,
+    entity_type: &str,
+    job_types: &[&str],
+    from_id: i64,
+    to_id: i64,
+) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO entity_tags (tag_id, entity_type, entity_id)
+         SELECT tag_id, entity_type, ?3 FROM entity_tags WHERE entity_type = ?1 AND entity_id = ?2",
+        params![entity_type, from_id, to_id],
+    )?;
+    conn.execute(
+        "DELETE FROM entity_tags WHERE entity_type = ?1 AND entity_id = ?2",
+        params![entity_type, from_id],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO custom_field_values (field_id, entity_id, value, updated_at)
+         SELECT v.field_id, ?3, v.value, v.updated_at FROM custom_field_values v
+         JOIN custom_fields f ON f.id = v.field_id
+         WHERE f.entity_type = ?1 AND v.entity_id = ?2",
+        params![entity_type, from_id, to_id],
+    )?;
+    // Append the duplicate's research history after the primary's versions
+    let offset: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM research_runs WHERE entity_type = ?1 AND entity_id = ?2",
+        params![entity_type, to_id],
+        |row| row.get(0),
+    )?;
+    conn.execute(
+        "UPDATE research_runs SET entity_id = ?1, version = version + ?2
+         WHERE entity_type = ?3 AND entity_id = ?4",
+        params![to_id, offset, entity_type, from_id],
+    )?;
+    conn.execute(
+        "UPDATE sources SET entity_id = ?1 WHERE entity_type = ?2 AND entity_id = ?3",
+        params![to_id, entity_type, from_id],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO field_provenance
+            (entity_type, entity_id, field, source_type, job_id, confidence, updated_at)
+         SELECT entity_type, ?3, field, source_type, job_id, confidence, updated_at
+         FROM field_provenance WHERE entity_type = ?1 AND entity_id = ?2",
+        params![entity_type, from_id, to_id],
+    )?;
+    for job_type in job_types {
+        conn.execute(
+            "UPDATE jobs SET entity_id = ?1 WHERE entity_id = ?2 AND job_type = ?3",
+            params![to_id, from_id, job_type],
+        )?;
+    }
+    Ok(())
+}
+
+/// Join two optional notes, keeping both when they differ
+fn merge_text(primary: Option<&str>, other: Option<&str>) -> Option<String> {
+    match (
+        primary.filter(|s| !s.trim().is_empty()),
+        other.filter(|s| !s.trim().is_empty()),
+    ) {
+        (Some(a), Some(b)) if a.trim() != b.trim() => Some(format!("{}\n\n---\n\n{}", a, b)),
+        (Some(a), _) => Some(a.to_string()),
+        (None, b) => b.map(String::from),
+    }
+}
+
+/// Fold `duplicate_ids` into `primary_id`: empty fields are filled from the
+/// duplicates, notes are concatenated, and people, scores, jobs, tags and
+/// custom field values are re-pointed before the duplicates are deleted.
+pub fn merge_leads(
+    conn: &Connection,
+    primary_id: i64,
+    duplicate_ids: &[i64],
+) -> SqliteResult<MergeResult> {
+    let tx = conn.unchecked_transaction()?;
+    get_lead(&tx, primary_id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+    let mut merged_ids = Vec::new();
+
+    for &dup_id in duplicate_ids.iter().filter(|&&id| id != primary_id) {
+        let Some(dup) = get_lead(&tx, dup_id)? else {
+            continue;
+        };
+        let primary = get_lead(&tx, primary_id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        record_merge(&tx, "lead", primary_id, dup_id, &dup)?;
+
+        // Prefer whichever record has finished research
+        let take_research =
+            primary.research_status != "completed" && dup.research_status == "completed";
+        tx.execute(
+            "UPDATE leads SET
+                website = COALESCE(website, ?1),
+                industry = COALESCE(industry, ?2),
+                sub_industry = COALESCE(sub_industry, ?3),
+                employees = COALESCE(employees, ?4),
+                employee_range = COALESCE(employee_range, ?5),
+                revenue = COALESCE(revenue, ?6),
+                revenue_range = COALESCE(revenue_range, ?7),
+                company_linkedin_url = COALESCE(company_linkedin_url, ?8),
+                city = COALESCE(city, ?9),
+                state = COALESCE(state, ?10),
+                country = COALESCE(country, ?11),
+                notes = ?12,
+                created_at = MIN(created_at, ?13)
+             WHERE id = ?14",
+            params![
+                dup.website,
+                dup.industry,
+                dup.sub_industry,
+                dup.employees,
+                dup.employee_range,
+                dup.revenue,
+                dup.revenue_range,
+                dup.company_linkedin_url,
+                dup.city,
+                dup.state,
+                dup.country,
+                merge_text(primary.notes.as_deref(), dup.notes.as_deref()),
+                dup.created_at,
+                primary_id
+            ],
+        )?;
+        if take_research || primary.company_profile.is_none() {
+            tx.execute(
+                "UPDATE leads SET company_profile = ?1, research_status = ?2, researched_at = ?3
+                 WHERE id = ?4 AND (?5 OR company_profile IS NULL) AND ?1 IS NOT NULL",
+                params![
+                    dup.company_profile,
+                    dup.research_status,
+                    dup.researched_at,
+                    primary_id,
+                    take_research
+                ],
+            )?;
+        }
+        // Keep the furthest-along pipeline status chosen by the user
+        if primary.user_status == "new" && dup.user_status != "new" {
+            tx.execute(
+                "UPDATE leads SET user_status = ?1 WHERE id = ?2",
+                params![dup.user_status, primary_id],
+            )?;
+        }
+
+        tx.execute(
+            "UPDATE people SET lead_id = ?1 WHERE lead_id = ?2",
+            params![primary_id, dup_id],
+        )?;
+        tx.execute(
+            "UPDATE lead_scores SET lead_id = ?1 WHERE lead_id = ?2",
+            params![primary_id, dup_id],
+        )?;
+        tx.execute(
+            "UPDATE lead_changes SET lead_id = ?1 WHERE lead_id = ?2",
+            params![primary_id, dup_id],
+        )?;
+        move_entity_extras(
+            &tx,
+            "lead",
+            &["company_research", "scoring"],
+            dup_id,
+            primary_id,
+        )?;
+        delete_leads(&tx, &[dup_id])?;
+        merged_ids.push(dup_id);
+    }
+
+    tx.commit()?;
+    Ok(MergeResult {
+        primary_id,
+        merged_ids,
+    })
+}
+
+/// Fold `duplicate_ids` into `primary_id` (see `merge_leads`)
+pub fn merge_people(
+    conn: &Connection,
+    primary_id: i64,
+    duplicate_ids: &[i64],
+) -> SqliteResult<MergeResult> {
+    let tx = conn.unchecked_transaction()?;
+    get_person_raw(&tx, primary_id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+    let mut merged_ids = Vec::new();
+
+    for &dup_id in duplicate_ids.iter().filter(|&&id| id != primary_id) {
+        let Some(dup) = get_person_raw(&tx, dup_id)? else {
+            continue;
+        };
+        let primary =
+            get_person_raw(&tx, primary_id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        record_merge(&tx, "person", primary_id, dup_id, &dup)?;
+
+        let take_research =
+            primary.research_status != "completed" && dup.research_status == "completed";
+        tx.execute(
+            "UPDATE people SET
+                lead_id = COALESCE(lead_id, ?1),
+                email = COALESCE(email, ?2),
+                title = COALESCE(title, ?3),
+                management_level = COALESCE(management_level, ?4),
+                linkedin_url = COALESCE(linkedin_url, ?5),
+                year_joined = COALESCE(year_joined, ?6),
+                conversation_topics = COALESCE(conversation_topics, ?7),
+                conversation_generated_at = COALESCE(conversation_generated_at, ?8),
+                created_at = MIN(created_at, ?9)
+             WHERE id = ?10",
+            params![
+                dup.lead_id,
+                dup.email,
+                dup.title,
+                dup.management_level,
+                dup.linkedin_url,
+                dup.year_joined,
+                dup.conversation_topics,
+                dup.conversation_generated_at,
+                dup.created_at,
+                primary_id
+            ],
+        )?;
+        if take_research || primary.person_profile.is_none() {
+            tx.execute(
+                "UPDATE people SET person_profile = ?1, research_status = ?2, researched_at = ?3
+                 WHERE id = ?4 AND (?5 OR person_profile IS NULL) AND ?1 IS NOT NULL",
+                params![
+                    dup.person_profile,
+                    dup.research_status,
+                    dup.researched_at,
+                    primary_id,
+                    take_research
+                ],
+            )?;
+        }
+        if primary.user_status == "new" && dup.user_status != "new" {
+            tx.execute(
+                "UPDATE people SET user_status = ?1 WHERE id = ?2",
+                params![dup.user_status, primary_id],
+            )?;
+        }
+
+        move_entity_extras(
+            &tx,
+            "person",
+            &["person_research", "conversation"],
+            dup_id,
+            primary_id,
+        )?;
+        delete_people(&tx, &[dup_id])?;
+        merged_ids.push(dup_id);
+    }
+
+    tx.commit()?;
+    Ok(MergeResult {
+        primary_id,
+        merged_ids,
+    })
+}
+
+/// Merges recorded for an entity, newest first
+pub fn get_merge_history(
+    conn: &Connection,
+    entity_type: &str,
+    primary_id: i64,
+) -> SqliteResult<Vec<MergeRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, entity_type, primary_id, merged_id, snapshot, merged_at
+         FROM merge_history WHERE entity_type = ?1 AND primary_id = ?2
+         ORDER BY merged_at DESC, id DESC",
+    )?;
+    let rows = stmt.query_map(params![entity_type, primary_id], |row| {
+        let snapshot: String = row.get(4)?;
+        Ok(MergeRecord {
+            id: row.get(0)?,
+            entity_type: row.get(1)?,
+            primary_id: row.get(2)?,
+            merged_id: row.get(3)?,
+            snapshot: serde_json::from_str(&snapshot).unwrap_or(serde_json::Value::Null),
+            merged_at: row.get(5)?,
+        })
+    })?;
+
+    rows.collect()
+}
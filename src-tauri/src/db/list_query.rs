@@ -0,0 +1,221 @@
+//! Helpers for building filtered, sorted and cursor-paginated list queries.
+//!
+//! Pagination is keyset-based: the cursor holds the sort key values of the last
+//! row on the previous page, so fetching page N costs the same as page 1.
+
+use super::schema::{Page, SortDirection};
+use rusqlite::types::Value;
+use rusqlite::{Connection, Error as SqliteError, OptionalExtension, Result as SqliteResult};
+
+/// Default and maximum page sizes for list queries
+pub const DEFAULT_PAGE_SIZE: i64 = 100;
+pub const MAX_PAGE_SIZE: i64 = 1000;
+
+/// A single ORDER BY term. Expressions must never evaluate to NULL
+/// (wrap nullable columns in COALESCE) so keyset comparisons stay total.
+pub struct SortKey {
+    pub expr: &'static str,
+    pub direction: SortDirection,
+}
+
+impl SortDirection {
+    fn sql(self) -> &'static str {
+        match self {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        }
+    }
+
+    fn after_op(self) -> &'static str {
+        match self {
+            SortDirection::Asc => ">",
+            SortDirection::Desc => "<",
+        }
+    }
+}
+
+/// Accumulates WHERE conditions and their positional parameters
+#[derive(Default)]
+pub struct Conditions {
+    clauses: Vec<String>,
+    pub params: Vec<Value>,
+}
+
+impl Conditions {
+    pub fn push(&mut self, clause: impl Into<String>, params: impl IntoIterator<Item = Value>) {
+        self.clauses.push(clause.into());
+        self.params.extend(params);
+    }
+
+    /// `expr IN (...)`, skipped when `values` is empty
+    pub fn push_in(&mut self, expr: &str, values: &[String]) {
+        if values.is_empty() {
+            return;
+        }
+        let placeholders = vec!["?"; values.len()].join(", ");
+        self.push(
+            format!("{} IN ({})", expr, placeholders),
+            values.iter().cloned().map(Value::Text),
+        );
+    }
+
+    pub fn sql(&self) -> String {
+        if self.clauses.is_empty() {
+            "1 = 1".to_string()
+        } else {
+            self.clauses.join(" AND ")
+        }
+    }
+}
+
+pub fn page_size(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+pub fn order_by(keys: &[SortKey]) -> String {
+    keys.iter()
+        .map(|k| format!("{} {}", k.expr, k.direction.sql()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Sort key expressions as extra select columns, used to build the next cursor
+pub fn sort_key_columns(keys: &[SortKey]) -> String {
+    keys.iter().map(|k| k.expr).collect::<Vec<_>>().join(", ")
+}
+
+/// Condition selecting rows strictly after the cursor position:
+/// `(k1 > v1) OR (k1 = v1 AND k2 > v2) OR ...`
+pub fn push_after_cursor(conds: &mut Conditions, keys: &[SortKey], cursor: &[Value]) {
+    let mut alternatives = Vec::with_capacity(keys.len());
+    let mut params = Vec::new();
+
+    for (i, key) in keys.iter().enumerate() {
+        let mut parts: Vec<String> = keys[..i]
+            .iter()
+            .map(|k| format!("{} = ?", k.expr))
+            .collect();
+        params.extend(cursor[..i].iter().cloned());
+        parts.push(format!("{} {} ?", key.expr, key.direction.after_op()));
+        params.push(cursor[i].clone());
+        alternatives.push(format!("({})", parts.join(" AND ")));
+    }
+
+    conds.push(format!("({})", alternatives.join(" OR ")), params);
+}
+
+pub fn encode_cursor(values: &[Value]) -> String {
+    let json: Vec<serde_json::Value> = values
+        .iter()
+        .map(|v| match v {
+            Value::Null => serde_json::Value::Null,
+            Value::Integer(i) => serde_json::json!(i),
+            Value::Real(f) => serde_json::json!(f),
+            Value::Text(s) => serde_json::json!(s),
+            Value::Blob(_) => serde_json::Value::Null,
+        })
+        .collect();
+    serde_json::Value::Array(json).to_string()
+}
+
+pub fn decode_cursor(cursor: &str, expected_len: usize) -> SqliteResult<Vec<Value>> {
+    let invalid = || SqliteError::ToSqlConversionFailure("Invalid pagination cursor".into());
+
+    let json: Vec<serde_json::Value> = serde_json::from_str(cursor).map_err(|_| invalid())?;
+    if json.len() != expected_len {
+        return Err(invalid());
+    }
+
+    json.into_iter()
+        .map(|v| match v {
+            serde_json::Value::Null => Ok(Value::Null),
+            serde_json::Value::String(s) => Ok(Value::Text(s)),
+            serde_json::Value::Number(n) => n
+                .as_i64()
+                .map(Value::Integer)
+                .or_else(|| n.as_f64().map(Value::Real))
+                .ok_or_else(invalid),
+            _ => Err(invalid()),
+        })
+        .collect()
+}
+
+/// Read the sort key columns selected after the first `offset` columns
+pub fn sort_key_values(
+    row: &rusqlite::Row,
+    offset: usize,
+    keys: &[SortKey],
+) -> SqliteResult<Vec<Value>> {
+    (0..keys.len()).map(|i| row.get(offset + i)).collect()
+}
+
+/// Build a page from `limit + 1` fetched rows paired with their sort key values
+pub fn into_page<T>(mut rows: Vec<(T, Vec<Value>)>, limit: i64, total: i64) -> Page<T> {
+    let next_cursor = if rows.len() as i64 > limit {
+        rows.truncate(limit as usize);
+        rows.last().map(|(_, values)| encode_cursor(values))
+    } else {
+        None
+    };
+
+    Page {
+        items: rows.into_iter().map(|(item, _)| item).collect(),
+        next_cursor,
+        total,
+    }
+}
+
+pub fn count(conn: &Connection, from: &str, conds: &Conditions) -> SqliteResult<i64> {
+    conn.query_row(
+        &format!("SELECT COUNT(*) {} WHERE {}", from, conds.sql()),
+        rusqlite::params_from_iter(&conds.params),
+        |row| row.get(0),
+    )
+}
+
+/// Previous/next ids, 1-based position and total for `current_id` within the
+/// filtered, sorted list. Position is 0 when the row is outside the filter.
+pub fn adjacent(
+    conn: &Connection,
+    from: &str,
+    id_expr: &str,
+    keys: &[SortKey],
+    conds: &Conditions,
+    current_id: i64,
+) -> SqliteResult<(Option<i64>, Option<i64>, usize, usize)> {
+    let order = order_by(keys);
+    let sql = format!(
+        "WITH ordered AS (
+            SELECT {id} AS id,
+                   ROW_NUMBER() OVER (ORDER BY {order}) AS position,
+                   LAG({id}) OVER (ORDER BY {order}) AS prev_id,
+                   LEAD({id}) OVER (ORDER BY {order}) AS next_id,
+                   COUNT(*) OVER () AS total
+            {from}
+            WHERE {where_sql}
+         )
+         SELECT prev_id, next_id, position, total FROM ordered WHERE id = ?",
+        id = id_expr,
+        order = order,
+        from = from,
+        where_sql = conds.sql(),
+    );
+    let mut params = conds.params.clone();
+    params.push(Value::Integer(current_id));
+
+    let found = conn
+        .query_row(&sql, rusqlite::params_from_iter(params), |row| {
+            Ok((
+                row.get::<_, Option<i64>>(0)?,
+                row.get::<_, Option<i64>>(1)?,
+                row.get::<_, i64>(2)? as usize,
+                row.get::<_, i64>(3)? as usize,
+            ))
+        })
+        .optional()?;
+
+    match found {
+        Some(result) => Ok(result),
+        None => Ok((None, None, 0, count(conn, from, conds)? as usize)),
+    }
+}
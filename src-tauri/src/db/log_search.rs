@@ -0,0 +1,374 @@
+//! Search and filtering across the logs of all jobs.
+//!
+//! Log lines are matched through the `job_logs_fts` index and the job they
+//! belong to, and come back newest first with a few lines of context from the
+//! same job. Job-level queries answer questions like "which runs never called
+//! WebSearch" from the typed events and the coarse tool name on each line.
+//!
+//! Only lines still in `job_logs` are indexed. Logs compacted by retention are
+//! not searched; each search reports how many jobs in range that left out.
+
+use super::list_query::{page_size, Conditions};
+use super::queries::build_fts_query;
+use super::schema::*;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, Result as SqliteResult};
+
+/// Most context lines returned on each side of a match
+const MAX_CONTEXT_LINES: i64 = 20;
+
+fn job_log_from_row(row: &rusqlite::Row) -> SqliteResult<JobLog> {
+    Ok(JobLog {
+        id: row.get(0)?,
+        job_id: row.get(1)?,
+        log_type: row.get(2)?,
+        content: row.get(3)?,
+        tool_name: row.get(4)?,
+        timestamp: row.get(5)?,
+        sequence: row.get(6)?,
+        source: row
+            .get::<_, Option<String>>(7)?
+            .unwrap_or_else(|| "stdout".to_string()),
+    })
+}
+
+/// Lines of a job with a sequence in `[from, to]`
+fn job_logs_between(
+    conn: &Connection,
+    job_id: &str,
+    from: i64,
+    to: i64,
+) -> SqliteResult<Vec<JobLog>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, job_id, log_type, content, tool_name, timestamp, sequence, source
+         FROM job_logs WHERE job_id = ?1 AND sequence BETWEEN ?2 AND ?3
+         ORDER BY sequence ASC",
+    )?;
+    let rows = stmt.query_map(params![job_id, from, to], job_log_from_row)?;
+    rows.collect()
+}
+
+/// Jobs matching the job-level filters whose logs were compacted
+fn count_compacted_jobs(conn: &Connection, query: &LogSearchQuery) -> SqliteResult<i64> {
+    let mut conditions = Conditions::default();
+    conditions.push_in("j.job_type", &query.job_types);
+    conditions.push_in("j.id", &query.job_ids);
+    if let Some(entity_id) = query.entity_id {
+        conditions.push("j.entity_id = ?", [Value::Integer(entity_id)]);
+    }
+    // Job times are in seconds, log timestamps in milliseconds
+    if let Some(from) = query.from {
+        conditions.push(
+            "COALESCE(j.completed_at, j.created_at) * 1000 >= ?",
+            [Value::Integer(from)],
+        );
+    }
+    if let Some(to) = query.to {
+        conditions.push("j.created_at * 1000 <= ?", [Value::Integer(to)]);
+    }
+    let sql = format!(
+        "SELECT COUNT(*) FROM job_log_archives a JOIN jobs j ON j.id = a.job_id WHERE {}",
+        conditions.sql()
+    );
+    conn.query_row(&sql, params_from_iter(conditions.params.iter()), |row| {
+        row.get(0)
+    })
+}
+
+/// Log lines matching the query, newest first
+pub fn search_job_logs(conn: &Connection, query: &LogSearchQuery) -> SqliteResult<LogSearchResult> {
+    let compacted_jobs = count_compacted_jobs(conn, query)?;
+    let mut conditions = Conditions::default();
+    let fts_query = query.text.as_deref().and_then(build_fts_query);
+    if query.text.as_deref().is_some_and(|t| !t.trim().is_empty()) && fts_query.is_none() {
+        return Ok(LogSearchResult {
+            matches: Vec::new(),
+            compacted_jobs,
+        });
+    }
+    if let Some(fts_query) = &fts_query {
+        conditions.push(
+            "l.id IN (SELECT rowid FROM job_logs_fts WHERE job_logs_fts MATCH ?)",
+            [Value::Text(fts_query.clone())],
+        );
+    }
+    conditions.push_in("j.job_type", &query.job_types);
+    conditions.push_in("l.job_id", &query.job_ids);
+    conditions.push_in("l.tool_name", &query.tool_names);
+    conditions.push_in("l.source", &query.sources);
+    conditions.push_in("l.log_type", &query.log_types);
+    if let Some(entity_id) = query.entity_id {
+        conditions.push("j.entity_id = ?", [Value::Integer(entity_id)]);
+    }
+    if let Some(from) = query.from {
+        conditions.push("l.timestamp >= ?", [Value::Integer(from)]);
+    }
+    if let Some(to) = query.to {
+        conditions.push("l.timestamp <= ?", [Value::Integer(to)]);
+    }
+    if let Some(before_id) = query.before_id {
+        conditions.push("l.id < ?", [Value::Integer(before_id)]);
+    }
+
+    let sql = format!(
+        "SELECT l.id, l.job_id, l.log_type, l.content, l.tool_name, l.timestamp, l.sequence, l.source,
+                j.job_type, j.entity_id, j.entity_label
+         FROM job_logs l
+         JOIN jobs j ON j.id = l.job_id
+         WHERE {}
+         ORDER BY l.id DESC
+         LIMIT {}",
+        conditions.sql(),
+        page_size(query.limit)
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(conditions.params.iter()), |row| {
+        Ok((
+            job_log_from_row(row)?,
+            row.get::<_, String>(8)?,
+            row.get::<_, i64>(9)?,
+            row.get::<_, String>(10)?,
+        ))
+    })?;
+    let hits = rows.collect::<SqliteResult<Vec<_>>>()?;
+
+    let context = query.context_lines.unwrap_or(0).clamp(0, MAX_CONTEXT_LINES);
+    let mut snippet_stmt = conn.prepare(
+        "SELECT snippet(job_logs_fts, 0, '<mark>', '</mark>', '…', 24)
+         FROM job_logs_fts WHERE job_logs_fts MATCH ?1 AND rowid = ?2",
+    )?;
+    let matches = hits
+        .into_iter()
+        .map(|(log, job_type, entity_id, entity_label)| {
+            let snippet = match &fts_query {
+                Some(q) => Some(snippet_stmt.query_row(params![q, log.id], |row| row.get(0))?),
+                None => None,
+            };
+            let (before, after) = if context > 0 {
+                (
+                    job_logs_between(conn, &log.job_id, log.sequence - context, log.sequence - 1)?,
+                    job_logs_between(conn, &log.job_id, log.sequence + 1, log.sequence + context)?,
+                )
+            } else {
+                (Vec::new(), Vec::new())
+            };
+            Ok(LogSearchMatch {
+                log,
+                job_type,
+                entity_id,
+                entity_label,
+                snippet,
+                before,
+                after,
+            })
+        })
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(LogSearchResult {
+        matches,
+        compacted_jobs,
+    })
+}
+
+/// Finished jobs that never called `tool_name`, newest first.
+/// `since` is a job creation time in seconds.
+pub fn get_jobs_without_tool(
+    conn: &Connection,
+    tool_name: &str,
+    job_types: &[String],
+    since: Option<i64>,
+    limit: Option<i64>,
+) -> SqliteResult<Vec<LogSearchJob>> {
+    let mut conditions = Conditions::default();
+    conditions.push("j.status NOT IN ('queued', 'running')", []);
+    conditions.push(
+        "NOT EXISTS (SELECT 1 FROM job_events e
+                     WHERE e.job_id = j.id AND e.kind = 'tool_use' AND e.tool_name = ?)",
+        [Value::Text(tool_name.to_string())],
+    );
+    conditions.push(
+        "NOT EXISTS (SELECT 1 FROM job_logs l WHERE l.job_id = j.id AND l.tool_name = ?)",
+        [Value::Text(tool_name.to_string())],
+    );
+    conditions.push_in("j.job_type", job_types);
+    if let Some(since) = since {
+        conditions.push("j.created_at >= ?", [Value::Integer(since)]);
+    }
+
+    let sql = format!(
+        "SELECT j.id, j.job_type, j.entity_id, j.entity_label, j.status, j.created_at
+         FROM jobs j
+         WHERE {}
+         ORDER BY j.created_at DESC
+         LIMIT {}",
+        conditions.sql(),
+        page_size(limit)
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(conditions.params.iter()), |row| {
+        Ok(LogSearchJob {
+            job_id: row.get(0)?,
+            job_type: row.get(1)?,
+            entity_id: row.get(2)?,
+            entity_label: row.get(3)?,
+            status: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_jobs_without_tool, search_job_logs};
+    use crate::db::init_job_log_index;
+    use crate::db::test_support::{add_job, memory_db};
+    use crate::db::{
+        insert_job_logs_batch_full, store_compacted_logs, BatchLogEntry, LogSearchQuery,
+    };
+
+    fn add_log(conn: &rusqlite::Connection, job_id: &str, content: &str) {
+        insert_job_logs_batch_full(
+            conn,
+            &[BatchLogEntry {
+                job_id: job_id.to_string(),
+                log_type: "assistant".to_string(),
+                content: content.to_string(),
+                tool_name: None,
+                sequence: 0,
+                source: "stdout".to_string(),
+            }],
+        )
+        .unwrap();
+    }
+
+    fn search(conn: &rusqlite::Connection, text: &str) -> (usize, i64) {
+        let query = LogSearchQuery {
+            text: Some(text.to_string()),
+            ..Default::default()
+        };
+        let result = search_job_logs(conn, &query).unwrap();
+        (result.matches.len(), result.compacted_jobs)
+    }
+
+    #[test]
+    fn job_logs_are_searchable_with_context() {
+        let conn = memory_db();
+        for (id, job_type, lines) in [
+            (
+                "job-1",
+                "company",
+                vec![
+                    ("assistant", Some("WebSearch"), "searching acme"),
+                    (
+                        "tool_result",
+                        None,
+                        "HTTP 429 Too Many Requests from example.com",
+                    ),
+                    ("assistant", None, "retrying later"),
+                ],
+            ),
+            (
+                "job-2",
+                "person",
+                vec![(
+                    "assistant",
+                    Some("WebFetch"),
+                    "fetched example.com about page",
+                )],
+            ),
+        ] {
+            add_job(&conn, id, job_type, 1, "completed");
+            let logs: Vec<BatchLogEntry> = lines
+                .into_iter()
+                .enumerate()
+                .map(|(sequence, (log_type, tool_name, content))| BatchLogEntry {
+                    job_id: id.to_string(),
+                    log_type: log_type.to_string(),
+                    content: content.to_string(),
+                    tool_name: tool_name.map(String::from),
+                    sequence: sequence as i64,
+                    source: "stdout".to_string(),
+                })
+                .collect();
+            insert_job_logs_batch_full(&conn, &logs).unwrap();
+        }
+
+        let matches = search_job_logs(
+            &conn,
+            &LogSearchQuery {
+                text: Some("429 example.com".to_string()),
+                context_lines: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .matches;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].log.job_id, "job-1");
+        assert!(matches[0]
+            .snippet
+            .as_deref()
+            .unwrap()
+            .contains("<mark>429</mark>"));
+        assert_eq!(matches[0].before[0].content, "searching acme");
+        assert_eq!(matches[0].after[0].content, "retrying later");
+
+        let by_type = search_job_logs(
+            &conn,
+            &LogSearchQuery {
+                text: Some("example.com".to_string()),
+                job_types: vec!["person".to_string()],
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .matches;
+        assert_eq!(by_type.len(), 1);
+        assert_eq!(by_type[0].log.job_id, "job-2");
+
+        let without = get_jobs_without_tool(&conn, "WebSearch", &[], None, None).unwrap();
+        assert_eq!(
+            without
+                .iter()
+                .map(|j| j.job_id.as_str())
+                .collect::<Vec<_>>(),
+            ["job-2"]
+        );
+    }
+
+    #[test]
+    fn compacted_jobs_are_reported_instead_of_searched() {
+        let conn = memory_db();
+        add_job(&conn, "live", "company", 1, "completed");
+        add_job(&conn, "old", "company", 2, "completed");
+        add_log(&conn, "live", "rate limited by example.com");
+        store_compacted_logs(&conn, "old", 1, 30, b"gz").unwrap();
+
+        assert_eq!(search(&conn, "example.com"), (1, 1));
+    }
+
+    #[test]
+    fn log_index_is_rebuilt_only_when_its_version_changes() {
+        let conn = memory_db();
+        add_job(&conn, "job-1", "company", 1, "completed");
+        add_log(&conn, "job-1", "rate limited by example.com");
+        // Drop the line from the index only, as a stale index would
+        conn.execute(
+            "INSERT INTO job_logs_fts (job_logs_fts, rowid, content)
+             SELECT 'delete', id, content FROM job_logs",
+            [],
+        )
+        .unwrap();
+
+        init_job_log_index(&conn).unwrap();
+        assert_eq!(search(&conn, "example.com"), (0, 0));
+
+        conn.execute(
+            "UPDATE index_versions SET version = 0 WHERE name = 'job_logs_fts'",
+            [],
+        )
+        .unwrap();
+        init_job_log_index(&conn).unwrap();
+        assert_eq!(search(&conn, "example.com"), (1, 0));
+    }
+}
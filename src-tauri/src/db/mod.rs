@@ -1,11 +1,24 @@
+mod changes;
+mod dedup;
+mod list_query;
+mod log_search;
+mod model_routing;
+mod provenance;
 pub mod queries;
 pub mod schema;
 pub mod seed;
+#[cfg(test)]
+pub mod test_support;
 
-use rusqlite::{Connection, Result as SqliteResult};
+use rusqlite::{Connection, OptionalExtension, Result as SqliteResult};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+pub use changes::*;
+pub use dedup::*;
+pub use log_search::*;
+pub use model_routing::*;
+pub use provenance::*;
 pub use queries::*;
 pub use schema::*;
 
@@ -24,6 +37,10 @@ impl DbState {
 
         let conn = Connection::open(&db_path)?;
 
+        // Free pages are reclaimed in small steps by scheduled maintenance.
+        // Takes effect on new databases; older ones switch on their next VACUUM.
+        conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")?;
+
         // Enable WAL mode for better concurrency
         conn.pragma_update(None, "journal_mode", "WAL")?;
 
@@ -148,7 +165,10 @@ fn init_schema(conn: &Connection) -> SqliteResult<()> {
             stderr_truncated INTEGER DEFAULT 0,
             total_stdout_bytes INTEGER DEFAULT 0,
             total_stderr_bytes INTEGER DEFAULT 0,
-            completion_state TEXT DEFAULT NULL
+            completion_state TEXT DEFAULT NULL,
+            output_archive_path TEXT,
+            progress TEXT,
+            output_archived_at INTEGER
         );
 
         -- Job logs table for persisting stream output
@@ -168,14 +188,245 @@ fn init_schema(conn: &Connection) -> SqliteResult<()> {
         CREATE INDEX IF NOT EXISTS idx_job_logs_job_id ON job_logs(job_id);
         CREATE INDEX IF NOT EXISTS idx_job_logs_sequence ON job_logs(job_id, sequence);
 
+        -- Logs of jobs past the retention period, gzip-compressed as one JSON
+        -- document per job. compressed is NULL once only the summary is kept.
+        CREATE TABLE IF NOT EXISTS job_log_archives (
+            job_id TEXT PRIMARY KEY REFERENCES jobs(id) ON DELETE CASCADE,
+            line_count INTEGER NOT NULL,
+            raw_bytes INTEGER NOT NULL,
+            compressed BLOB,
+            compacted_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS job_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id TEXT NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+            sequence INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            tool_use_id TEXT,
+            tool_name TEXT,
+            content TEXT,
+            input TEXT,
+            is_error INTEGER NOT NULL DEFAULT 0,
+            timestamp INTEGER NOT NULL,
+            duration_ms INTEGER
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_job_events_job ON job_events(job_id, sequence);
+        CREATE INDEX IF NOT EXISTS idx_job_events_tool_use ON job_events(job_id, tool_use_id);
+
+        -- User-defined tags, attached to leads and people
+        CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            color TEXT,
+            created_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS entity_tags (
+            tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+            entity_type TEXT NOT NULL,
+            entity_id INTEGER NOT NULL,
+            PRIMARY KEY (tag_id, entity_type, entity_id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_entity_tags_entity ON entity_tags(entity_type, entity_id);
+
+        -- Typed custom fields per entity type, values stored one row per entity
+        CREATE TABLE IF NOT EXISTS custom_fields (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entity_type TEXT NOT NULL,
+            key TEXT NOT NULL,
+            label TEXT NOT NULL,
+            field_type TEXT NOT NULL,
+            options TEXT NOT NULL DEFAULT '[]',
+            research_fillable INTEGER NOT NULL DEFAULT 0,
+            description TEXT,
+            position INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            UNIQUE (entity_type, key)
+        );
+
+        CREATE TABLE IF NOT EXISTS custom_field_values (
+            field_id INTEGER NOT NULL REFERENCES custom_fields(id) ON DELETE CASCADE,
+            entity_id INTEGER NOT NULL,
+            value NOT NULL,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (field_id, entity_id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_custom_field_values_entity ON custom_field_values(entity_id);
+
+        -- Every version of company/person research profiles
+        CREATE TABLE IF NOT EXISTS research_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entity_type TEXT NOT NULL,
+            entity_id INTEGER NOT NULL,
+            version INTEGER NOT NULL,
+            job_id TEXT,
+            model TEXT,
+            prompt_version TEXT,
+            restored_from INTEGER,
+            profile TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            UNIQUE (entity_type, entity_id, version)
+        );
+
+        -- Sources cited by research runs, and the enrichment fields each backs
+        CREATE TABLE IF NOT EXISTS sources (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entity_type TEXT NOT NULL,
+            entity_id INTEGER NOT NULL,
+            research_run_id INTEGER,
+            job_id TEXT,
+            url TEXT NOT NULL,
+            title TEXT,
+            retrieved_at TEXT,
+            claim TEXT,
+            created_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_sources_entity ON sources(entity_type, entity_id);
+
+        CREATE TABLE IF NOT EXISTS source_fields (
+            source_id INTEGER NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+            field TEXT NOT NULL,
+            PRIMARY KEY (source_id, field)
+        );
+
+        -- Where each enrichment field value came from
+        CREATE TABLE IF NOT EXISTS field_provenance (
+            entity_type TEXT NOT NULL,
+            entity_id INTEGER NOT NULL,
+            field TEXT NOT NULL,
+            source_type TEXT NOT NULL,
+            job_id TEXT,
+            confidence REAL,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (entity_type, entity_id, field)
+        );
+
+        -- Per entity type rule for research overwriting existing values
+        CREATE TABLE IF NOT EXISTS merge_policies (
+            entity_type TEXT PRIMARY KEY,
+            policy TEXT NOT NULL,
+            min_confidence REAL
+        );
+
+        -- Changes detected when company research is re-run
+        CREATE TABLE IF NOT EXISTS lead_changes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            lead_id INTEGER NOT NULL,
+            job_id TEXT,
+            summary TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_lead_changes_lead ON lead_changes(lead_id);
+        CREATE INDEX IF NOT EXISTS idx_lead_changes_created ON lead_changes(created_at);
+
+        -- Merged duplicates, with a snapshot of each merged row
+        CREATE TABLE IF NOT EXISTS merge_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entity_type TEXT NOT NULL,
+            primary_id INTEGER NOT NULL,
+            merged_id INTEGER NOT NULL,
+            snapshot TEXT NOT NULL,
+            merged_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_merge_history_primary ON merge_history(entity_type, primary_id);
+
+        -- Saved lead views (named filter + sort, stored as JSON)
+        CREATE TABLE IF NOT EXISTS saved_views (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            filter TEXT NOT NULL,
+            sort TEXT NOT NULL DEFAULT '[]',
+            pinned INTEGER NOT NULL DEFAULT 0,
+            position INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
         -- App settings table (single row)
         CREATE TABLE IF NOT EXISTS settings (
             id INTEGER PRIMARY KEY CHECK (id = 1),
             model TEXT NOT NULL DEFAULT 'claude-sonnet-5',
             use_chrome INTEGER NOT NULL DEFAULT 0,
+            updated_at INTEGER NOT NULL,
+            output_retention_days INTEGER NOT NULL DEFAULT 30,
+            orphan_process_policy TEXT NOT NULL DEFAULT 'adopt',
+            log_retention_days INTEGER NOT NULL DEFAULT 14,
+            log_storage_cap_mb INTEGER NOT NULL DEFAULT 512,
+            redaction_enabled INTEGER NOT NULL DEFAULT 1,
+            raw_copy_policy TEXT NOT NULL DEFAULT 'discard',
+            max_concurrent_jobs INTEGER NOT NULL DEFAULT 5,
+            job_timeout_secs INTEGER NOT NULL DEFAULT 600,
+            queue_timeout_secs INTEGER NOT NULL DEFAULT 30,
+            graceful_shutdown_secs INTEGER NOT NULL DEFAULT 2,
+            stream_drain_timeout_secs INTEGER NOT NULL DEFAULT 5,
+            max_accumulated_output_mb INTEGER NOT NULL DEFAULT 10,
+            stale_job_threshold_secs INTEGER NOT NULL DEFAULT 600,
+            notify_job_completed INTEGER NOT NULL DEFAULT 1,
+            notify_job_failed INTEGER NOT NULL DEFAULT 1,
+            notify_hot_lead INTEGER NOT NULL DEFAULT 1
+        );
+
+        -- Model per job type, replacing settings.model for those jobs
+        CREATE TABLE IF NOT EXISTS job_type_models (
+            job_type TEXT PRIMARY KEY,
+            model TEXT NOT NULL,
             updated_at INTEGER NOT NULL
         );
 
+        -- Rules picking a model from the job type and the lead being worked on
+        CREATE TABLE IF NOT EXISTS model_routing_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_type TEXT,
+            tier TEXT,
+            industry TEXT,
+            model TEXT NOT NULL,
+            priority INTEGER NOT NULL DEFAULT 0,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            created_at INTEGER NOT NULL
+        );
+
+        -- Per job type overrides of the job limits in settings; NULL keeps the global value
+        CREATE TABLE IF NOT EXISTS job_type_limits (
+            job_type TEXT PRIMARY KEY,
+            job_timeout_secs INTEGER,
+            queue_timeout_secs INTEGER,
+            graceful_shutdown_secs INTEGER,
+            stream_drain_timeout_secs INTEGER,
+            max_accumulated_output_mb INTEGER,
+            stale_job_threshold_secs INTEGER,
+            updated_at INTEGER NOT NULL
+        );
+
+        -- Patterns masked in persisted prompts and logs
+        CREATE TABLE IF NOT EXISTS redaction_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            pattern TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            builtin INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL
+        );
+
+        -- Encrypted originals of redacted prompts and log lines
+        CREATE TABLE IF NOT EXISTS raw_copies (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id TEXT NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+            kind TEXT NOT NULL,
+            sequence INTEGER NOT NULL DEFAULT 0,
+            nonce BLOB NOT NULL,
+            ciphertext BLOB NOT NULL,
+            created_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_raw_copies_job ON raw_copies(job_id, kind, sequence);
+
         -- Insert default settings if not exists
         INSERT OR IGNORE INTO settings (id, model, use_chrome, updated_at)
         VALUES (1, 'claude-sonnet-5', 0, strftime('%s', 'now') * 1000);
@@ -199,6 +450,184 @@ fn init_schema(conn: &Connection) -> SqliteResult<()> {
     // Run migrations for new columns
     run_migrations(conn)?;
 
+    // Full-text index must be created after migrations, since the people
+    // migration recreates the table (which drops its triggers)
+    init_search_index(conn)?;
+    init_job_log_index(conn)?;
+
+    // Seed research history with the profiles that predate it
+    conn.execute_batch(
+        r#"
+        INSERT INTO research_runs (entity_type, entity_id, version, profile, created_at)
+        SELECT 'lead', id, 1, company_profile, COALESCE(researched_at, created_at)
+        FROM leads
+        WHERE company_profile IS NOT NULL
+          AND NOT EXISTS (SELECT 1 FROM research_runs r WHERE r.entity_type = 'lead' AND r.entity_id = leads.id);
+
+        INSERT INTO research_runs (entity_type, entity_id, version, profile, created_at)
+        SELECT 'person', id, 1, person_profile, COALESCE(researched_at, created_at)
+        FROM people
+        WHERE person_profile IS NOT NULL
+          AND NOT EXISTS (SELECT 1 FROM research_runs r WHERE r.entity_type = 'person' AND r.entity_id = people.id);
+        "#,
+    )?;
+
+    // List filter/sort indexes, also after migrations for the same reason
+    conn.execute_batch(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_leads_company_name ON leads(company_name);
+        CREATE INDEX IF NOT EXISTS idx_leads_company_name_nocase ON leads(company_name COLLATE NOCASE);
+        CREATE INDEX IF NOT EXISTS idx_leads_industry ON leads(industry);
+        CREATE INDEX IF NOT EXISTS idx_leads_country ON leads(country);
+        CREATE INDEX IF NOT EXISTS idx_leads_research_status ON leads(research_status);
+        CREATE INDEX IF NOT EXISTS idx_leads_user_status ON leads(user_status);
+        CREATE INDEX IF NOT EXISTS idx_leads_created_at ON leads(created_at);
+        CREATE INDEX IF NOT EXISTS idx_lead_scores_tier ON lead_scores(tier);
+        CREATE INDEX IF NOT EXISTS idx_lead_scores_total ON lead_scores(total_score);
+        CREATE INDEX IF NOT EXISTS idx_people_name ON people(last_name, first_name);
+        CREATE INDEX IF NOT EXISTS idx_people_research_status ON people(research_status);
+        CREATE INDEX IF NOT EXISTS idx_people_user_status ON people(user_status);
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// Create the FTS5 search index over leads and people, kept in sync via triggers.
+/// The index is rebuilt from the content tables when it is first created.
+fn init_search_index(conn: &Connection) -> SqliteResult<()> {
+    let index_exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'leads_fts')",
+        [],
+        |row| row.get(0),
+    )?;
+
+    conn.execute_batch(
+        r#"
+        CREATE VIRTUAL TABLE IF NOT EXISTS leads_fts USING fts5(
+            company_name, industry, company_profile, notes,
+            content = 'leads', content_rowid = 'id'
+        );
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS people_fts USING fts5(
+            first_name, last_name, title, person_profile, conversation_topics,
+            content = 'people', content_rowid = 'id'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS leads_fts_ai AFTER INSERT ON leads BEGIN
+            INSERT INTO leads_fts (rowid, company_name, industry, company_profile, notes)
+            VALUES (new.id, new.company_name, new.industry, new.company_profile, new.notes);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS leads_fts_ad AFTER DELETE ON leads BEGIN
+            INSERT INTO leads_fts (leads_fts, rowid, company_name, industry, company_profile, notes)
+            VALUES ('delete', old.id, old.company_name, old.industry, old.company_profile, old.notes);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS leads_fts_au
+        AFTER UPDATE OF company_name, industry, company_profile, notes ON leads BEGIN
+            INSERT INTO leads_fts (leads_fts, rowid, company_name, industry, company_profile, notes)
+            VALUES ('delete', old.id, old.company_name, old.industry, old.company_profile, old.notes);
+            INSERT INTO leads_fts (rowid, company_name, industry, company_profile, notes)
+            VALUES (new.id, new.company_name, new.industry, new.company_profile, new.notes);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS people_fts_ai AFTER INSERT ON people BEGIN
+            INSERT INTO people_fts (rowid, first_name, last_name, title, person_profile, conversation_topics)
+            VALUES (new.id, new.first_name, new.last_name, new.title, new.person_profile, new.conversation_topics);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS people_fts_ad AFTER DELETE ON people BEGIN
+            INSERT INTO people_fts (people_fts, rowid, first_name, last_name, title, person_profile, conversation_topics)
+            VALUES ('delete', old.id, old.first_name, old.last_name, old.title, old.person_profile, old.conversation_topics);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS people_fts_au
+        AFTER UPDATE OF first_name, last_name, title, person_profile, conversation_topics ON people BEGIN
+            INSERT INTO people_fts (people_fts, rowid, first_name, last_name, title, person_profile, conversation_topics)
+            VALUES ('delete', old.id, old.first_name, old.last_name, old.title, old.person_profile, old.conversation_topics);
+            INSERT INTO people_fts (rowid, first_name, last_name, title, person_profile, conversation_topics)
+            VALUES (new.id, new.first_name, new.last_name, new.title, new.person_profile, new.conversation_topics);
+        END;
+        "#,
+    )?;
+
+    if !index_exists {
+        eprintln!("[db] Building full-text search index");
+        conn.execute_batch(
+            "INSERT INTO leads_fts (leads_fts) VALUES ('rebuild');
+             INSERT INTO people_fts (people_fts) VALUES ('rebuild');",
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Version of the job log index definition. Bump it when the FTS table or its
+/// triggers change so existing databases drop and rebuild the index once.
+const JOB_LOG_INDEX_VERSION: i64 = 1;
+
+/// Create the FTS5 index over job log content. Logs are only ever inserted and
+/// deleted, so no update trigger is needed. The index is only rebuilt from
+/// `job_logs` when it is missing or its definition changed.
+fn init_job_log_index(conn: &Connection) -> SqliteResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS index_versions (
+            name TEXT PRIMARY KEY,
+            version INTEGER NOT NULL
+        );",
+    )?;
+    let index_exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'job_logs_fts')",
+        [],
+        |row| row.get(0),
+    )?;
+    // Indexes built before versions were recorded match version 1
+    let version: i64 = conn
+        .query_row(
+            "SELECT version FROM index_versions WHERE name = 'job_logs_fts'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(1);
+    let rebuild = !index_exists || version != JOB_LOG_INDEX_VERSION;
+    if index_exists && rebuild {
+        conn.execute_batch(
+            "DROP TRIGGER IF EXISTS job_logs_fts_ai;
+             DROP TRIGGER IF EXISTS job_logs_fts_ad;
+             DROP TABLE job_logs_fts;",
+        )?;
+    }
+
+    conn.execute_batch(
+        r#"
+        CREATE VIRTUAL TABLE IF NOT EXISTS job_logs_fts USING fts5(
+            content,
+            content = 'job_logs', content_rowid = 'id'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS job_logs_fts_ai AFTER INSERT ON job_logs BEGIN
+            INSERT INTO job_logs_fts (rowid, content) VALUES (new.id, new.content);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS job_logs_fts_ad AFTER DELETE ON job_logs BEGIN
+            INSERT INTO job_logs_fts (job_logs_fts, rowid, content)
+            VALUES ('delete', old.id, old.content);
+        END;
+        "#,
+    )?;
+
+    if rebuild {
+        eprintln!("[db] Building job log search index");
+        conn.execute_batch("INSERT INTO job_logs_fts (job_logs_fts) VALUES ('rebuild');")?;
+        conn.execute(
+            "INSERT INTO index_versions (name, version) VALUES ('job_logs_fts', ?1)
+             ON CONFLICT(name) DO UPDATE SET version = excluded.version",
+            [JOB_LOG_INDEX_VERSION],
+        )?;
+    }
+
     Ok(())
 }
 
@@ -219,6 +648,111 @@ fn run_migrations(conn: &Connection) -> SqliteResult<()> {
         conn.execute("ALTER TABLE leads ADD COLUMN notes TEXT", [])?;
     }
 
+    // Older databases may predate these tables entirely; init_schema creates them
+    fn needs_column(conn: &Connection, table: &str, column: &str) -> bool {
+        column_exists(conn, table, "id") && !column_exists(conn, table, column)
+    }
+
+    if needs_column(conn, "jobs", "output_archive_path") {
+        conn.execute("ALTER TABLE jobs ADD COLUMN output_archive_path TEXT", [])?;
+    }
+
+    if needs_column(conn, "jobs", "progress") {
+        conn.execute("ALTER TABLE jobs ADD COLUMN progress TEXT", [])?;
+    }
+
+    if needs_column(conn, "jobs", "output_archived_at") {
+        conn.execute("ALTER TABLE jobs ADD COLUMN output_archived_at INTEGER", [])?;
+    }
+
+    if needs_column(conn, "settings", "output_retention_days") {
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN output_retention_days INTEGER NOT NULL DEFAULT 30",
+            [],
+        )?;
+    }
+
+    if needs_column(conn, "settings", "orphan_process_policy") {
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN orphan_process_policy TEXT NOT NULL DEFAULT 'adopt'",
+            [],
+        )?;
+    }
+
+    if needs_column(conn, "settings", "log_retention_days") {
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN log_retention_days INTEGER NOT NULL DEFAULT 14",
+            [],
+        )?;
+    }
+
+    if needs_column(conn, "settings", "log_storage_cap_mb") {
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN log_storage_cap_mb INTEGER NOT NULL DEFAULT 512",
+            [],
+        )?;
+    }
+
+    if needs_column(conn, "settings", "redaction_enabled") {
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN redaction_enabled INTEGER NOT NULL DEFAULT 1",
+            [],
+        )?;
+    }
+
+    if needs_column(conn, "settings", "raw_copy_policy") {
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN raw_copy_policy TEXT NOT NULL DEFAULT 'discard'",
+            [],
+        )?;
+    }
+
+    let defaults = JobLimits::default();
+    for (column, default) in [
+        ("max_concurrent_jobs", defaults.max_concurrent_jobs),
+        ("job_timeout_secs", defaults.job_timeout_secs),
+        ("queue_timeout_secs", defaults.queue_timeout_secs),
+        ("graceful_shutdown_secs", defaults.graceful_shutdown_secs),
+        (
+            "stream_drain_timeout_secs",
+            defaults.stream_drain_timeout_secs,
+        ),
+        (
+            "max_accumulated_output_mb",
+            defaults.max_accumulated_output_mb,
+        ),
+        (
+            "stale_job_threshold_secs",
+            defaults.stale_job_threshold_secs,
+        ),
+    ] {
+        if needs_column(conn, "settings", column) {
+            conn.execute(
+                &format!(
+                    "ALTER TABLE settings ADD COLUMN {} INTEGER NOT NULL DEFAULT {}",
+                    column, default
+                ),
+                [],
+            )?;
+        }
+    }
+
+    for column in [
+        "notify_job_completed",
+        "notify_job_failed",
+        "notify_hot_lead",
+    ] {
+        if needs_column(conn, "settings", column) {
+            conn.execute(
+                &format!(
+                    "ALTER TABLE settings ADD COLUMN {} INTEGER NOT NULL DEFAULT 1",
+                    column
+                ),
+                [],
+            )?;
+        }
+    }
+
     // Helper to check if a column has NOT NULL constraint
     fn column_has_notnull(conn: &Connection, table: &str, column: &str) -> bool {
         let query = format!("PRAGMA table_info({})", table);
@@ -295,34 +829,719 @@ pub fn get_db_path() -> PathBuf {
 
 #[cfg(test)]
 mod tests {
-    use super::{init_schema, run_migrations};
-    use crate::db::{get_lead, insert_lead, update_lead_notes, NewLead};
-    use rusqlite::Connection;
+    use super::run_migrations;
+    use super::test_support::{add_job, add_lead, memory_db, new_job, new_lead};
+    use crate::db::{
+        delete_leads, get_adjacent_leads, get_lead, insert_lead, query_leads, search,
+        update_lead_notes, LeadFilter, LeadQuery, LeadSort, LeadSortField, NewLead, SortDirection,
+    };
+    use rusqlite::{params, Connection};
 
     #[test]
     fn lead_notes_round_trip_and_can_be_cleared() {
-        let conn = Connection::open_in_memory().unwrap();
-        init_schema(&conn).unwrap();
+        let conn = memory_db();
+        let lead_id = add_lead(&conn, "Acme");
+
+        update_lead_notes(&conn, lead_id, "Follow up next Tuesday").unwrap();
+        assert_eq!(
+            get_lead(&conn, lead_id).unwrap().unwrap().notes.as_deref(),
+            Some("Follow up next Tuesday")
+        );
+
+        update_lead_notes(&conn, lead_id, "").unwrap();
+        assert_eq!(get_lead(&conn, lead_id).unwrap().unwrap().notes, None);
+    }
+
+    #[test]
+    fn search_index_follows_profile_updates_and_deletes() {
+        let conn = memory_db();
+        let acme = add_lead(&conn, "Acme");
+        let globex = add_lead(&conn, "Globex");
+        conn.execute(
+            "UPDATE leads SET company_profile = 'SOC 2 Type II certified, runs on Snowflake' WHERE id = ?1",
+            [acme],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE leads SET company_profile = 'Snowflake customer' WHERE id = ?1",
+            [globex],
+        )
+        .unwrap();
+
+        let leads_only = ["lead".to_string()];
+        let hits = search(&conn, "SOC 2 and Snowflake", Some(&leads_only), 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entity_id, acme);
+        assert!(hits[0].snippet.contains("<mark>"));
+
+        delete_leads(&conn, &[acme]).unwrap();
+        assert!(search(&conn, "SOC 2", None, 10).unwrap().is_empty());
+        assert_eq!(search(&conn, "snowflake", None, 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn lead_query_pages_through_filtered_sorted_results() {
+        let conn = memory_db();
+        for (name, country) in [
+            ("Delta", "DE"),
+            ("Alpha", "DE"),
+            ("Echo", "FR"),
+            ("Charlie", "DE"),
+            ("Bravo", "DE"),
+        ] {
+            insert_lead(
+                &conn,
+                &NewLead {
+                    company_name: name.to_string(),
+                    website: None,
+                    city: None,
+                    state: None,
+                    country: Some(country.to_string()),
+                },
+            )
+            .unwrap();
+        }
+
+        let mut query = LeadQuery {
+            filter: LeadFilter {
+                countries: vec!["DE".to_string()],
+                ..Default::default()
+            },
+            sort: vec![LeadSort {
+                field: LeadSortField::CompanyName,
+                direction: SortDirection::Desc,
+            }],
+            cursor: None,
+            limit: Some(3),
+        };
+        let first = query_leads(&conn, &query).unwrap();
+        let names = |page: &crate::db::Page<crate::db::LeadWithScore>| {
+            page.items
+                .iter()
+                .map(|l| l.lead.company_name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(first.total, 4);
+        assert_eq!(names(&first), ["Delta", "Charlie", "Bravo"]);
+
+        query.cursor = first.next_cursor.clone();
+        let second = query_leads(&conn, &query).unwrap();
+        assert_eq!(names(&second), ["Alpha"]);
+        assert!(second.next_cursor.is_none());
+
+        let charlie = first.items[1].lead.id;
+        let (prev, next, position, total) =
+            get_adjacent_leads(&conn, charlie, &query.filter, &query.sort).unwrap();
+        assert_eq!(prev, Some(first.items[0].lead.id));
+        assert_eq!(next, Some(first.items[2].lead.id));
+        assert_eq!((position, total), (2, 4));
+    }
+
+    #[test]
+    fn saved_views_round_trip_with_live_counts() {
+        use crate::db::{get_saved_view, insert_saved_view, NewSavedView};
+
+        let conn = memory_db();
+        for (name, country) in [("Acme", "DE"), ("Globex", "FR")] {
+            let lead = NewLead {
+                country: Some(country.to_string()),
+                ..new_lead(name)
+            };
+            insert_lead(&conn, &lead).unwrap();
+        }
+        let view = NewSavedView {
+            name: "German leads".to_string(),
+            filter: LeadFilter {
+                countries: vec!["DE".to_string()],
+                ..Default::default()
+            },
+            sort: vec![LeadSort {
+                field: LeadSortField::CompanyName,
+                direction: SortDirection::Desc,
+            }],
+            pinned: true,
+        };
+        let id = insert_saved_view(&conn, &view).unwrap();
+
+        let stored = get_saved_view(&conn, id).unwrap().unwrap();
+        assert_eq!(stored.name, "German leads");
+        assert_eq!(stored.filter.countries, ["DE"]);
+        assert_eq!(stored.sort.len(), 1);
+        assert!(stored.pinned);
+        assert_eq!(stored.count, Some(1));
+        assert_eq!(stored.error, None);
+    }
+
+    #[test]
+    fn broken_saved_views_report_an_error_without_hiding_the_others() {
+        use crate::db::{get_saved_view, get_saved_views, insert_saved_view, NewSavedView};
+
+        let conn = memory_db();
+        add_lead(&conn, "Acme");
+        let working = insert_saved_view(
+            &conn,
+            &NewSavedView {
+                name: "All".to_string(),
+                filter: LeadFilter::default(),
+                sort: Vec::new(),
+                pinned: true,
+            },
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO saved_views (name, filter, sort, pinned, position, created_at, updated_at)
+             VALUES ('Broken', '{\"countries\":\"DE\"}', '[]', 1, 1, 0, 0)",
+            [],
+        )
+        .unwrap();
+        let id = conn.last_insert_rowid();
+
+        let broken = get_saved_view(&conn, id).unwrap().unwrap();
+        assert_eq!(broken.count, None);
+        assert!(broken.error.unwrap().starts_with("Filter no longer parses"));
+        let views = get_saved_views(&conn).unwrap();
+        assert_eq!(views.len(), 2);
+        assert_eq!(views[0].id, working);
+        assert_eq!(views[0].count, Some(1));
+
+        conn.execute(
+            "UPDATE saved_views SET filter = '{}', sort = 'not json' WHERE id = ?1",
+            [id],
+        )
+        .unwrap();
+        let broken = get_saved_view(&conn, id).unwrap().unwrap();
+        assert!(broken.error.unwrap().starts_with("Sort no longer parses"));
+
+        // A filter on a custom field that no longer exists
+        conn.execute(
+            "UPDATE saved_views SET sort = '[]',
+                 filter = '{\"customFields\":[{\"fieldId\":99,\"op\":\"is_set\"}]}'
+             WHERE id = ?1",
+            [id],
+        )
+        .unwrap();
+        let broken = get_saved_view(&conn, id).unwrap().unwrap();
+        assert_eq!(broken.count, None);
+        assert!(broken.error.unwrap().contains("Unknown custom field"));
+    }
+
+    #[test]
+    fn custom_field_values_are_typed_and_filterable() {
+        use crate::db::{
+            coerce_custom_field_value, count_leads, get_custom_field, insert_custom_field,
+            insert_tag, set_custom_field_value, set_entity_tags, CustomFieldFilter, CustomFieldOp,
+            CustomFieldType, NewCustomField,
+        };
+        use serde_json::json;
+
+        let conn = memory_db();
+        let small = add_lead(&conn, "Small");
+        let large = add_lead(&conn, "Large");
+
+        let field_id = insert_custom_field(
+            &conn,
+            &NewCustomField {
+                entity_type: "lead".to_string(),
+                key: "warehouse_tb".to_string(),
+                label: "Warehouse size (TB)".to_string(),
+                field_type: CustomFieldType::Number,
+                options: vec![],
+                research_fillable: false,
+                description: None,
+            },
+        )
+        .unwrap();
+        let field = get_custom_field(&conn, field_id).unwrap().unwrap();
+        assert!(coerce_custom_field_value(&field, &json!("lots")).is_err());
+
+        for (id, value) in [(small, json!(2)), (large, json!("1,500"))] {
+            let value = coerce_custom_field_value(&field, &value).unwrap();
+            set_custom_field_value(&conn, field_id, id, Some(value)).unwrap();
+        }
+        let tag_id = insert_tag(&conn, "partner-sourced", None).unwrap();
+        set_entity_tags(&conn, "lead", small, &[tag_id]).unwrap();
+
+        let filter = |op, value| LeadFilter {
+            custom_fields: vec![CustomFieldFilter {
+                field_id,
+                op,
+                value,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            count_leads(&conn, &filter(CustomFieldOp::Gt, json!(100))).unwrap(),
+            1
+        );
+        assert_eq!(
+            count_leads(&conn, &filter(CustomFieldOp::IsSet, json!(null))).unwrap(),
+            2
+        );
+        let tagged = LeadFilter {
+            tag_ids: vec![tag_id],
+            ..Default::default()
+        };
+        assert_eq!(count_leads(&conn, &tagged).unwrap(), 1);
+
+        delete_leads(&conn, &[small]).unwrap();
+        assert_eq!(
+            count_leads(&conn, &filter(CustomFieldOp::IsSet, json!(null))).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn custom_field_of_another_entity_type_is_rejected_in_filters() {
+        use crate::db::{
+            count_leads, insert_custom_field, CustomFieldFilter, CustomFieldOp, CustomFieldType,
+            NewCustomField,
+        };
+
+        let conn = memory_db();
+        add_lead(&conn, "Acme");
+        let person_field = insert_custom_field(
+            &conn,
+            &NewCustomField {
+                entity_type: "person".to_string(),
+                key: "alma_mater".to_string(),
+                label: "Alma mater".to_string(),
+                field_type: CustomFieldType::Text,
+                options: vec![],
+                research_fillable: false,
+                description: None,
+            },
+        )
+        .unwrap();
+
+        let filter = |field_id| LeadFilter {
+            custom_fields: vec![CustomFieldFilter {
+                field_id,
+                op: CustomFieldOp::IsNotSet,
+                value: serde_json::Value::Null,
+            }],
+            ..Default::default()
+        };
+        assert!(count_leads(&conn, &filter(person_field)).is_err());
+        assert!(count_leads(&conn, &filter(person_field + 1)).is_err());
+    }
+
+    #[test]
+    fn custom_fields_used_by_saved_views_cannot_be_deleted() {
+        use crate::db::{
+            delete_custom_field, delete_saved_view, get_custom_field, insert_custom_field,
+            insert_saved_view, CustomFieldFilter, CustomFieldOp, CustomFieldType, NewCustomField,
+            NewSavedView,
+        };
+
+        let conn = memory_db();
+        let field = insert_custom_field(
+            &conn,
+            &NewCustomField {
+                entity_type: "lead".to_string(),
+                key: "arr".to_string(),
+                label: "ARR".to_string(),
+                field_type: CustomFieldType::Number,
+                options: vec![],
+                research_fillable: false,
+                description: None,
+            },
+        )
+        .unwrap();
+        let view = insert_saved_view(
+            &conn,
+            &NewSavedView {
+                name: "Big accounts".to_string(),
+                filter: LeadFilter {
+                    custom_fields: vec![CustomFieldFilter {
+                        field_id: field,
+                        op: CustomFieldOp::IsSet,
+                        value: serde_json::Value::Null,
+                    }],
+                    ..Default::default()
+                },
+                sort: Vec::new(),
+                pinned: true,
+            },
+        )
+        .unwrap();
+
+        let err = delete_custom_field(&conn, field).unwrap_err();
+        assert!(err.to_string().contains("Big accounts"));
+        assert!(get_custom_field(&conn, field).unwrap().is_some());
+
+        delete_saved_view(&conn, view).unwrap();
+        delete_custom_field(&conn, field).unwrap();
+        assert!(get_custom_field(&conn, field).unwrap().is_none());
+    }
+
+    #[test]
+    fn duplicate_leads_are_detected_and_merged() {
+        use crate::db::{
+            find_duplicate_leads, get_merge_history, get_people_for_lead, insert_person,
+            merge_leads, NewPerson,
+        };
+
+        let conn = memory_db();
+        let lead = |name: &str, website: Option<&str>| NewLead {
+            company_name: name.to_string(),
+            website: website.map(String::from),
+            city: None,
+            state: None,
+            country: None,
+        };
+        let acme = insert_lead(&conn, &lead("Acme Corp", None)).unwrap();
+        let acme_inc =
+            insert_lead(&conn, &lead("ACME, Inc.", Some("https://www.acme.com/"))).unwrap();
+        let acme_site = insert_lead(&conn, &lead("Acme Widgets", Some("acme.com"))).unwrap();
+        insert_lead(&conn, &lead("Globex", None)).unwrap();
+        update_lead_notes(&conn, acme_site, "Met at conference").unwrap();
+        insert_person(
+            &conn,
+            &NewPerson {
+                first_name: "Jane".to_string(),
+                last_name: "Doe".to_string(),
+                email: None,
+                title: None,
+                linkedin_url: None,
+                lead_id: Some(acme_site),
+            },
+        )
+        .unwrap();
+
+        let groups = find_duplicate_leads(&conn).unwrap();
+        assert_eq!(groups.len(), 1);
+        let ids: Vec<i64> = groups[0].members.iter().map(|m| m.id).collect();
+        assert_eq!(ids, [acme, acme_inc, acme_site]);
+
+        let result = merge_leads(&conn, acme, &[acme_inc, acme_site]).unwrap();
+        assert_eq!(result.merged_ids, [acme_inc, acme_site]);
+        let merged = get_lead(&conn, acme).unwrap().unwrap();
+        assert_eq!(merged.website.as_deref(), Some("https://www.acme.com/"));
+        assert_eq!(merged.notes.as_deref(), Some("Met at conference"));
+        assert_eq!(get_people_for_lead(&conn, acme).unwrap().len(), 1);
+        assert!(get_lead(&conn, acme_site).unwrap().is_none());
+        assert_eq!(get_merge_history(&conn, "lead", acme).unwrap().len(), 2);
+        assert!(find_duplicate_leads(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn new_records_match_existing_leads_and_people_before_insert() {
+        use crate::db::{find_matching_lead, find_matching_person, insert_person, NewPerson};
+
+        let conn = memory_db();
+        let acme = insert_lead(
+            &conn,
+            &NewLead {
+                website: Some("https://www.acme.com/".to_string()),
+                ..new_lead("Acme Corp")
+            },
+        )
+        .unwrap();
+        add_lead(&conn, "Globex");
+
+        let matching = |name, website| find_matching_lead(&conn, name, website).unwrap();
+        assert_eq!(matching("Widgets Ltd", Some("acme.com/about")), Some(acme));
+        assert_eq!(matching("ACME, Inc.", None), Some(acme));
+        assert_eq!(matching("Initech", Some("initech.com")), None);
+
+        let jane = insert_person(
+            &conn,
+            &NewPerson {
+                first_name: "Jane".to_string(),
+                last_name: "Doe".to_string(),
+                email: Some("jane@acme.com".to_string()),
+                title: None,
+                linkedin_url: None,
+                lead_id: Some(acme),
+            },
+        )
+        .unwrap();
+        let person = |first, last, email, linkedin| {
+            find_matching_person(&conn, acme, first, last, email, linkedin).unwrap()
+        };
+        assert_eq!(
+            person("J.", "Doe", Some(" Jane@Acme.com"), None),
+            Some(jane)
+        );
+        // Without contact details the exact name identifies the person
+        assert_eq!(person("jane", "DOE", None, None), Some(jane));
+        assert_eq!(
+            person("Jane", "Doe", None, Some("linkedin.com/in/jdoe")),
+            Some(jane)
+        );
+        assert_eq!(person("Janet", "Doe", None, None), None);
+        // Two different emails are two different people
+        assert_eq!(person("Jane", "Doe", Some("j.doe@acme.com"), None), None);
+    }
+
+    #[test]
+    fn research_runs_keep_versions_and_restore() {
+        use crate::db::{
+            diff_research_runs, get_research_runs, insert_research_run, restore_research_run,
+            DiffOp,
+        };
+
+        let conn = memory_db();
+        let lead_id = add_lead(&conn, "Acme");
+
+        let first =
+            insert_research_run(&conn, "lead", lead_id, None, "# Acme\nCEO: Ann", None).unwrap();
+        let second =
+            insert_research_run(&conn, "lead", lead_id, None, "# Acme\nCEO: Bob", None).unwrap();
+
+        let diff = diff_research_runs(&conn, first, second).unwrap();
+        assert_eq!((diff.added, diff.removed), (1, 1));
+        assert_eq!(diff.lines[0].op, DiffOp::Equal);
+
+        let restored = restore_research_run(&conn, first).unwrap();
+        assert_eq!((restored.version, restored.restored_from), (3, Some(1)));
+        assert_eq!(
+            get_lead(&conn, lead_id)
+                .unwrap()
+                .unwrap()
+                .company_profile
+                .as_deref(),
+            Some("# Acme\nCEO: Ann")
+        );
+        assert_eq!(get_research_runs(&conn, "lead", lead_id).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn sources_record_which_run_backs_each_field() {
+        use crate::db::{get_field_sources, get_sources, insert_research_run, insert_sources};
+        use crate::jobs::enrichment::ResearchSource;
+
+        let conn = memory_db();
+        let lead_id = add_lead(&conn, "Acme");
+        let source = |url: &str, fields: &[&str]| ResearchSource {
+            url: url.to_string(),
+            title: None,
+            retrieved_at: Some("2026-10-01".to_string()),
+            claim: None,
+            fields: fields.iter().map(|f| f.to_string()).collect(),
+        };
+
+        let first = insert_research_run(&conn, "lead", lead_id, None, "v1", None).unwrap();
+        insert_sources(
+            &conn,
+            "lead",
+            lead_id,
+            Some(first),
+            None,
+            &[source("https://old.example", &["employees", "industry"])],
+        )
+        .unwrap();
+        let second = insert_research_run(&conn, "lead", lead_id, None, "v2", None).unwrap();
+        insert_sources(
+            &conn,
+            "lead",
+            lead_id,
+            Some(second),
+            None,
+            &[
+                source("https://a.example", &["employees"]),
+                source("https://b.example", &["employees"]),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(get_sources(&conn, "lead", lead_id).unwrap().len(), 3);
+        let fields = get_field_sources(&conn, "lead", lead_id).unwrap();
+        let urls = |field: &str| -> Vec<String> {
+            let entry = fields.iter().find(|f| f.field == field).unwrap();
+            entry.sources.iter().map(|s| s.url.clone()).collect()
+        };
+        assert_eq!(
+            urls("employees"),
+            ["https://a.example", "https://b.example"]
+        );
+        assert_eq!(urls("industry"), ["https://old.example"]);
+
+        delete_leads(&conn, &[lead_id]).unwrap();
+        assert!(get_sources(&conn, "lead", lead_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn merge_policy_protects_user_entered_fields() {
+        use crate::db::{
+            enrich_lead, get_field_provenance, record_inserted_fields, set_merge_policy,
+            FieldSourceType, MergePolicy, MergePolicySetting,
+        };
+        use crate::jobs::enrichment::LeadEnrichment;
+
+        let conn = memory_db();
         let lead_id = insert_lead(
             &conn,
             &NewLead {
                 company_name: "Acme".to_string(),
                 website: None,
-                city: None,
+                city: Some("Austin".to_string()),
                 state: None,
                 country: None,
             },
         )
         .unwrap();
+        record_inserted_fields(&conn, "lead", lead_id, FieldSourceType::User, None).unwrap();
 
-        update_lead_notes(&conn, lead_id, "Follow up next Tuesday").unwrap();
+        let research = |city: &str, employees: i64| LeadEnrichment {
+            city: Some(city.to_string()),
+            employees: Some(employees),
+            confidence: [("employees".to_string(), 0.9)].into_iter().collect(),
+            ..Default::default()
+        };
+
+        // Default policy only fills empty fields
         assert_eq!(
-            get_lead(&conn, lead_id).unwrap().unwrap().notes.as_deref(),
-            Some("Follow up next Tuesday")
+            enrich_lead(&conn, lead_id, &research("Dallas", 100), Some("job-1")).unwrap(),
+            1
+        );
+        assert_eq!(
+            enrich_lead(&conn, lead_id, &research("Dallas", 120), Some("job-2")).unwrap(),
+            0
         );
 
-        update_lead_notes(&conn, lead_id, "").unwrap();
-        assert_eq!(get_lead(&conn, lead_id).unwrap().unwrap().notes, None);
+        set_merge_policy(
+            &conn,
+            &MergePolicySetting {
+                entity_type: "lead".to_string(),
+                policy: MergePolicy::OverwriteUnlessUser,
+                min_confidence: Some(0.8),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            enrich_lead(&conn, lead_id, &research("Dallas", 120), Some("job-3")).unwrap(),
+            1
+        );
+        let lead = get_lead(&conn, lead_id).unwrap().unwrap();
+        assert_eq!(lead.city.as_deref(), Some("Austin"));
+        assert_eq!(lead.employees, Some(120));
+
+        let provenance = get_field_provenance(&conn, "lead", lead_id).unwrap();
+        let employees = provenance.iter().find(|p| p.field == "employees").unwrap();
+        assert_eq!(employees.source_type, FieldSourceType::Research);
+        assert_eq!(employees.job_id.as_deref(), Some("job-3"));
+        assert_eq!(employees.confidence, Some(0.9));
+        let city = provenance.iter().find(|p| p.field == "city").unwrap();
+        assert_eq!(city.source_type, FieldSourceType::User);
+    }
+
+    #[test]
+    fn research_rerun_changes_roll_into_digest() {
+        use crate::db::{
+            digest_to_markdown, enrich_lead, get_change_digests, get_lead_changes,
+            insert_lead_change, record_inserted_fields, set_merge_policy, summarize_lead_changes,
+            FieldSourceType, MergePolicy, MergePolicySetting, PersonChange,
+        };
+        use crate::jobs::enrichment::LeadEnrichment;
+
+        let conn = memory_db();
+        let lead_id = insert_lead(
+            &conn,
+            &NewLead {
+                city: Some("Austin".to_string()),
+                ..new_lead("Acme")
+            },
+        )
+        .unwrap();
+        record_inserted_fields(&conn, "lead", lead_id, FieldSourceType::User, None).unwrap();
+        let first = LeadEnrichment {
+            employees: Some(500),
+            industry: Some("Software".to_string()),
+            ..Default::default()
+        };
+        enrich_lead(&conn, lead_id, &first, None).unwrap();
+        conn.execute(
+            "UPDATE leads SET company_profile = ?1 WHERE id = ?2",
+            params!["# Overview\nMakes widgets.\n# Team\nCEO: Ann", lead_id],
+        )
+        .unwrap();
+        let before = get_lead(&conn, lead_id).unwrap().unwrap();
+
+        // The user's city is kept by the merge policy, so it is no change
+        set_merge_policy(
+            &conn,
+            &MergePolicySetting {
+                entity_type: "lead".to_string(),
+                policy: MergePolicy::OverwriteUnlessUser,
+                min_confidence: None,
+            },
+        )
+        .unwrap();
+        let rerun = LeadEnrichment {
+            employees: Some(650),
+            industry: Some("software".to_string()),
+            city: Some("Dallas".to_string()),
+            ..Default::default()
+        };
+        enrich_lead(&conn, lead_id, &rerun, None).unwrap();
+        conn.execute(
+            "UPDATE leads SET company_profile = ?1 WHERE id = ?2",
+            params![
+                "# Overview\nMakes widgets.\n# Team\nCEO: Ann\n# Funding\n- Raised a $40M Series B in May",
+                lead_id
+            ],
+        )
+        .unwrap();
+        let after = get_lead(&conn, lead_id).unwrap().unwrap();
+
+        let summary = summarize_lead_changes(
+            &before,
+            &after,
+            vec![PersonChange {
+                person_id: None,
+                name: "Jane Doe".to_string(),
+                title: Some("VP of Sales".to_string()),
+                management_level: None,
+            }],
+            Vec::new(),
+        );
+        assert_eq!(summary.field_changes.len(), 1);
+        assert_eq!(summary.field_changes[0].field, "employees");
+        assert_eq!(summary.sections_added, ["Funding"]);
+        assert!(summary.sections_changed.is_empty());
+        assert_eq!(summary.highlights.len(), 2);
+        assert!(summary.highlights[0].contains("Jane Doe"));
+        assert!(summary.highlights[1].contains("Series B"));
+
+        insert_lead_change(&conn, lead_id, None, &summary).unwrap();
+        assert_eq!(get_lead_changes(&conn, lead_id).unwrap().len(), 1);
+        let digests = get_change_digests(&conn, 1).unwrap();
+        assert_eq!(digests.len(), 1);
+        let markdown = digest_to_markdown(&digests[0]);
+        assert!(markdown.contains("## Acme"));
+        assert!(markdown.contains("employees: 500 → 650"));
+
+        delete_leads(&conn, &[lead_id]).unwrap();
+        assert!(get_change_digests(&conn, 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn interrupted_jobs_with_a_session_are_resumable_until_rerun() {
+        use crate::db::{
+            get_resumable_jobs, insert_job, update_job_claude_session, update_job_status,
+        };
+
+        let conn = memory_db();
+        let interrupt = |id: &str, entity_id: i64, session: Option<&str>| {
+            insert_job(&conn, &new_job(id, "company_research", entity_id)).unwrap();
+            update_job_status(&conn, id, "running", None, None).unwrap();
+            if let Some(session) = session {
+                update_job_claude_session(&conn, id, session, "claude-sonnet-5").unwrap();
+            }
+            update_job_status(&conn, id, "interrupted", None, Some("Interrupted")).unwrap();
+        };
+        interrupt("resumable", 1, Some("session-1"));
+        interrupt("no-session", 2, None);
+        interrupt("superseded", 3, Some("session-3"));
+        add_job(&conn, "rerun", "company_research", 3, "completed");
+
+        let resumable: Vec<String> = get_resumable_jobs(&conn)
+            .unwrap()
+            .into_iter()
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(resumable, ["resumable"]);
     }
 
     #[test]
@@ -345,4 +1564,146 @@ mod tests {
             .any(|column| column == "notes");
         assert!(has_notes);
     }
+
+    #[test]
+    fn saving_the_model_keeps_archive_retention_and_orphan_policy() {
+        use crate::db::{
+            get_settings, update_orphan_process_policy, update_output_retention_days,
+            update_settings, OrphanProcessPolicy,
+        };
+
+        let conn = memory_db();
+        update_settings(&conn, "sonnet", false).unwrap();
+        update_output_retention_days(&conn, 7).unwrap();
+        update_orphan_process_policy(&conn, OrphanProcessPolicy::Terminate).unwrap();
+
+        update_settings(&conn, "opus", true).unwrap();
+        let settings = get_settings(&conn).unwrap();
+        assert_eq!(
+            (settings.model.as_str(), settings.use_chrome),
+            ("opus", true)
+        );
+        assert_eq!(settings.output_retention_days, 7);
+        assert_eq!(
+            settings.orphan_process_policy,
+            OrphanProcessPolicy::Terminate
+        );
+    }
+
+    #[test]
+    fn job_limits_are_validated_in_range_and_against_each_other() {
+        use crate::db::{JobLimitOverrides, JobLimits};
+
+        let defaults = JobLimits::default();
+        assert_eq!(defaults.validate(), Ok(()));
+        let zero_slots = JobLimits {
+            max_concurrent_jobs: 0,
+            ..defaults
+        };
+        assert_eq!(
+            zero_slots.validate().unwrap_err(),
+            "Concurrent jobs must be between 1 and 20"
+        );
+        let stale_too_soon = JobLimits {
+            job_timeout_secs: 900,
+            stale_job_threshold_secs: 600,
+            ..defaults
+        };
+        assert!(stale_too_soon.validate().is_err());
+
+        // Overrides are checked with the global limits they fall back to
+        let longer_timeout = JobLimitOverrides {
+            job_type: "company_research".to_string(),
+            job_timeout_secs: Some(1800),
+            ..Default::default()
+        };
+        assert!(longer_timeout.validate(&defaults).is_err());
+        let with_threshold = JobLimitOverrides {
+            stale_job_threshold_secs: Some(3600),
+            ..longer_timeout
+        };
+        assert_eq!(with_threshold.validate(&defaults), Ok(()));
+        let out_of_range = JobLimitOverrides {
+            queue_timeout_secs: Some(0),
+            ..with_threshold
+        };
+        assert!(out_of_range.validate(&defaults).is_err());
+    }
+
+    #[test]
+    fn job_limits_apply_per_job_type_overrides() {
+        use crate::db::{
+            get_job_limits, get_settings, set_job_limit_overrides, update_job_limits,
+            JobLimitOverrides, JobLimits,
+        };
+        use crate::jobs::recovery::detect_stale_jobs;
+
+        let conn = memory_db();
+        assert_eq!(
+            get_settings(&conn).unwrap().job_limits,
+            JobLimits::default()
+        );
+
+        let limits = JobLimits {
+            max_concurrent_jobs: 3,
+            job_timeout_secs: 900,
+            ..JobLimits::default()
+        };
+        update_job_limits(&conn, &limits).unwrap();
+        set_job_limit_overrides(
+            &conn,
+            &JobLimitOverrides {
+                job_type: "company_research".to_string(),
+                job_timeout_secs: Some(1800),
+                stale_job_threshold_secs: Some(3600),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        set_job_limit_overrides(
+            &conn,
+            &JobLimitOverrides {
+                job_type: "conversation".to_string(),
+                job_timeout_secs: Some(180),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let company = get_job_limits(&conn, "company_research").unwrap();
+        assert_eq!(company.job_timeout_secs, 1800);
+        assert_eq!(company.max_concurrent_jobs, 3);
+        assert_eq!(company.queue_timeout_secs, limits.queue_timeout_secs);
+        assert_eq!(
+            get_job_limits(&conn, "conversation")
+                .unwrap()
+                .job_timeout_secs,
+            180
+        );
+        assert_eq!(get_job_limits(&conn, "scoring").unwrap(), limits);
+
+        // Clearing every field removes the override
+        set_job_limit_overrides(
+            &conn,
+            &JobLimitOverrides {
+                job_type: "conversation".to_string(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(get_job_limits(&conn, "conversation").unwrap(), limits);
+
+        // Stale detection uses each job type's threshold
+        for (id, job_type) in [("job-1", "company_research"), ("job-2", "scoring")] {
+            add_job(&conn, id, job_type, 1, "running");
+        }
+        conn.execute("UPDATE jobs SET created_at = created_at - 1200", [])
+            .unwrap();
+        let stale: Vec<_> = detect_stale_jobs(&conn)
+            .unwrap()
+            .into_iter()
+            .map(|j| j.id)
+            .collect();
+        assert_eq!(stale, ["job-2"]);
+    }
 }
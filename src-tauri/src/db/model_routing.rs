@@ -0,0 +1,262 @@
+//! Model selection for agent jobs.
+//!
+//! A job runs on the first enabled routing rule matching its job type and the
+//! lead it works on (latest score tier, industry), else on the model set for
+//! its job type, else on the global `Settings::model`. Entries naming a model
+//! that is no longer supported are skipped.
+
+use super::queries::get_settings;
+use super::schema::*;
+use crate::model_config::is_supported_model;
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult};
+
+pub fn get_job_type_models(conn: &Connection) -> SqliteResult<Vec<JobTypeModel>> {
+    let mut stmt = conn.prepare("SELECT job_type, model FROM job_type_models ORDER BY job_type")?;
+    let rows = stmt.query_map([], |row| {
+        Ok(JobTypeModel {
+            job_type: row.get(0)?,
+            model: row.get(1)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Set the model of a job type; None goes back to the global model
+pub fn set_job_type_model(
+    conn: &Connection,
+    job_type: &str,
+    model: Option<&str>,
+) -> SqliteResult<()> {
+    match model {
+        Some(model) => conn.execute(
+            "INSERT OR REPLACE INTO job_type_models (job_type, model, updated_at)
+             VALUES (?1, ?2, ?3)",
+            params![job_type, model, chrono::Utc::now().timestamp_millis()],
+        )?,
+        None => conn.execute(
+            "DELETE FROM job_type_models WHERE job_type = ?1",
+            params![job_type],
+        )?,
+    };
+    Ok(())
+}
+
+pub fn get_model_routing_rules(conn: &Connection) -> SqliteResult<Vec<ModelRoutingRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, job_type, tier, industry, model, priority, enabled, created_at
+         FROM model_routing_rules ORDER BY priority ASC, id ASC",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(ModelRoutingRule {
+            id: row.get(0)?,
+            job_type: row.get(1)?,
+            tier: row.get(2)?,
+            industry: row.get(3)?,
+            model: row.get(4)?,
+            priority: row.get(5)?,
+            enabled: row.get::<_, i64>(6)? != 0,
+            created_at: row.get(7)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn insert_model_routing_rule(
+    conn: &Connection,
+    rule: &NewModelRoutingRule,
+) -> SqliteResult<i64> {
+    conn.execute(
+        "INSERT INTO model_routing_rules (job_type, tier, industry, model, priority, enabled, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6)",
+        params![
+            rule.job_type,
+            rule.tier,
+            rule.industry,
+            rule.model,
+            rule.priority,
+            chrono::Utc::now().timestamp_millis()
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn set_model_routing_rule_enabled(
+    conn: &Connection,
+    id: i64,
+    enabled: bool,
+) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE model_routing_rules SET enabled = ?1 WHERE id = ?2",
+        params![enabled as i64, id],
+    )?;
+    Ok(())
+}
+
+pub fn delete_model_routing_rule(conn: &Connection, id: i64) -> SqliteResult<bool> {
+    let deleted = conn.execute("DELETE FROM model_routing_rules WHERE id = ?1", params![id])?;
+    Ok(deleted > 0)
+}
+
+/// (tier, industry) of the lead a job works on, if any
+fn lead_facts(
+    conn: &Connection,
+    job_type: &str,
+    entity_id: i64,
+) -> SqliteResult<(Option<String>, Option<String>)> {
+    let lead_id = match job_type {
+        "company_research" | "scoring" => Some(entity_id),
+        "person_research" | "conversation" => conn
+            .query_row(
+                "SELECT lead_id FROM people WHERE id = ?1",
+                params![entity_id],
+                |row| row.get::<_, Option<i64>>(0),
+            )
+            .optional()?
+            .flatten(),
+        _ => None,
+    };
+    let Some(lead_id) = lead_id else {
+        return Ok((None, None));
+    };
+    let facts = conn
+        .query_row(
+            "SELECT (SELECT tier FROM lead_scores WHERE lead_id = l.id
+                     ORDER BY created_at DESC, id DESC LIMIT 1),
+                    l.industry
+             FROM leads l WHERE l.id = ?1",
+            params![lead_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    Ok(facts.unwrap_or((None, None)))
+}
+
+/// Model a new job of `job_type` on `entity_id` should run on
+pub fn resolve_job_model(
+    conn: &Connection,
+    job_type: &str,
+    entity_id: i64,
+) -> SqliteResult<ResolvedModel> {
+    let rules = get_model_routing_rules(conn)?;
+    if rules.iter().any(|r| r.enabled) {
+        let (tier, industry) = lead_facts(conn, job_type, entity_id)?;
+        let matches = |condition: &Option<String>, value: &Option<String>| match condition {
+            None => true,
+            Some(expected) => value
+                .as_deref()
+                .is_some_and(|v| v.trim().eq_ignore_ascii_case(expected.trim())),
+        };
+        let rule = rules.iter().find(|r| {
+            r.enabled
+                && is_supported_model(&r.model)
+                && r.job_type.as_deref().is_none_or(|t| t == job_type)
+                && matches(&r.tier, &tier)
+                && matches(&r.industry, &industry)
+        });
+        if let Some(rule) = rule {
+            return Ok(ResolvedModel {
+                model: rule.model.clone(),
+                source: "rule".to_string(),
+                rule_id: Some(rule.id),
+            });
+        }
+    }
+
+    let job_type_model = get_job_type_models(conn)?
+        .into_iter()
+        .find(|m| m.job_type == job_type && is_supported_model(&m.model));
+    if let Some(m) = job_type_model {
+        return Ok(ResolvedModel {
+            model: m.model,
+            source: "job_type".to_string(),
+            rule_id: None,
+        });
+    }
+
+    Ok(ResolvedModel {
+        model: get_settings(conn)?.model,
+        source: "default".to_string(),
+        rule_id: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        insert_model_routing_rule, resolve_job_model, set_job_type_model,
+        set_model_routing_rule_enabled,
+    };
+    use crate::db::test_support::memory_db;
+    use crate::db::NewModelRoutingRule;
+
+    #[test]
+    fn job_models_resolve_from_rules_then_job_type_then_default() {
+        let conn = memory_db();
+        conn.execute_batch(
+            "INSERT INTO leads (id, company_name, industry, created_at)
+                 VALUES (1, 'Acme', 'Software', 0), (2, 'Globex', 'Retail', 0);
+             INSERT INTO people (id, lead_id, first_name, last_name, created_at)
+                 VALUES (10, 1, 'Jane', 'Doe', 0);
+             INSERT INTO scoring_config (id, required_characteristics, demand_signifiers,
+                                         created_at, updated_at)
+                 VALUES (1, '[]', '[]', 0, 0);
+             INSERT INTO lead_scores (lead_id, config_id, passes_requirements, requirement_results,
+                                      total_score, score_breakdown, tier, created_at)
+                 VALUES (1, 1, 1, '[]', 40, '[]', 'warm', 1), (1, 1, 1, '[]', 90, '[]', 'hot', 2);",
+        )
+        .unwrap();
+        let model = |job_type: &str, entity_id: i64| {
+            let resolved = resolve_job_model(&conn, job_type, entity_id).unwrap();
+            (resolved.model, resolved.source)
+        };
+
+        assert_eq!(
+            model("company_research", 1),
+            ("claude-sonnet-5".to_string(), "default".to_string())
+        );
+
+        set_job_type_model(&conn, "conversation", Some("claude-haiku-4-5")).unwrap();
+        let hot = insert_model_routing_rule(
+            &conn,
+            &NewModelRoutingRule {
+                tier: Some("hot".to_string()),
+                model: "claude-opus-4-8".to_string(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        // Unsupported models are skipped
+        insert_model_routing_rule(
+            &conn,
+            &NewModelRoutingRule {
+                industry: Some("retail".to_string()),
+                model: "claude-made-up-99".to_string(),
+                priority: -1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // The latest score is hot; people inherit their lead's tier
+        assert_eq!(
+            model("company_research", 1),
+            ("claude-opus-4-8".to_string(), "rule".to_string())
+        );
+        assert_eq!(
+            model("conversation", 10),
+            ("claude-opus-4-8".to_string(), "rule".to_string())
+        );
+        assert_eq!(
+            model("company_research", 2),
+            ("claude-sonnet-5".to_string(), "default".to_string())
+        );
+
+        set_model_routing_rule_enabled(&conn, hot, false).unwrap();
+        assert_eq!(
+            model("conversation", 10),
+            ("claude-haiku-4-5".to_string(), "job_type".to_string())
+        );
+        set_job_type_model(&conn, "conversation", None).unwrap();
+        assert_eq!(model("conversation", 10).1, "default");
+    }
+}
@@ -0,0 +1,327 @@
+//! Field-level provenance for lead and person enrichment data.
+//!
+//! Every enrichment column records whether its value came from the user, an
+//! import or a research job (with job id and confidence). Research enrichment
+//! goes through the entity type's merge policy, which decides whether an
+//! existing value may be overwritten.
+
+use super::schema::*;
+use rusqlite::types::Value;
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy)]
+enum FieldKind {
+    Text,
+    Integer,
+    Real,
+}
+
+/// (enrichment key, column, kind)
+type FieldSpec = (&'static str, &'static str, FieldKind);
+
+const LEAD_FIELDS: &[FieldSpec] = &[
+    ("website", "website", FieldKind::Text),
+    ("industry", "industry", FieldKind::Text),
+    ("subIndustry", "sub_industry", FieldKind::Text),
+    ("employees", "employees", FieldKind::Integer),
+    ("employeeRange", "employee_range", FieldKind::Text),
+    ("revenue", "revenue", FieldKind::Real),
+    ("revenueRange", "revenue_range", FieldKind::Text),
+    (
+        "companyLinkedinUrl",
+        "company_linkedin_url",
+        FieldKind::Text,
+    ),
+    ("city", "city", FieldKind::Text),
+    ("state", "state", FieldKind::Text),
+    ("country", "country", FieldKind::Text),
+];
+
+const PERSON_FIELDS: &[FieldSpec] = &[
+    ("email", "email", FieldKind::Text),
+    ("title", "title", FieldKind::Text),
+    ("managementLevel", "management_level", FieldKind::Text),
+    ("linkedinUrl", "linkedin_url", FieldKind::Text),
+    ("yearJoined", "year_joined", FieldKind::Integer),
+];
+
+/// Table and enrichment fields of an entity type
+fn entity_fields(entity_type: &str) -> SqliteResult<(&'static str, &'static [FieldSpec])> {
+    match entity_type {
+        "lead" => Ok(("leads", LEAD_FIELDS)),
+        "person" => Ok(("people", PERSON_FIELDS)),
+        _ => Err(rusqlite::Error::InvalidParameterName(format!(
+            "Unknown entity type: {}",
+            entity_type
+        ))),
+    }
+}
+
+fn find_field(entity_type: &str, field: &str) -> SqliteResult<(&'static str, FieldSpec)> {
+    let (table, fields) = entity_fields(entity_type)?;
+    fields
+        .iter()
+        .find(|(key, _, _)| *key == field)
+        .map(|spec| (table, *spec))
+        .ok_or_else(|| {
+            rusqlite::Error::InvalidParameterName(format!(
+                "Unknown {} field: {}",
+                entity_type, field
+            ))
+        })
+}
+
+/// Coerce a JSON value into the SQL type of an enrichment field.
+/// Empty strings and null clear the field.
+pub fn coerce_entity_field_value(
+    entity_type: &str,
+    field: &str,
+    value: &serde_json::Value,
+) -> Result<Value, String> {
+    let (_, (_, _, kind)) = find_field(entity_type, field).map_err(|e| e.to_string())?;
+    let text = match value {
+        serde_json::Value::Null => return Ok(Value::Null),
+        serde_json::Value::String(s) if s.trim().is_empty() => return Ok(Value::Null),
+        serde_json::Value::String(s) => s.trim().to_string(),
+        other => other.to_string(),
+    };
+    match kind {
+        FieldKind::Text => Ok(Value::Text(text)),
+        FieldKind::Integer => text
+            .replace(',', "")
+            .parse::<f64>()
+            .map(|n| Value::Integer(n.round() as i64))
+            .map_err(|_| format!("Expected a whole number, got '{}'", text)),
+        FieldKind::Real => text
+            .replace(',', "")
+            .parse::<f64>()
+            .map(Value::Real)
+            .map_err(|_| format!("Expected a number, got '{}'", text)),
+    }
+}
+
+// ============================================================================
+// Provenance
+// ============================================================================
+
+pub fn record_provenance(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+    field: &str,
+    source_type: FieldSourceType,
+    job_id: Option<&str>,
+    confidence: Option<f64>,
+) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO field_provenance
+            (entity_type, entity_id, field, source_type, job_id, confidence, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            entity_type,
+            entity_id,
+            field,
+            source_type.as_str(),
+            job_id,
+            confidence,
+            chrono::Utc::now().timestamp()
+        ],
+    )?;
+    Ok(())
+}
+
+/// Record `source_type` for every enrichment field that is set on a freshly inserted row
+pub fn record_inserted_fields(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+    source_type: FieldSourceType,
+    job_id: Option<&str>,
+) -> SqliteResult<()> {
+    let (table, fields) = entity_fields(entity_type)?;
+    for (key, column, _) in fields {
+        let is_set: bool = conn.query_row(
+            &format!("SELECT {} IS NOT NULL FROM {} WHERE id = ?1", column, table),
+            [entity_id],
+            |row| row.get(0),
+        )?;
+        if is_set {
+            record_provenance(conn, entity_type, entity_id, key, source_type, job_id, None)?;
+        }
+    }
+    Ok(())
+}
+
+fn provenance_from_row(row: &rusqlite::Row) -> SqliteResult<FieldProvenance> {
+    let source_type: String = row.get(1)?;
+    Ok(FieldProvenance {
+        field: row.get(0)?,
+        source_type: FieldSourceType::parse(&source_type).unwrap_or(FieldSourceType::Research),
+        job_id: row.get(2)?,
+        confidence: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+pub fn get_field_provenance(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+) -> SqliteResult<Vec<FieldProvenance>> {
+    let mut stmt = conn.prepare(
+        "SELECT field, source_type, job_id, confidence, updated_at FROM field_provenance
+         WHERE entity_type = ?1 AND entity_id = ?2 ORDER BY field",
+    )?;
+    let rows = stmt.query_map(params![entity_type, entity_id], provenance_from_row)?;
+    rows.collect()
+}
+
+/// Set one enrichment field by hand (or from an import) and record where it came from
+pub fn set_entity_field(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+    field: &str,
+    value: Value,
+    source_type: FieldSourceType,
+) -> SqliteResult<()> {
+    let (table, (_, column, _)) = find_field(entity_type, field)?;
+    let cleared = value == Value::Null;
+    let rows = conn.execute(
+        &format!("UPDATE {} SET {} = ?1 WHERE id = ?2", table, column),
+        params![value, entity_id],
+    )?;
+    if rows == 0 {
+        return Err(rusqlite::Error::QueryReturnedNoRows);
+    }
+    if cleared {
+        conn.execute(
+            "DELETE FROM field_provenance WHERE entity_type = ?1 AND entity_id = ?2 AND field = ?3",
+            params![entity_type, entity_id, field],
+        )?;
+        Ok(())
+    } else {
+        record_provenance(conn, entity_type, entity_id, field, source_type, None, None)
+    }
+}
+
+// ============================================================================
+// Merge Policies
+// ============================================================================
+
+pub fn get_merge_policy(conn: &Connection, entity_type: &str) -> SqliteResult<MergePolicySetting> {
+    let row: Option<(String, Option<f64>)> = conn
+        .query_row(
+            "SELECT policy, min_confidence FROM merge_policies WHERE entity_type = ?1",
+            [entity_type],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let (policy, min_confidence) = row
+        .map(|(p, c)| (MergePolicy::parse(&p).unwrap_or_default(), c))
+        .unwrap_or_default();
+    Ok(MergePolicySetting {
+        entity_type: entity_type.to_string(),
+        policy,
+        min_confidence,
+    })
+}
+
+pub fn get_merge_policies(conn: &Connection) -> SqliteResult<Vec<MergePolicySetting>> {
+    TAGGABLE_ENTITY_TYPES
+        .iter()
+        .map(|entity_type| get_merge_policy(conn, entity_type))
+        .collect()
+}
+
+pub fn set_merge_policy(conn: &Connection, setting: &MergePolicySetting) -> SqliteResult<()> {
+    entity_fields(&setting.entity_type)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO merge_policies (entity_type, policy, min_confidence)
+         VALUES (?1, ?2, ?3)",
+        params![
+            setting.entity_type,
+            setting.policy.as_str(),
+            setting.min_confidence
+        ],
+    )?;
+    Ok(())
+}
+
+/// Whether research may replace an existing value under `setting`
+fn may_overwrite(
+    setting: &MergePolicySetting,
+    existing: Option<&FieldProvenance>,
+    confidence: Option<f64>,
+) -> bool {
+    // Values without provenance predate tracking and may have been typed in by hand
+    let allowed = match (setting.policy, existing.map(|p| p.source_type)) {
+        (MergePolicy::FillEmpty, _) | (_, None) => false,
+        (MergePolicy::OverwriteResearch, Some(source)) => source == FieldSourceType::Research,
+        (MergePolicy::OverwriteUnlessUser, Some(source)) => source != FieldSourceType::User,
+    };
+    allowed
+        && setting
+            .min_confidence
+            .is_none_or(|min| confidence.is_some_and(|c| c >= min))
+}
+
+/// Apply research enrichment values (keyed by enrichment key) according to the
+/// entity type's merge policy. Returns the number of fields written.
+pub fn apply_enrichment(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+    values: &[(&str, Value)],
+    confidence: &HashMap<String, f64>,
+    job_id: Option<&str>,
+) -> SqliteResult<usize> {
+    let setting = get_merge_policy(conn, entity_type)?;
+    let provenance: HashMap<String, FieldProvenance> =
+        get_field_provenance(conn, entity_type, entity_id)?
+            .into_iter()
+            .map(|p| (p.field.clone(), p))
+            .collect();
+
+    let mut written = 0;
+    for (field, value) in values {
+        if *value == Value::Null {
+            continue;
+        }
+        let (table, (_, column, _)) = find_field(entity_type, field)?;
+        let current: Option<Value> = conn
+            .query_row(
+                &format!("SELECT {} FROM {} WHERE id = ?1", column, table),
+                [entity_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(current) = current else {
+            // Entity no longer exists
+            return Ok(written);
+        };
+
+        let field_confidence = confidence.get(*field).copied();
+        let write = current == Value::Null
+            || (current != *value
+                && may_overwrite(&setting, provenance.get(*field), field_confidence));
+        if write {
+            conn.execute(
+                &format!("UPDATE {} SET {} = ?1 WHERE id = ?2", table, column),
+                params![value, entity_id],
+            )?;
+            record_provenance(
+                conn,
+                entity_type,
+                entity_id,
+                field,
+                FieldSourceType::Research,
+                job_id,
+                field_confidence,
+            )?;
+            written += 1;
+        }
+    }
+    Ok(written)
+}
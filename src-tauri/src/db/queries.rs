@@ -1,6 +1,13 @@
+use crate::db::list_query::{
+    adjacent, count, decode_cursor, into_page, order_by, page_size, push_after_cursor,
+    sort_key_columns, sort_key_values, Conditions, SortKey,
+};
+use crate::db::provenance::apply_enrichment;
 use crate::db::schema::*;
-use crate::jobs::enrichment::{LeadEnrichment, PersonEnrichment};
-use rusqlite::{params, Connection, Result as SqliteResult};
+use crate::jobs::enrichment::{LeadEnrichment, PersonEnrichment, ResearchSource};
+use crate::redaction::Redaction;
+use rusqlite::types::Value;
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult};
 
 // ============================================================================
 // Lead Queries
@@ -86,32 +93,6 @@ pub fn get_all_leads(conn: &Connection) -> SqliteResult<Vec<Lead>> {
     rows.collect()
 }
 
-pub fn get_adjacent_leads(
-    conn: &Connection,
-    current_id: i64,
-) -> SqliteResult<(Option<i64>, Option<i64>, usize, usize)> {
-    let all_ids: Vec<i64> = conn
-        .prepare("SELECT id FROM leads ORDER BY company_name ASC")?
-        .query_map([], |row| row.get(0))?
-        .collect::<SqliteResult<Vec<_>>>()?;
-
-    let total = all_ids.len();
-    let current_index = all_ids.iter().position(|&id| id == current_id).unwrap_or(0);
-
-    let prev_id = if current_index > 0 {
-        Some(all_ids[current_index - 1])
-    } else {
-        None
-    };
-    let next_id = if current_index < total - 1 {
-        Some(all_ids[current_index + 1])
-    } else {
-        None
-    };
-
-    Ok((prev_id, next_id, current_index + 1, total))
-}
-
 pub fn insert_lead(conn: &Connection, data: &NewLead) -> SqliteResult<i64> {
     let now = chrono::Utc::now().timestamp();
     conn.execute(
@@ -145,6 +126,19 @@ pub fn delete_leads(conn: &Connection, lead_ids: &[i64]) -> SqliteResult<usize>
 
     let placeholders: String = lead_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
 
+    // Delete tags and custom field values of the leads and their people
+    let person_ids: Vec<i64> = conn
+        .prepare(&format!(
+            "SELECT id FROM people WHERE lead_id IN ({})",
+            placeholders
+        ))?
+        .query_map(rusqlite::params_from_iter(lead_ids.iter()), |row| {
+            row.get(0)
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    delete_entity_extras(conn, "lead", lead_ids)?;
+    delete_entity_extras(conn, "person", &person_ids)?;
+
     // Delete associated people
     conn.execute(
         &format!("DELETE FROM people WHERE lead_id IN ({})", placeholders),
@@ -160,6 +154,15 @@ pub fn delete_leads(conn: &Connection, lead_ids: &[i64]) -> SqliteResult<usize>
         rusqlite::params_from_iter(lead_ids.iter()),
     )?;
 
+    // Delete detected changes
+    conn.execute(
+        &format!(
+            "DELETE FROM lead_changes WHERE lead_id IN ({})",
+            placeholders
+        ),
+        rusqlite::params_from_iter(lead_ids.iter()),
+    )?;
+
     // Delete leads
     let deleted = conn.execute(
         &format!("DELETE FROM leads WHERE id IN ({})", placeholders),
@@ -292,73 +295,16 @@ pub fn get_people_for_lead(conn: &Connection, lead_id: i64) -> SqliteResult<Vec<
 }
 
 pub fn get_all_people(conn: &Connection) -> SqliteResult<Vec<PersonWithCompany>> {
-    let mut stmt = conn.prepare(
-        "SELECT p.id, p.lead_id, p.first_name, p.last_name, p.email, p.title, p.management_level,
-                p.linkedin_url, p.year_joined, p.person_profile, p.research_status, p.researched_at,
-                p.user_status, p.conversation_topics, p.conversation_generated_at, p.created_at,
-                l.company_name, l.website, l.industry
-         FROM people p
-         LEFT JOIN leads l ON p.lead_id = l.id
-         ORDER BY p.last_name ASC, p.first_name ASC",
-    )?;
-
-    let rows = stmt.query_map([], |row| {
-        Ok(PersonWithCompany {
-            id: row.get(0)?,
-            lead_id: row.get(1)?,
-            first_name: row.get(2)?,
-            last_name: row.get(3)?,
-            email: row.get(4)?,
-            title: row.get(5)?,
-            management_level: row.get(6)?,
-            linkedin_url: row.get(7)?,
-            year_joined: row.get(8)?,
-            person_profile: row.get(9)?,
-            research_status: row
-                .get::<_, Option<String>>(10)?
-                .unwrap_or_else(|| "pending".to_string()),
-            researched_at: row.get(11)?,
-            user_status: row
-                .get::<_, Option<String>>(12)?
-                .unwrap_or_else(|| "new".to_string()),
-            conversation_topics: row.get(13)?,
-            conversation_generated_at: row.get(14)?,
-            created_at: row.get(15)?,
-            company_name: row.get(16)?,
-            company_website: row.get(17)?,
-            company_industry: row.get(18)?,
-        })
-    })?;
+    let sql = format!(
+        "SELECT {} {} ORDER BY p.last_name ASC, p.first_name ASC",
+        PERSON_LIST_COLUMNS, PERSON_LIST_FROM
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([], person_with_company_from_row)?;
 
     rows.collect()
 }
 
-pub fn get_adjacent_people(
-    conn: &Connection,
-    current_id: i64,
-) -> SqliteResult<(Option<i64>, Option<i64>, usize, usize)> {
-    let all_ids: Vec<i64> = conn
-        .prepare("SELECT id FROM people ORDER BY last_name ASC, first_name ASC")?
-        .query_map([], |row| row.get(0))?
-        .collect::<SqliteResult<Vec<_>>>()?;
-
-    let total = all_ids.len();
-    let current_index = all_ids.iter().position(|&id| id == current_id).unwrap_or(0);
-
-    let prev_id = if current_index > 0 {
-        Some(all_ids[current_index - 1])
-    } else {
-        None
-    };
-    let next_id = if current_index < total - 1 {
-        Some(all_ids[current_index + 1])
-    } else {
-        None
-    };
-
-    Ok((prev_id, next_id, current_index + 1, total))
-}
-
 pub fn insert_person(conn: &Connection, data: &NewPerson) -> SqliteResult<i64> {
     let now = chrono::Utc::now().timestamp();
     conn.execute(
@@ -404,6 +350,7 @@ pub fn delete_people(conn: &Connection, person_ids: &[i64]) -> SqliteResult<usiz
     }
 
     let placeholders: String = person_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    delete_entity_extras(conn, "person", person_ids)?;
     let deleted = conn.execute(
         &format!("DELETE FROM people WHERE id IN ({})", placeholders),
         rusqlite::params_from_iter(person_ids.iter()),
@@ -576,15 +523,7 @@ pub fn get_lead_score(conn: &Connection, lead_id: i64) -> SqliteResult<Option<Pa
 }
 
 pub fn get_leads_with_scores(conn: &Connection) -> SqliteResult<Vec<LeadWithScore>> {
-    let leads = get_all_leads(conn)?;
-    let mut result = Vec::with_capacity(leads.len());
-
-    for lead in leads {
-        let score = get_lead_score(conn, lead.id)?;
-        result.push(LeadWithScore { lead, score });
-    }
-
-    Ok(result)
+    get_filtered_leads_with_scores(conn, &LeadFilter::default(), &[])
 }
 
 pub fn get_unscored_leads(conn: &Connection) -> SqliteResult<Vec<Lead>> {
@@ -679,177 +618,1655 @@ pub fn delete_lead_score(conn: &Connection, lead_id: i64) -> SqliteResult<()> {
 }
 
 // ============================================================================
-// Job Queries
+// Lead List Queries
 // ============================================================================
 
-pub fn insert_job(conn: &Connection, job: &NewJob) -> SqliteResult<String> {
-    let now = chrono::Utc::now().timestamp();
-    conn.execute(
-        "INSERT INTO jobs (id, job_type, entity_id, entity_label, status, prompt, model, working_dir, output_path, created_at)
-         VALUES (?1, ?2, ?3, ?4, 'queued', ?5, ?6, ?7, ?8, ?9)",
-        params![
-            job.id,
-            job.job_type,
-            job.entity_id,
-            job.entity_label,
-            job.prompt,
-            job.model,
-            job.working_dir,
-            job.output_path,
-            now
-        ],
-    )?;
-    Ok(job.id.clone())
+/// Leads joined with their latest score, so one statement serves the whole list
+const LEAD_LIST_FROM: &str = "FROM leads l
+     LEFT JOIN lead_scores ls
+       ON ls.id = (SELECT MAX(s.id) FROM lead_scores s WHERE s.lead_id = l.id)";
+
+const LEAD_LIST_COLUMNS: &str =
+    "l.id, l.company_name, l.website, l.industry, l.sub_industry, l.employees, l.employee_range,
+     l.revenue, l.revenue_range, l.company_linkedin_url, l.city, l.state, l.country,
+     l.research_status, l.researched_at, l.user_status, l.created_at, l.company_profile, l.notes,
+     ls.id, ls.lead_id, ls.config_id, ls.passes_requirements, ls.requirement_results,
+     ls.total_score, ls.score_breakdown, ls.tier, ls.scoring_notes, ls.scored_at, ls.created_at";
+
+/// Number of columns in LEAD_LIST_COLUMNS (sort keys are selected after them)
+const LEAD_LIST_COLUMN_COUNT: usize = 30;
+
+fn lead_from_row(row: &rusqlite::Row) -> SqliteResult<Lead> {
+    Ok(Lead {
+        id: row.get(0)?,
+        company_name: row.get(1)?,
+        website: row.get(2)?,
+        industry: row.get(3)?,
+        sub_industry: row.get(4)?,
+        employees: row.get(5)?,
+        employee_range: row.get(6)?,
+        revenue: row.get(7)?,
+        revenue_range: row.get(8)?,
+        company_linkedin_url: row.get(9)?,
+        city: row.get(10)?,
+        state: row.get(11)?,
+        country: row.get(12)?,
+        research_status: row
+            .get::<_, Option<String>>(13)?
+            .unwrap_or_else(|| "pending".to_string()),
+        researched_at: row.get(14)?,
+        user_status: row
+            .get::<_, Option<String>>(15)?
+            .unwrap_or_else(|| "new".to_string()),
+        created_at: row.get(16)?,
+        company_profile: row.get(17)?,
+        notes: row.get(18)?,
+    })
 }
 
-pub fn update_job_status(
-    conn: &Connection,
-    job_id: &str,
-    status: &str,
-    exit_code: Option<i32>,
-    error_message: Option<&str>,
-) -> SqliteResult<()> {
-    let now = chrono::Utc::now().timestamp();
+/// Read the score columns starting at index 19 (NULL id means unscored)
+fn lead_score_from_row(row: &rusqlite::Row) -> SqliteResult<Option<ParsedLeadScore>> {
+    let Some(id) = row.get::<_, Option<i64>>(19)? else {
+        return Ok(None);
+    };
+    let req_results: String = row.get(23)?;
+    let score_breakdown: String = row.get(25)?;
+
+    Ok(Some(ParsedLeadScore {
+        id,
+        lead_id: row.get(20)?,
+        config_id: row.get(21)?,
+        passes_requirements: row.get(22)?,
+        requirement_results: serde_json::from_str(&req_results)
+            .unwrap_or(serde_json::Value::Array(vec![])),
+        total_score: row.get(24)?,
+        score_breakdown: serde_json::from_str(&score_breakdown)
+            .unwrap_or(serde_json::Value::Array(vec![])),
+        tier: row.get(26)?,
+        scoring_notes: row.get(27)?,
+        scored_at: row.get(28)?,
+        created_at: row.get(29)?,
+    }))
+}
 
-    // If transitioning to running, set started_at
-    // If transitioning to completed/error/timeout/cancelled, set completed_at
-    match status {
-        "running" => {
-            conn.execute(
-                "UPDATE jobs SET status = ?1, started_at = ?2 WHERE id = ?3",
-                params![status, now, job_id],
-            )?;
-        }
-        "completed" | "error" | "timeout" | "cancelled" => {
-            conn.execute(
-                "UPDATE jobs SET status = ?1, exit_code = ?2, error_message = ?3, completed_at = ?4 WHERE id = ?5",
-                params![status, exit_code, error_message, now, job_id],
-            )?;
+fn lead_sort_keys(sort: &[LeadSort]) -> Vec<SortKey> {
+    let mut keys: Vec<SortKey> = sort
+        .iter()
+        .map(|s| SortKey {
+            expr: match s.field {
+                LeadSortField::CompanyName => "l.company_name",
+                LeadSortField::Industry => "COALESCE(l.industry, '')",
+                LeadSortField::Country => "COALESCE(l.country, '')",
+                LeadSortField::Employees => "COALESCE(l.employees, -1)",
+                LeadSortField::Revenue => "COALESCE(l.revenue, -1)",
+                LeadSortField::ResearchStatus => "COALESCE(l.research_status, 'pending')",
+                LeadSortField::UserStatus => "COALESCE(l.user_status, 'new')",
+                LeadSortField::CreatedAt => "l.created_at",
+                LeadSortField::ResearchedAt => "COALESCE(l.researched_at, 0)",
+                LeadSortField::Score => "COALESCE(ls.total_score, -1)",
+            },
+            direction: s.direction,
+        })
+        .collect();
+
+    if keys.is_empty() {
+        keys.push(SortKey {
+            expr: "l.company_name",
+            direction: SortDirection::Asc,
+        });
+    }
+    // Unique tiebreaker keeps the order (and therefore cursors) stable
+    keys.push(SortKey {
+        expr: "l.id",
+        direction: SortDirection::Asc,
+    });
+    keys
+}
+
+fn lead_filter_conditions(conn: &Connection, filter: &LeadFilter) -> SqliteResult<Conditions> {
+    let mut conds = Conditions::default();
+
+    if let Some(fts) = filter.search.as_deref().and_then(build_fts_query) {
+        conds.push(
+            "l.id IN (SELECT rowid FROM leads_fts WHERE leads_fts MATCH ?)",
+            [Value::Text(fts)],
+        );
+    }
+    conds.push_in("l.industry", &filter.industries);
+    conds.push_in("l.country", &filter.countries);
+    conds.push_in("l.employee_range", &filter.employee_ranges);
+    conds.push_in(
+        "COALESCE(l.research_status, 'pending')",
+        &filter.research_statuses,
+    );
+    conds.push_in("COALESCE(l.user_status, 'new')", &filter.user_statuses);
+    if let Some(min) = filter.employees_min {
+        conds.push("l.employees >= ?", [Value::Integer(min)]);
+    }
+    if let Some(max) = filter.employees_max {
+        conds.push("l.employees <= ?", [Value::Integer(max)]);
+    }
+    if let Some(min) = filter.score_min {
+        conds.push("ls.total_score >= ?", [Value::Integer(min)]);
+    }
+    if let Some(max) = filter.score_max {
+        conds.push("ls.total_score <= ?", [Value::Integer(max)]);
+    }
+    if !filter.tiers.is_empty() {
+        let tiers: Vec<String> = filter
+            .tiers
+            .iter()
+            .filter(|t| t.as_str() != "unscored")
+            .cloned()
+            .collect();
+        let mut alternatives = Vec::new();
+        if !tiers.is_empty() {
+            alternatives.push(format!(
+                "ls.tier IN ({})",
+                vec!["?"; tiers.len()].join(", ")
+            ));
         }
-        _ => {
-            conn.execute(
-                "UPDATE jobs SET status = ?1 WHERE id = ?2",
-                params![status, job_id],
-            )?;
+        if tiers.len() < filter.tiers.len() {
+            alternatives.push("ls.id IS NULL".to_string());
         }
+        conds.push(
+            format!("({})", alternatives.join(" OR ")),
+            tiers.into_iter().map(Value::Text),
+        );
     }
-    Ok(())
-}
+    match filter.has_people {
+        Some(true) => conds.push("EXISTS (SELECT 1 FROM people p WHERE p.lead_id = l.id)", []),
+        Some(false) => conds.push(
+            "NOT EXISTS (SELECT 1 FROM people p WHERE p.lead_id = l.id)",
+            [],
+        ),
+        None => {}
+    }
+    push_tag_conditions(&mut conds, "lead", "l.id", &filter.tag_ids);
+    push_custom_field_conditions(conn, &mut conds, "lead", "l.id", &filter.custom_fields)?;
 
-pub fn update_job_pid(conn: &Connection, job_id: &str, pid: u32) -> SqliteResult<()> {
-    conn.execute(
-        "UPDATE jobs SET pid = ?1 WHERE id = ?2",
-        params![pid as i64, job_id],
-    )?;
-    Ok(())
+    Ok(conds)
 }
 
-pub fn update_job_claude_session(
+/// Run the lead list query, pairing each row with its sort key values.
+/// `limit` of None returns every matching row.
+fn select_leads_with_scores(
     conn: &Connection,
-    job_id: &str,
-    session_id: &str,
-    model: &str,
-) -> SqliteResult<()> {
-    conn.execute(
-        "UPDATE jobs SET claude_session_id = ?1, claude_model = ?2 WHERE id = ?3",
-        params![session_id, model, job_id],
-    )?;
-    Ok(())
+    keys: &[SortKey],
+    conds: &Conditions,
+    limit: Option<i64>,
+) -> SqliteResult<Vec<(LeadWithScore, Vec<Value>)>> {
+    let mut sql = format!(
+        "SELECT {}, {} {} WHERE {} ORDER BY {}",
+        LEAD_LIST_COLUMNS,
+        sort_key_columns(keys),
+        LEAD_LIST_FROM,
+        conds.sql(),
+        order_by(keys)
+    );
+    let mut params = conds.params.clone();
+    if let Some(limit) = limit {
+        sql.push_str(" LIMIT ?");
+        params.push(Value::Integer(limit));
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+        let lead = LeadWithScore {
+            lead: lead_from_row(row)?,
+            score: lead_score_from_row(row)?,
+        };
+        Ok((lead, sort_key_values(row, LEAD_LIST_COLUMN_COUNT, keys)?))
+    })?;
+
+    rows.collect()
 }
 
-#[allow(dead_code)] // API function for job event index tracking
-pub fn update_job_last_event_index(
+/// Filtered, sorted, cursor-paginated lead list with scores
+pub fn query_leads(conn: &Connection, query: &LeadQuery) -> SqliteResult<Page<LeadWithScore>> {
+    let keys = lead_sort_keys(&query.sort);
+    let limit = page_size(query.limit);
+
+    let mut conds = lead_filter_conditions(conn, &query.filter)?;
+    let total = count(conn, LEAD_LIST_FROM, &conds)?;
+    if let Some(cursor) = &query.cursor {
+        push_after_cursor(&mut conds, &keys, &decode_cursor(cursor, keys.len())?);
+    }
+
+    // Fetch one extra row to learn whether another page exists
+    let rows = select_leads_with_scores(conn, &keys, &conds, Some(limit + 1))?;
+    Ok(into_page(rows, limit, total))
+}
+
+/// Previous/next lead ids, 1-based position and total within the filtered,
+/// sorted list (company name order when no sort is given)
+pub fn get_adjacent_leads(
     conn: &Connection,
-    job_id: &str,
-    index: i64,
-) -> SqliteResult<()> {
-    conn.execute(
-        "UPDATE jobs SET last_event_index = ?1 WHERE id = ?2",
-        params![index, job_id],
-    )?;
-    Ok(())
+    current_id: i64,
+    filter: &LeadFilter,
+    sort: &[LeadSort],
+) -> SqliteResult<(Option<i64>, Option<i64>, usize, usize)> {
+    let keys = lead_sort_keys(sort);
+    let conds = lead_filter_conditions(conn, filter)?;
+    adjacent(conn, LEAD_LIST_FROM, "l.id", &keys, &conds, current_id)
 }
 
-pub fn update_job_stream_stats(
+/// Number of leads matching a filter
+pub fn count_leads(conn: &Connection, filter: &LeadFilter) -> SqliteResult<i64> {
+    count(conn, LEAD_LIST_FROM, &lead_filter_conditions(conn, filter)?)
+}
+
+/// Ids of every lead matching a filter, in list order (for bulk actions)
+pub fn get_lead_ids(
     conn: &Connection,
-    job_id: &str,
-    stdout_truncated: bool,
-    stderr_truncated: bool,
-    total_stdout_bytes: i64,
-    total_stderr_bytes: i64,
-) -> SqliteResult<()> {
-    conn.execute(
-        "UPDATE jobs SET stdout_truncated = ?1, stderr_truncated = ?2, total_stdout_bytes = ?3, total_stderr_bytes = ?4 WHERE id = ?5",
-        params![stdout_truncated as i64, stderr_truncated as i64, total_stdout_bytes, total_stderr_bytes, job_id],
-    )?;
-    Ok(())
+    filter: &LeadFilter,
+    sort: &[LeadSort],
+) -> SqliteResult<Vec<i64>> {
+    let keys = lead_sort_keys(sort);
+    let conds = lead_filter_conditions(conn, filter)?;
+    let sql = format!(
+        "SELECT l.id {} WHERE {} ORDER BY {}",
+        LEAD_LIST_FROM,
+        conds.sql(),
+        order_by(&keys)
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(&conds.params), |row| row.get(0))?;
+
+    rows.collect()
 }
 
-pub fn update_job_completion_state(
+/// Every lead matching a filter with its score, in list order
+pub fn get_filtered_leads_with_scores(
     conn: &Connection,
-    job_id: &str,
-    state: Option<&str>,
-) -> SqliteResult<()> {
-    conn.execute(
-        "UPDATE jobs SET completion_state = ?1 WHERE id = ?2",
-        params![state, job_id],
-    )?;
-    Ok(())
+    filter: &LeadFilter,
+    sort: &[LeadSort],
+) -> SqliteResult<Vec<LeadWithScore>> {
+    let keys = lead_sort_keys(sort);
+    let conds = lead_filter_conditions(conn, filter)?;
+    let rows = select_leads_with_scores(conn, &keys, &conds, None)?;
+    Ok(rows.into_iter().map(|(lead, _)| lead).collect())
 }
 
-pub fn get_job(conn: &Connection, job_id: &str) -> SqliteResult<Option<Job>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, job_type, entity_id, entity_label, status, prompt, model, working_dir,
-                output_path, exit_code, error_message, created_at, started_at, completed_at,
-                pid, claude_session_id, claude_model, last_event_index,
-                stdout_truncated, stderr_truncated, total_stdout_bytes, total_stderr_bytes, completion_state
-         FROM jobs WHERE id = ?1"
-    )?;
+// ============================================================================
+// Person List Queries
+// ============================================================================
 
-    let mut rows = stmt.query(params![job_id])?;
+const PERSON_LIST_FROM: &str = "FROM people p LEFT JOIN leads l ON l.id = p.lead_id";
+
+const PERSON_LIST_COLUMNS: &str =
+    "p.id, p.lead_id, p.first_name, p.last_name, p.email, p.title, p.management_level,
+     p.linkedin_url, p.year_joined, p.person_profile, p.research_status, p.researched_at,
+     p.user_status, p.conversation_topics, p.conversation_generated_at, p.created_at,
+     l.company_name, l.website, l.industry";
+
+/// Number of columns in PERSON_LIST_COLUMNS (sort keys are selected after them)
+const PERSON_LIST_COLUMN_COUNT: usize = 19;
+
+fn person_sort_keys(sort: &[PersonSort]) -> Vec<SortKey> {
+    let mut keys: Vec<SortKey> = sort
+        .iter()
+        .map(|s| SortKey {
+            expr: match s.field {
+                PersonSortField::LastName => "p.last_name",
+                PersonSortField::FirstName => "p.first_name",
+                PersonSortField::Title => "COALESCE(p.title, '')",
+                PersonSortField::CompanyName => "COALESCE(l.company_name, '')",
+                PersonSortField::ResearchStatus => "COALESCE(p.research_status, 'pending')",
+                PersonSortField::UserStatus => "COALESCE(p.user_status, 'new')",
+                PersonSortField::CreatedAt => "p.created_at",
+                PersonSortField::ResearchedAt => "COALESCE(p.researched_at, 0)",
+            },
+            direction: s.direction,
+        })
+        .collect();
 
-    if let Some(row) = rows.next()? {
-        Ok(Some(Job {
-            id: row.get(0)?,
-            job_type: row.get(1)?,
-            entity_id: row.get(2)?,
-            entity_label: row.get(3)?,
-            status: row.get(4)?,
-            prompt: row.get(5)?,
-            model: row.get(6)?,
-            working_dir: row.get(7)?,
-            output_path: row.get(8)?,
-            exit_code: row.get(9)?,
-            error_message: row.get(10)?,
-            created_at: row.get(11)?,
-            started_at: row.get(12)?,
-            completed_at: row.get(13)?,
-            pid: row.get(14)?,
-            claude_session_id: row.get(15)?,
-            claude_model: row.get(16)?,
-            last_event_index: row.get::<_, Option<i64>>(17)?.unwrap_or(0),
-            stdout_truncated: row.get::<_, Option<i64>>(18)?.unwrap_or(0) != 0,
-            stderr_truncated: row.get::<_, Option<i64>>(19)?.unwrap_or(0) != 0,
-            total_stdout_bytes: row.get::<_, Option<i64>>(20)?.unwrap_or(0),
-            total_stderr_bytes: row.get::<_, Option<i64>>(21)?.unwrap_or(0),
-            completion_state: row.get(22)?,
-        }))
-    } else {
-        Ok(None)
+    if keys.is_empty() {
+        for expr in ["p.last_name", "p.first_name"] {
+            keys.push(SortKey {
+                expr,
+                direction: SortDirection::Asc,
+            });
+        }
     }
+    keys.push(SortKey {
+        expr: "p.id",
+        direction: SortDirection::Asc,
+    });
+    keys
 }
 
-/// Get active job for a specific entity and job type (used to prevent duplicate jobs)
-pub fn get_active_job_for_entity(
-    conn: &Connection,
-    entity_id: i64,
-    job_type: &str,
-) -> SqliteResult<Option<Job>> {
+fn person_filter_conditions(conn: &Connection, filter: &PersonFilter) -> SqliteResult<Conditions> {
+    let mut conds = Conditions::default();
+
+    if let Some(fts) = filter.search.as_deref().and_then(build_fts_query) {
+        conds.push(
+            "p.id IN (SELECT rowid FROM people_fts WHERE people_fts MATCH ?)",
+            [Value::Text(fts)],
+        );
+    }
+    if let Some(lead_id) = filter.lead_id {
+        conds.push("p.lead_id = ?", [Value::Integer(lead_id)]);
+    }
+    conds.push_in("l.industry", &filter.industries);
+    conds.push_in("p.management_level", &filter.management_levels);
+    conds.push_in(
+        "COALESCE(p.research_status, 'pending')",
+        &filter.research_statuses,
+    );
+    conds.push_in("COALESCE(p.user_status, 'new')", &filter.user_statuses);
+    match filter.has_conversation_topics {
+        Some(true) => conds.push("COALESCE(p.conversation_topics, '') != ''", []),
+        Some(false) => conds.push("COALESCE(p.conversation_topics, '') = ''", []),
+        None => {}
+    }
+    push_tag_conditions(&mut conds, "person", "p.id", &filter.tag_ids);
+    push_custom_field_conditions(conn, &mut conds, "person", "p.id", &filter.custom_fields)?;
+
+    Ok(conds)
+}
+
+fn person_with_company_from_row(row: &rusqlite::Row) -> SqliteResult<PersonWithCompany> {
+    Ok(PersonWithCompany {
+        id: row.get(0)?,
+        lead_id: row.get(1)?,
+        first_name: row.get(2)?,
+        last_name: row.get(3)?,
+        email: row.get(4)?,
+        title: row.get(5)?,
+        management_level: row.get(6)?,
+        linkedin_url: row.get(7)?,
+        year_joined: row.get(8)?,
+        person_profile: row.get(9)?,
+        research_status: row
+            .get::<_, Option<String>>(10)?
+            .unwrap_or_else(|| "pending".to_string()),
+        researched_at: row.get(11)?,
+        user_status: row
+            .get::<_, Option<String>>(12)?
+            .unwrap_or_else(|| "new".to_string()),
+        conversation_topics: row.get(13)?,
+        conversation_generated_at: row.get(14)?,
+        created_at: row.get(15)?,
+        company_name: row.get(16)?,
+        company_website: row.get(17)?,
+        company_industry: row.get(18)?,
+    })
+}
+
+/// Filtered, sorted, cursor-paginated people list with company info
+pub fn query_people(
+    conn: &Connection,
+    query: &PersonQuery,
+) -> SqliteResult<Page<PersonWithCompany>> {
+    let keys = person_sort_keys(&query.sort);
+    let limit = page_size(query.limit);
+
+    let mut conds = person_filter_conditions(conn, &query.filter)?;
+    let total = count(conn, PERSON_LIST_FROM, &conds)?;
+    if let Some(cursor) = &query.cursor {
+        push_after_cursor(&mut conds, &keys, &decode_cursor(cursor, keys.len())?);
+    }
+
+    let sql = format!(
+        "SELECT {}, {} {} WHERE {} ORDER BY {} LIMIT ?",
+        PERSON_LIST_COLUMNS,
+        sort_key_columns(&keys),
+        PERSON_LIST_FROM,
+        conds.sql(),
+        order_by(&keys)
+    );
+    let mut params = conds.params;
+    params.push(Value::Integer(limit + 1));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(params), |row| {
+            Ok((
+                person_with_company_from_row(row)?,
+                sort_key_values(row, PERSON_LIST_COLUMN_COUNT, &keys)?,
+            ))
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(into_page(rows, limit, total))
+}
+
+/// Previous/next person ids, 1-based position and total within the filtered,
+/// sorted list (last name, first name order when no sort is given)
+pub fn get_adjacent_people(
+    conn: &Connection,
+    current_id: i64,
+    filter: &PersonFilter,
+    sort: &[PersonSort],
+) -> SqliteResult<(Option<i64>, Option<i64>, usize, usize)> {
+    let keys = person_sort_keys(sort);
+    let conds = person_filter_conditions(conn, filter)?;
+    adjacent(conn, PERSON_LIST_FROM, "p.id", &keys, &conds, current_id)
+}
+
+// ============================================================================
+// Saved View Queries
+// ============================================================================
+
+/// Build a view on its own. A filter or sort that no longer parses, or one
+/// that no longer applies (e.g. a deleted custom field), is reported in
+/// `error` rather than failing the whole list or defaulting to every lead.
+fn saved_view_from_row(conn: &Connection, row: &rusqlite::Row) -> SqliteResult<SavedView> {
+    let filter: String = row.get(2)?;
+    let sort: String = row.get(3)?;
+    let parsed = serde_json::from_str::<LeadFilter>(&filter)
+        .map_err(|e| format!("Filter no longer parses: {}", e))
+        .and_then(|filter| {
+            serde_json::from_str::<Vec<LeadSort>>(&sort)
+                .map(|sort| (filter, sort))
+                .map_err(|e| format!("Sort no longer parses: {}", e))
+        });
+    let (filter, sort, count, error) = match parsed {
+        Ok((filter, sort)) => match count_leads(conn, &filter) {
+            Ok(count) => (filter, sort, Some(count), None),
+            Err(e) => (filter, sort, None, Some(e.to_string())),
+        },
+        Err(e) => (LeadFilter::default(), Vec::new(), None, Some(e)),
+    };
+
+    Ok(SavedView {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        filter,
+        sort,
+        count,
+        error,
+        pinned: row.get(4)?,
+        position: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+/// All saved views with live counts, pinned first then by position
+pub fn get_saved_views(conn: &Connection) -> SqliteResult<Vec<SavedView>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, filter, sort, pinned, position, created_at, updated_at
+         FROM saved_views ORDER BY pinned DESC, position ASC, name ASC",
+    )?;
+    let rows = stmt.query_map([], |row| saved_view_from_row(conn, row))?;
+
+    rows.collect()
+}
+
+pub fn get_pinned_saved_views(conn: &Connection) -> SqliteResult<Vec<SavedView>> {
+    Ok(get_saved_views(conn)?
+        .into_iter()
+        .filter(|view| view.pinned)
+        .collect())
+}
+
+pub fn get_saved_view(conn: &Connection, id: i64) -> SqliteResult<Option<SavedView>> {
+    conn.query_row(
+        "SELECT id, name, filter, sort, pinned, position, created_at, updated_at
+         FROM saved_views WHERE id = ?1",
+        [id],
+        |row| saved_view_from_row(conn, row),
+    )
+    .optional()
+}
+
+pub fn insert_saved_view(conn: &Connection, data: &NewSavedView) -> SqliteResult<i64> {
+    let now = chrono::Utc::now().timestamp();
+    let filter = serde_json::to_string(&data.filter).unwrap_or_else(|_| "{}".to_string());
+    let sort = serde_json::to_string(&data.sort).unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        "INSERT INTO saved_views (name, filter, sort, pinned, position, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, (SELECT COALESCE(MAX(position), -1) + 1 FROM saved_views), ?5, ?5)",
+        params![data.name, filter, sort, data.pinned, now],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_saved_view(conn: &Connection, id: i64, data: &NewSavedView) -> SqliteResult<()> {
+    let now = chrono::Utc::now().timestamp();
+    let filter = serde_json::to_string(&data.filter).unwrap_or_else(|_| "{}".to_string());
+    let sort = serde_json::to_string(&data.sort).unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        "UPDATE saved_views SET name = ?1, filter = ?2, sort = ?3, pinned = ?4, updated_at = ?5
+         WHERE id = ?6",
+        params![data.name, filter, sort, data.pinned, now, id],
+    )?;
+    Ok(())
+}
+
+pub fn set_saved_view_pinned(conn: &Connection, id: i64, pinned: bool) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE saved_views SET pinned = ?1, updated_at = ?2 WHERE id = ?3",
+        params![pinned, chrono::Utc::now().timestamp(), id],
+    )?;
+    Ok(())
+}
+
+/// Persist a new display order: each id gets its index in `ids` as position
+pub fn reorder_saved_views(conn: &Connection, ids: &[i64]) -> SqliteResult<()> {
+    let tx = conn.unchecked_transaction()?;
+    for (position, id) in ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE saved_views SET position = ?1 WHERE id = ?2",
+            params![position as i64, id],
+        )?;
+    }
+    tx.commit()
+}
+
+pub fn delete_saved_view(conn: &Connection, id: i64) -> SqliteResult<()> {
+    conn.execute("DELETE FROM saved_views WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+// ============================================================================
+// Tag Queries
+// ============================================================================
+
+fn check_entity_type(entity_type: &str) -> SqliteResult<()> {
+    if TAGGABLE_ENTITY_TYPES.contains(&entity_type) {
+        Ok(())
+    } else {
+        Err(rusqlite::Error::InvalidParameterName(format!(
+            "Unknown entity type: {}",
+            entity_type
+        )))
+    }
+}
+
+/// Remove tag links, custom field values and research history of deleted entities
+fn delete_entity_extras(conn: &Connection, entity_type: &str, ids: &[i64]) -> SqliteResult<()> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let placeholders = vec!["?"; ids.len()].join(",");
+    let mut params: Vec<Value> = vec![Value::Text(entity_type.to_string())];
+    params.extend(ids.iter().map(|&id| Value::Integer(id)));
+
+    conn.execute(
+        &format!(
+            "DELETE FROM entity_tags WHERE entity_type = ? AND entity_id IN ({})",
+            placeholders
+        ),
+        rusqlite::params_from_iter(&params),
+    )?;
+    conn.execute(
+        &format!(
+            "DELETE FROM custom_field_values
+             WHERE field_id IN (SELECT id FROM custom_fields WHERE entity_type = ?)
+               AND entity_id IN ({})",
+            placeholders
+        ),
+        rusqlite::params_from_iter(&params),
+    )?;
+    conn.execute(
+        &format!(
+            "DELETE FROM research_runs WHERE entity_type = ? AND entity_id IN ({})",
+            placeholders
+        ),
+        rusqlite::params_from_iter(&params),
+    )?;
+    conn.execute(
+        &format!(
+            "DELETE FROM source_fields WHERE source_id IN
+             (SELECT id FROM sources WHERE entity_type = ? AND entity_id IN ({}))",
+            placeholders
+        ),
+        rusqlite::params_from_iter(&params),
+    )?;
+    conn.execute(
+        &format!(
+            "DELETE FROM sources WHERE entity_type = ? AND entity_id IN ({})",
+            placeholders
+        ),
+        rusqlite::params_from_iter(&params),
+    )?;
+    conn.execute(
+        &format!(
+            "DELETE FROM field_provenance WHERE entity_type = ? AND entity_id IN ({})",
+            placeholders
+        ),
+        rusqlite::params_from_iter(&params),
+    )?;
+    Ok(())
+}
+
+/// Entity must carry every tag in `tag_ids`
+fn push_tag_conditions(conds: &mut Conditions, entity_type: &str, id_expr: &str, tag_ids: &[i64]) {
+    for &tag_id in tag_ids {
+        conds.push(
+            format!(
+                "EXISTS (SELECT 1 FROM entity_tags et
+                         WHERE et.entity_type = ? AND et.entity_id = {} AND et.tag_id = ?)",
+                id_expr
+            ),
+            [Value::Text(entity_type.to_string()), Value::Integer(tag_id)],
+        );
+    }
+}
+
+pub fn get_tags(conn: &Connection) -> SqliteResult<Vec<Tag>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.id, t.name, t.color, t.created_at,
+                (SELECT COUNT(*) FROM entity_tags et WHERE et.tag_id = t.id)
+         FROM tags t ORDER BY t.name COLLATE NOCASE",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Tag {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            color: row.get(2)?,
+            created_at: row.get(3)?,
+            usage_count: row.get(4)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+pub fn get_entity_tags(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+) -> SqliteResult<Vec<Tag>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.id, t.name, t.color, t.created_at,
+                (SELECT COUNT(*) FROM entity_tags c WHERE c.tag_id = t.id)
+         FROM tags t
+         JOIN entity_tags et ON et.tag_id = t.id
+         WHERE et.entity_type = ?1 AND et.entity_id = ?2
+         ORDER BY t.name COLLATE NOCASE",
+    )?;
+    let rows = stmt.query_map(params![entity_type, entity_id], |row| {
+        Ok(Tag {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            color: row.get(2)?,
+            created_at: row.get(3)?,
+            usage_count: row.get(4)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Tag names per entity id for one entity type (used by exports)
+pub fn get_tag_names_by_entity(
+    conn: &Connection,
+    entity_type: &str,
+) -> SqliteResult<std::collections::HashMap<i64, Vec<String>>> {
+    let mut stmt = conn.prepare(
+        "SELECT et.entity_id, t.name FROM entity_tags et
+         JOIN tags t ON t.id = et.tag_id
+         WHERE et.entity_type = ?1
+         ORDER BY t.name COLLATE NOCASE",
+    )?;
+    let mut map: std::collections::HashMap<i64, Vec<String>> = std::collections::HashMap::new();
+    let rows = stmt.query_map([entity_type], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get(1)?))
+    })?;
+    for row in rows {
+        let (entity_id, name) = row?;
+        map.entry(entity_id).or_default().push(name);
+    }
+    Ok(map)
+}
+
+/// Create a tag, or return the id of the existing tag with the same name
+pub fn insert_tag(conn: &Connection, name: &str, color: Option<&str>) -> SqliteResult<i64> {
+    conn.execute(
+        "INSERT INTO tags (name, color, created_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(name) DO NOTHING",
+        params![name.trim(), color, chrono::Utc::now().timestamp()],
+    )?;
+    conn.query_row(
+        "SELECT id FROM tags WHERE name = ?1",
+        [name.trim()],
+        |row| row.get(0),
+    )
+}
+
+pub fn update_tag(conn: &Connection, id: i64, name: &str, color: Option<&str>) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE tags SET name = ?1, color = ?2 WHERE id = ?3",
+        params![name.trim(), color, id],
+    )?;
+    Ok(())
+}
+
+pub fn delete_tag(conn: &Connection, id: i64) -> SqliteResult<()> {
+    conn.execute("DELETE FROM entity_tags WHERE tag_id = ?1", [id])?;
+    conn.execute("DELETE FROM tags WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+/// Replace the full tag set of one entity
+pub fn set_entity_tags(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+    tag_ids: &[i64],
+) -> SqliteResult<()> {
+    check_entity_type(entity_type)?;
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "DELETE FROM entity_tags WHERE entity_type = ?1 AND entity_id = ?2",
+        params![entity_type, entity_id],
+    )?;
+    for tag_id in tag_ids {
+        tx.execute(
+            "INSERT OR IGNORE INTO entity_tags (tag_id, entity_type, entity_id) VALUES (?1, ?2, ?3)",
+            params![tag_id, entity_type, entity_id],
+        )?;
+    }
+    tx.commit()
+}
+
+/// Add (or remove) one tag on many entities at once
+pub fn tag_entities(
+    conn: &Connection,
+    tag_id: i64,
+    entity_type: &str,
+    entity_ids: &[i64],
+    add: bool,
+) -> SqliteResult<()> {
+    check_entity_type(entity_type)?;
+    let tx = conn.unchecked_transaction()?;
+    let sql = if add {
+        "INSERT OR IGNORE INTO entity_tags (tag_id, entity_type, entity_id) VALUES (?1, ?2, ?3)"
+    } else {
+        "DELETE FROM entity_tags WHERE tag_id = ?1 AND entity_type = ?2 AND entity_id = ?3"
+    };
+    for entity_id in entity_ids {
+        tx.execute(sql, params![tag_id, entity_type, entity_id])?;
+    }
+    tx.commit()
+}
+
+// ============================================================================
+// Custom Field Queries
+// ============================================================================
+
+fn custom_field_from_row(row: &rusqlite::Row) -> SqliteResult<CustomField> {
+    let field_type: String = row.get(4)?;
+    let options: String = row.get(5)?;
+    Ok(CustomField {
+        id: row.get(0)?,
+        entity_type: row.get(1)?,
+        key: row.get(2)?,
+        label: row.get(3)?,
+        field_type: CustomFieldType::parse(&field_type).unwrap_or(CustomFieldType::Text),
+        options: serde_json::from_str(&options).unwrap_or_default(),
+        research_fillable: row.get(6)?,
+        description: row.get(7)?,
+        position: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+/// Custom field definitions, optionally for one entity type, in display order
+pub fn get_custom_fields(
+    conn: &Connection,
+    entity_type: Option<&str>,
+) -> SqliteResult<Vec<CustomField>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, entity_type, key, label, field_type, options, research_fillable,
+                description, position, created_at
+         FROM custom_fields
+         WHERE ?1 IS NULL OR entity_type = ?1
+         ORDER BY entity_type, position, id",
+    )?;
+    let rows = stmt.query_map([entity_type], custom_field_from_row)?;
+
+    rows.collect()
+}
+
+pub fn get_custom_field(conn: &Connection, id: i64) -> SqliteResult<Option<CustomField>> {
+    conn.query_row(
+        "SELECT id, entity_type, key, label, field_type, options, research_fillable,
+                description, position, created_at
+         FROM custom_fields WHERE id = ?1",
+        [id],
+        custom_field_from_row,
+    )
+    .optional()
+}
+
+pub fn insert_custom_field(conn: &Connection, data: &NewCustomField) -> SqliteResult<i64> {
+    check_entity_type(&data.entity_type)?;
+    let options = serde_json::to_string(&data.options).unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        "INSERT INTO custom_fields (entity_type, key, label, field_type, options, research_fillable,
+                                    description, position, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7,
+                 (SELECT COALESCE(MAX(position), -1) + 1 FROM custom_fields WHERE entity_type = ?1),
+                 ?8)",
+        params![
+            data.entity_type,
+            data.key,
+            data.label,
+            data.field_type.as_str(),
+            options,
+            data.research_fillable,
+            data.description,
+            chrono::Utc::now().timestamp()
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Update a field definition. The entity type and field type are fixed once
+/// created, since existing values were validated against them.
+pub fn update_custom_field(conn: &Connection, id: i64, data: &NewCustomField) -> SqliteResult<()> {
+    let options = serde_json::to_string(&data.options).unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        "UPDATE custom_fields SET key = ?1, label = ?2, options = ?3, research_fillable = ?4,
+                description = ?5
+         WHERE id = ?6",
+        params![
+            data.key,
+            data.label,
+            options,
+            data.research_fillable,
+            data.description,
+            id
+        ],
+    )?;
+    Ok(())
+}
+
+/// Names of the saved views whose filter uses a custom field
+pub fn saved_views_using_custom_field(
+    conn: &Connection,
+    field_id: i64,
+) -> SqliteResult<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name, filter FROM saved_views ORDER BY position, name")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+
+    let mut names = Vec::new();
+    for row in rows {
+        let (name, filter) = row?;
+        // Views that no longer parse are already reported as broken
+        let Ok(filter) = serde_json::from_str::<LeadFilter>(&filter) else {
+            continue;
+        };
+        if filter.custom_fields.iter().any(|f| f.field_id == field_id) {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
+
+/// Delete a field and its values. Refused while a saved view filters on it,
+/// since the view would stop working.
+pub fn delete_custom_field(conn: &Connection, id: i64) -> SqliteResult<()> {
+    let tx = conn.unchecked_transaction()?;
+    let views = saved_views_using_custom_field(&tx, id)?;
+    if !views.is_empty() {
+        return Err(rusqlite::Error::InvalidParameterName(format!(
+            "Custom field is used by saved views: {}",
+            views.join(", ")
+        )));
+    }
+    tx.execute("DELETE FROM custom_field_values WHERE field_id = ?1", [id])?;
+    tx.execute("DELETE FROM custom_fields WHERE id = ?1", [id])?;
+    tx.commit()
+}
+
+/// Validate a JSON value against a field definition and convert it for storage.
+/// Numbers are stored as REAL so range filters compare numerically.
+pub fn coerce_custom_field_value(
+    field: &CustomField,
+    value: &serde_json::Value,
+) -> Result<Value, String> {
+    let invalid = || {
+        format!(
+            "Invalid value for {} field '{}'",
+            field.field_type.as_str(),
+            field.key
+        )
+    };
+
+    match field.field_type {
+        CustomFieldType::Text => match value {
+            serde_json::Value::String(s) => Ok(Value::Text(s.trim().to_string())),
+            serde_json::Value::Number(n) => Ok(Value::Text(n.to_string())),
+            serde_json::Value::Bool(b) => Ok(Value::Text(b.to_string())),
+            _ => Err(invalid()),
+        },
+        CustomFieldType::Number => match value {
+            serde_json::Value::Number(n) => n.as_f64().map(Value::Real).ok_or_else(invalid),
+            serde_json::Value::String(s) => s
+                .trim()
+                .replace(',', "")
+                .parse::<f64>()
+                .map(Value::Real)
+                .map_err(|_| invalid()),
+            _ => Err(invalid()),
+        },
+        CustomFieldType::Date => {
+            let s = value.as_str().ok_or_else(invalid)?.trim();
+            // Accept full timestamps too, keeping only the date part
+            let date = s.get(..10).unwrap_or(s);
+            chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map(|d| Value::Text(d.format("%Y-%m-%d").to_string()))
+                .map_err(|_| invalid())
+        }
+        CustomFieldType::Select => {
+            let s = value.as_str().ok_or_else(invalid)?.trim();
+            field
+                .options
+                .iter()
+                .find(|o| o.eq_ignore_ascii_case(s))
+                .map(|o| Value::Text(o.clone()))
+                .ok_or_else(invalid)
+        }
+    }
+}
+
+fn sql_value_to_json(value: Value) -> serde_json::Value {
+    match value {
+        Value::Integer(i) => serde_json::json!(i),
+        Value::Real(f) => serde_json::json!(f),
+        Value::Text(s) => serde_json::Value::String(s),
+        Value::Null | Value::Blob(_) => serde_json::Value::Null,
+    }
+}
+
+fn json_to_sql_value(value: &serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Number(n) => n
+            .as_i64()
+            .map(Value::Integer)
+            .or_else(|| n.as_f64().map(Value::Real))
+            .unwrap_or(Value::Null),
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        serde_json::Value::Bool(b) => Value::Text(b.to_string()),
+        _ => Value::Null,
+    }
+}
+
+fn push_custom_field_conditions(
+    conn: &Connection,
+    conds: &mut Conditions,
+    entity_type: &str,
+    id_expr: &str,
+    filters: &[CustomFieldFilter],
+) -> SqliteResult<()> {
+    for f in filters {
+        // A field of another entity type would quietly match nothing
+        let field_entity_type: String = conn
+            .query_row(
+                "SELECT entity_type FROM custom_fields WHERE id = ?",
+                [f.field_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| {
+                rusqlite::Error::InvalidParameterName(format!(
+                    "Unknown custom field: {}",
+                    f.field_id
+                ))
+            })?;
+        if field_entity_type != entity_type {
+            return Err(rusqlite::Error::InvalidParameterName(format!(
+                "Custom field {} belongs to {}, not {}",
+                f.field_id, field_entity_type, entity_type
+            )));
+        }
+
+        let exists = |predicate: &str| {
+            format!(
+                "EXISTS (SELECT 1 FROM custom_field_values v
+                         WHERE v.field_id = ? AND v.entity_id = {} AND {})",
+                id_expr, predicate
+            )
+        };
+        let field = Value::Integer(f.field_id);
+        let value = json_to_sql_value(&f.value);
+
+        match f.op {
+            CustomFieldOp::IsSet => conds.push(exists("1 = 1"), [field]),
+            CustomFieldOp::IsNotSet => conds.push(format!("NOT {}", exists("1 = 1")), [field]),
+            CustomFieldOp::Eq => conds.push(exists("v.value = ?"), [field, value]),
+            CustomFieldOp::Ne => {
+                conds.push(format!("NOT {}", exists("v.value = ?")), [field, value])
+            }
+            CustomFieldOp::Contains => {
+                conds.push(exists("v.value LIKE '%' || ? || '%'"), [field, value])
+            }
+            CustomFieldOp::Gt => conds.push(exists("v.value > ?"), [field, value]),
+            CustomFieldOp::Gte => conds.push(exists("v.value >= ?"), [field, value]),
+            CustomFieldOp::Lt => conds.push(exists("v.value < ?"), [field, value]),
+            CustomFieldOp::Lte => conds.push(exists("v.value <= ?"), [field, value]),
+            CustomFieldOp::In => {
+                let values: Vec<Value> = f
+                    .value
+                    .as_array()
+                    .map(|a| a.iter().map(json_to_sql_value).collect())
+                    .unwrap_or_default();
+                if values.is_empty() {
+                    conds.push("1 = 0", []);
+                } else {
+                    let predicate = format!("v.value IN ({})", vec!["?"; values.len()].join(", "));
+                    conds.push(exists(&predicate), std::iter::once(field).chain(values));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Values of all custom fields set on one entity
+pub fn get_custom_field_values(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+) -> SqliteResult<Vec<CustomFieldValue>> {
+    let mut stmt = conn.prepare(
+        "SELECT f.id, f.key, v.value, v.updated_at
+         FROM custom_field_values v
+         JOIN custom_fields f ON f.id = v.field_id
+         WHERE f.entity_type = ?1 AND v.entity_id = ?2
+         ORDER BY f.position, f.id",
+    )?;
+    let rows = stmt.query_map(params![entity_type, entity_id], |row| {
+        Ok(CustomFieldValue {
+            field_id: row.get(0)?,
+            key: row.get(1)?,
+            value: sql_value_to_json(row.get(2)?),
+            updated_at: row.get(3)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Custom field values per entity id for one entity type, keyed by field id (used by exports)
+pub fn get_custom_field_values_by_entity(
+    conn: &Connection,
+    entity_type: &str,
+) -> SqliteResult<std::collections::HashMap<i64, std::collections::HashMap<i64, serde_json::Value>>>
+{
+    let mut stmt = conn.prepare(
+        "SELECT v.entity_id, v.field_id, v.value
+         FROM custom_field_values v
+         JOIN custom_fields f ON f.id = v.field_id
+         WHERE f.entity_type = ?1",
+    )?;
+    let mut map: std::collections::HashMap<i64, std::collections::HashMap<i64, serde_json::Value>> =
+        std::collections::HashMap::new();
+    let rows = stmt.query_map([entity_type], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get(2)?))
+    })?;
+    for row in rows {
+        let (entity_id, field_id, value) = row?;
+        map.entry(entity_id)
+            .or_default()
+            .insert(field_id, sql_value_to_json(value));
+    }
+    Ok(map)
+}
+
+/// Set one custom field on one entity, or clear it with `None`.
+/// Values should come from `coerce_custom_field_value`.
+pub fn set_custom_field_value(
+    conn: &Connection,
+    field_id: i64,
+    entity_id: i64,
+    value: Option<Value>,
+) -> SqliteResult<()> {
+    match value {
+        Some(value) => conn.execute(
+            "INSERT INTO custom_field_values (field_id, entity_id, value, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(field_id, entity_id) DO UPDATE SET value = ?3, updated_at = ?4",
+            params![field_id, entity_id, value, chrono::Utc::now().timestamp()],
+        )?,
+        None => conn.execute(
+            "DELETE FROM custom_field_values WHERE field_id = ?1 AND entity_id = ?2",
+            params![field_id, entity_id],
+        )?,
+    };
+    Ok(())
+}
+
+/// Fill research-fillable custom fields from enrichment output. Like the other
+/// enrichment updates, only fields without a value are written; values that
+/// don't match the field type are skipped.
+pub fn enrich_custom_fields<C: std::ops::Deref<Target = Connection>>(
+    conn: &C,
+    entity_type: &str,
+    entity_id: i64,
+    values: &std::collections::HashMap<String, serde_json::Value>,
+) -> SqliteResult<usize> {
+    if values.is_empty() {
+        return Ok(0);
+    }
+
+    let mut written = 0;
+    for field in get_custom_fields(conn, Some(entity_type))? {
+        if !field.research_fillable {
+            continue;
+        }
+        let Some(raw) = values.get(&field.key).filter(|v| !v.is_null()) else {
+            continue;
+        };
+        match coerce_custom_field_value(&field, raw) {
+            Ok(value) => {
+                written += conn.execute(
+                    "INSERT OR IGNORE INTO custom_field_values (field_id, entity_id, value, updated_at)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![field.id, entity_id, value, chrono::Utc::now().timestamp()],
+                )?;
+            }
+            Err(e) => eprintln!("[enrichment] Skipping custom field value: {}", e),
+        }
+    }
+    Ok(written)
+}
+
+// ============================================================================
+// Research Run Queries
+// ============================================================================
+
+fn research_run_from_row(row: &rusqlite::Row) -> SqliteResult<ResearchRun> {
+    Ok(ResearchRun {
+        id: row.get(0)?,
+        entity_type: row.get(1)?,
+        entity_id: row.get(2)?,
+        version: row.get(3)?,
+        job_id: row.get(4)?,
+        model: row.get(5)?,
+        prompt_version: row.get(6)?,
+        restored_from: row.get(7)?,
+        profile: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+/// Store a new profile version. Model and prompt version are taken from the
+/// job (when given) and the prompt currently configured for the entity type.
+pub fn insert_research_run(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+    job_id: Option<&str>,
+    profile: &str,
+    restored_from: Option<i64>,
+) -> SqliteResult<i64> {
+    let prompt_type = if entity_type == "lead" {
+        "company"
+    } else {
+        "person"
+    };
+    conn.execute(
+        "INSERT INTO research_runs (entity_type, entity_id, version, job_id, model, prompt_version,
+                                    restored_from, profile, created_at)
+         VALUES (?1, ?2,
+                 (SELECT COALESCE(MAX(version), 0) + 1 FROM research_runs
+                  WHERE entity_type = ?1 AND entity_id = ?2),
+                 ?3,
+                 (SELECT COALESCE(claude_model, model) FROM jobs WHERE id = ?3),
+                 CASE WHEN ?3 IS NULL THEN NULL
+                      ELSE COALESCE((SELECT CAST(updated_at AS TEXT) FROM prompts WHERE type = ?4), 'default')
+                 END,
+                 ?5, ?6, ?7)",
+        params![
+            entity_type,
+            entity_id,
+            job_id,
+            prompt_type,
+            restored_from,
+            profile,
+            chrono::Utc::now().timestamp()
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// All profile versions of an entity, newest first
+pub fn get_research_runs(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+) -> SqliteResult<Vec<ResearchRun>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, entity_type, entity_id, version, job_id, model, prompt_version, restored_from,
+                profile, created_at
+         FROM research_runs WHERE entity_type = ?1 AND entity_id = ?2
+         ORDER BY version DESC",
+    )?;
+    let rows = stmt.query_map(params![entity_type, entity_id], research_run_from_row)?;
+
+    rows.collect()
+}
+
+pub fn get_research_run(conn: &Connection, id: i64) -> SqliteResult<Option<ResearchRun>> {
+    conn.query_row(
+        "SELECT id, entity_type, entity_id, version, job_id, model, prompt_version, restored_from,
+                profile, created_at
+         FROM research_runs WHERE id = ?1",
+        [id],
+        research_run_from_row,
+    )
+    .optional()
+}
+
+/// Make an earlier version the current profile again. The restore is recorded
+/// as a new version so history stays append-only.
+pub fn restore_research_run(conn: &Connection, run_id: i64) -> SqliteResult<ResearchRun> {
+    let run = get_research_run(conn, run_id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+    let tx = conn.unchecked_transaction()?;
+
+    let sql = if run.entity_type == "lead" {
+        "UPDATE leads SET company_profile = ?1 WHERE id = ?2"
+    } else {
+        "UPDATE people SET person_profile = ?1 WHERE id = ?2"
+    };
+    if tx.execute(sql, params![run.profile, run.entity_id])? == 0 {
+        return Err(rusqlite::Error::QueryReturnedNoRows);
+    }
+    let id = insert_research_run(
+        &tx,
+        &run.entity_type,
+        run.entity_id,
+        None,
+        &run.profile,
+        Some(run.version),
+    )?;
+    tx.commit()?;
+
+    get_research_run(conn, id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+}
+
+/// Line diff from one profile version to another
+pub fn diff_research_runs(
+    conn: &Connection,
+    from_id: i64,
+    to_id: i64,
+) -> SqliteResult<ProfileDiff> {
+    let from = get_research_run(conn, from_id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+    let to = get_research_run(conn, to_id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+
+    let lines: Vec<DiffLine> = diff::lines(&from.profile, &to.profile)
+        .into_iter()
+        .map(|line| match line {
+            diff::Result::Left(text) => DiffLine {
+                op: DiffOp::Delete,
+                text: text.to_string(),
+            },
+            diff::Result::Both(text, _) => DiffLine {
+                op: DiffOp::Equal,
+                text: text.to_string(),
+            },
+            diff::Result::Right(text) => DiffLine {
+                op: DiffOp::Insert,
+                text: text.to_string(),
+            },
+        })
+        .collect();
+
+    Ok(ProfileDiff {
+        from_version: from.version,
+        to_version: to.version,
+        added: lines.iter().filter(|l| l.op == DiffOp::Insert).count(),
+        removed: lines.iter().filter(|l| l.op == DiffOp::Delete).count(),
+        lines,
+    })
+}
+
+// ============================================================================
+// Source Queries
+// ============================================================================
+
+/// Store the sources cited by a research run, with the enrichment fields each backs
+pub fn insert_sources(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+    research_run_id: Option<i64>,
+    job_id: Option<&str>,
+    sources: &[ResearchSource],
+) -> SqliteResult<usize> {
+    let now = chrono::Utc::now().timestamp();
+    for source in sources {
+        conn.execute(
+            "INSERT INTO sources (entity_type, entity_id, research_run_id, job_id, url, title, retrieved_at, claim, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                entity_type,
+                entity_id,
+                research_run_id,
+                job_id,
+                source.url.trim(),
+                source.title,
+                source.retrieved_at,
+                source.claim,
+                now
+            ],
+        )?;
+        let source_id = conn.last_insert_rowid();
+        for field in source
+            .fields
+            .iter()
+            .map(|f| f.trim())
+            .filter(|f| !f.is_empty())
+        {
+            conn.execute(
+                "INSERT OR IGNORE INTO source_fields (source_id, field) VALUES (?1, ?2)",
+                params![source_id, field],
+            )?;
+        }
+    }
+    Ok(sources.len())
+}
+
+/// Sources cited for an entity, newest research run first
+pub fn get_sources(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+) -> SqliteResult<Vec<Source>> {
+    let mut stmt = conn.prepare(
+        "SELECT s.id, s.entity_type, s.entity_id, s.research_run_id, s.job_id, s.url, s.title,
+                s.retrieved_at, s.claim, s.created_at,
+                (SELECT group_concat(field, char(31)) FROM source_fields f WHERE f.source_id = s.id)
+         FROM sources s
+         WHERE s.entity_type = ?1 AND s.entity_id = ?2
+         ORDER BY s.created_at DESC, s.research_run_id DESC, s.id",
+    )?;
+    let rows = stmt.query_map(params![entity_type, entity_id], |row| {
+        let fields: Option<String> = row.get(10)?;
+        Ok(Source {
+            id: row.get(0)?,
+            entity_type: row.get(1)?,
+            entity_id: row.get(2)?,
+            research_run_id: row.get(3)?,
+            job_id: row.get(4)?,
+            url: row.get(5)?,
+            title: row.get(6)?,
+            retrieved_at: row.get(7)?,
+            claim: row.get(8)?,
+            created_at: row.get(9)?,
+            fields: fields
+                .map(|f| f.split('\u{1f}').map(String::from).collect())
+                .unwrap_or_default(),
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// For each enrichment field, the sources of the latest research run that cited it
+pub fn get_field_sources(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: i64,
+) -> SqliteResult<Vec<FieldSources>> {
+    let mut by_field: Vec<FieldSources> = Vec::new();
+    for source in get_sources(conn, entity_type, entity_id)? {
+        for field in &source.fields {
+            match by_field.iter_mut().find(|f| &f.field == field) {
+                // Only sources from the same (latest) run as the first match
+                Some(entry) => {
+                    if entry.sources[0].research_run_id == source.research_run_id {
+                        entry.sources.push(source.clone());
+                    }
+                }
+                None => by_field.push(FieldSources {
+                    field: field.clone(),
+                    sources: vec![source.clone()],
+                }),
+            }
+        }
+    }
+    by_field.sort_by(|a, b| a.field.cmp(&b.field));
+    Ok(by_field)
+}
+
+// ============================================================================
+// Job Queries
+// ============================================================================
+
+pub fn insert_job(conn: &Connection, job: &NewJob) -> SqliteResult<String> {
+    let now = chrono::Utc::now().timestamp();
+    // The prompt is stored redacted; the agent still receives the original
+    let prompt = match Redaction::load(conn)? {
+        Some(redaction) => redaction.protect(conn, &job.id, "prompt", 0, &job.prompt)?,
+        None => job.prompt.clone(),
+    };
+    conn.execute(
+        "INSERT INTO jobs (id, job_type, entity_id, entity_label, status, prompt, model, working_dir, output_path, created_at)
+         VALUES (?1, ?2, ?3, ?4, 'queued', ?5, ?6, ?7, ?8, ?9)",
+        params![
+            job.id,
+            job.job_type,
+            job.entity_id,
+            job.entity_label,
+            prompt,
+            job.model,
+            job.working_dir,
+            job.output_path,
+            now
+        ],
+    )?;
+    Ok(job.id.clone())
+}
+
+pub fn update_job_status(
+    conn: &Connection,
+    job_id: &str,
+    status: &str,
+    exit_code: Option<i32>,
+    error_message: Option<&str>,
+) -> SqliteResult<()> {
+    let now = chrono::Utc::now().timestamp();
+
+    // If transitioning to running, set started_at
+    // If transitioning to completed/error/timeout/cancelled/interrupted, set completed_at
+    match status {
+        "running" => {
+            conn.execute(
+                "UPDATE jobs SET status = ?1, started_at = ?2 WHERE id = ?3",
+                params![status, now, job_id],
+            )?;
+        }
+        "completed" | "error" | "timeout" | "cancelled" | "interrupted" => {
+            conn.execute(
+                "UPDATE jobs SET status = ?1, exit_code = ?2, error_message = ?3, completed_at = ?4 WHERE id = ?5",
+                params![status, exit_code, error_message, now, job_id],
+            )?;
+        }
+        _ => {
+            conn.execute(
+                "UPDATE jobs SET status = ?1 WHERE id = ?2",
+                params![status, job_id],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+pub fn update_job_pid(conn: &Connection, job_id: &str, pid: u32) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE jobs SET pid = ?1 WHERE id = ?2",
+        params![pid as i64, job_id],
+    )?;
+    Ok(())
+}
+
+pub fn update_job_claude_session(
+    conn: &Connection,
+    job_id: &str,
+    session_id: &str,
+    model: &str,
+) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE jobs SET claude_session_id = ?1, claude_model = ?2 WHERE id = ?3",
+        params![session_id, model, job_id],
+    )?;
+    Ok(())
+}
+
+#[allow(dead_code)] // API function for job event index tracking
+pub fn update_job_last_event_index(
+    conn: &Connection,
+    job_id: &str,
+    index: i64,
+) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE jobs SET last_event_index = ?1 WHERE id = ?2",
+        params![index, job_id],
+    )?;
+    Ok(())
+}
+
+pub fn update_job_stream_stats(
+    conn: &Connection,
+    job_id: &str,
+    stdout_truncated: bool,
+    stderr_truncated: bool,
+    total_stdout_bytes: i64,
+    total_stderr_bytes: i64,
+) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE jobs SET stdout_truncated = ?1, stderr_truncated = ?2, total_stdout_bytes = ?3, total_stderr_bytes = ?4 WHERE id = ?5",
+        params![stdout_truncated as i64, stderr_truncated as i64, total_stdout_bytes, total_stderr_bytes, job_id],
+    )?;
+    Ok(())
+}
+
+pub fn update_job_progress(
+    conn: &Connection,
+    job_id: &str,
+    progress: &JobProgress,
+) -> SqliteResult<()> {
+    let json = serde_json::to_string(progress).unwrap_or_else(|_| "{}".to_string());
+    conn.execute(
+        "UPDATE jobs SET progress = ?1 WHERE id = ?2",
+        params![json, job_id],
+    )?;
+    Ok(())
+}
+
+pub fn update_job_completion_state(
+    conn: &Connection,
+    job_id: &str,
+    state: Option<&str>,
+) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE jobs SET completion_state = ?1 WHERE id = ?2",
+        params![state, job_id],
+    )?;
+    Ok(())
+}
+
+pub fn set_job_output_archive(
+    conn: &Connection,
+    job_id: &str,
+    path: Option<&str>,
+) -> SqliteResult<()> {
+    let archived_at = path.map(|_| chrono::Utc::now().timestamp());
+    conn.execute(
+        "UPDATE jobs SET output_archive_path = ?1, output_archived_at = ?2 WHERE id = ?3",
+        params![path, archived_at, job_id],
+    )?;
+    Ok(())
+}
+
+/// Unlink output archives written before `cutoff` and return their paths.
+/// Archives linked before their time was recorded are aged from the job.
+pub fn take_expired_output_archives(conn: &Connection, cutoff: i64) -> SqliteResult<Vec<String>> {
+    const EXPIRED: &str =
+        "output_archive_path IS NOT NULL AND COALESCE(output_archived_at, created_at) < ?1";
+    let paths = {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT output_archive_path FROM jobs WHERE {}",
+            EXPIRED
+        ))?;
+        let rows = stmt.query_map(params![cutoff], |row| row.get(0))?;
+        rows.collect::<SqliteResult<Vec<String>>>()?
+    };
+    conn.execute(
+        &format!(
+            "UPDATE jobs SET output_archive_path = NULL, output_archived_at = NULL WHERE {}",
+            EXPIRED
+        ),
+        params![cutoff],
+    )?;
+    Ok(paths)
+}
+
+pub fn get_job_ids(conn: &Connection) -> SqliteResult<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT id FROM jobs")?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+pub fn get_job(conn: &Connection, job_id: &str) -> SqliteResult<Option<Job>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, job_type, entity_id, entity_label, status, prompt, model, working_dir,
+                output_path, exit_code, error_message, created_at, started_at, completed_at,
+                pid, claude_session_id, claude_model, last_event_index,
+                stdout_truncated, stderr_truncated, total_stdout_bytes, total_stderr_bytes, completion_state,
+                output_archive_path, progress
+         FROM jobs WHERE id = ?1"
+    )?;
+
+    let mut rows = stmt.query(params![job_id])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(Job {
+            id: row.get(0)?,
+            job_type: row.get(1)?,
+            entity_id: row.get(2)?,
+            entity_label: row.get(3)?,
+            status: row.get(4)?,
+            prompt: row.get(5)?,
+            model: row.get(6)?,
+            working_dir: row.get(7)?,
+            output_path: row.get(8)?,
+            exit_code: row.get(9)?,
+            error_message: row.get(10)?,
+            created_at: row.get(11)?,
+            started_at: row.get(12)?,
+            completed_at: row.get(13)?,
+            pid: row.get(14)?,
+            claude_session_id: row.get(15)?,
+            claude_model: row.get(16)?,
+            last_event_index: row.get::<_, Option<i64>>(17)?.unwrap_or(0),
+            stdout_truncated: row.get::<_, Option<i64>>(18)?.unwrap_or(0) != 0,
+            stderr_truncated: row.get::<_, Option<i64>>(19)?.unwrap_or(0) != 0,
+            total_stdout_bytes: row.get::<_, Option<i64>>(20)?.unwrap_or(0),
+            total_stderr_bytes: row.get::<_, Option<i64>>(21)?.unwrap_or(0),
+            completion_state: row.get(22)?,
+            output_archive_path: row.get(23)?,
+            progress: row
+                .get::<_, Option<String>>(24)?
+                .and_then(|p| serde_json::from_str(&p).ok()),
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Get active job for a specific entity and job type (used to prevent duplicate jobs)
+pub fn get_active_job_for_entity(
+    conn: &Connection,
+    entity_id: i64,
+    job_type: &str,
+) -> SqliteResult<Option<Job>> {
     let mut stmt = conn.prepare(
         "SELECT id, job_type, entity_id, entity_label, status, prompt, model, working_dir,
                 output_path, exit_code, error_message, created_at, started_at, completed_at,
                 pid, claude_session_id, claude_model, last_event_index,
-                stdout_truncated, stderr_truncated, total_stdout_bytes, total_stderr_bytes, completion_state
+                stdout_truncated, stderr_truncated, total_stdout_bytes, total_stderr_bytes, completion_state,
+                output_archive_path, progress
          FROM jobs
          WHERE entity_id = ?1 AND job_type = ?2 AND status IN ('queued', 'running')
          ORDER BY created_at DESC
@@ -883,6 +2300,10 @@ pub fn get_active_job_for_entity(
             total_stdout_bytes: row.get::<_, Option<i64>>(20)?.unwrap_or(0),
             total_stderr_bytes: row.get::<_, Option<i64>>(21)?.unwrap_or(0),
             completion_state: row.get(22)?,
+            output_archive_path: row.get(23)?,
+            progress: row
+                .get::<_, Option<String>>(24)?
+                .and_then(|p| serde_json::from_str(&p).ok()),
         }))
     } else {
         Ok(None)
@@ -894,8 +2315,63 @@ pub fn get_active_jobs_db(conn: &Connection) -> SqliteResult<Vec<Job>> {
         "SELECT id, job_type, entity_id, entity_label, status, prompt, model, working_dir,
                 output_path, exit_code, error_message, created_at, started_at, completed_at,
                 pid, claude_session_id, claude_model, last_event_index,
-                stdout_truncated, stderr_truncated, total_stdout_bytes, total_stderr_bytes, completion_state
-         FROM jobs WHERE status IN ('queued', 'running')
+                stdout_truncated, stderr_truncated, total_stdout_bytes, total_stderr_bytes, completion_state,
+                output_archive_path, progress
+         FROM jobs WHERE status IN ('queued', 'running')
+         ORDER BY created_at DESC"
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok(Job {
+            id: row.get(0)?,
+            job_type: row.get(1)?,
+            entity_id: row.get(2)?,
+            entity_label: row.get(3)?,
+            status: row.get(4)?,
+            prompt: row.get(5)?,
+            model: row.get(6)?,
+            working_dir: row.get(7)?,
+            output_path: row.get(8)?,
+            exit_code: row.get(9)?,
+            error_message: row.get(10)?,
+            created_at: row.get(11)?,
+            started_at: row.get(12)?,
+            completed_at: row.get(13)?,
+            pid: row.get(14)?,
+            claude_session_id: row.get(15)?,
+            claude_model: row.get(16)?,
+            last_event_index: row.get::<_, Option<i64>>(17)?.unwrap_or(0),
+            stdout_truncated: row.get::<_, Option<i64>>(18)?.unwrap_or(0) != 0,
+            stderr_truncated: row.get::<_, Option<i64>>(19)?.unwrap_or(0) != 0,
+            total_stdout_bytes: row.get::<_, Option<i64>>(20)?.unwrap_or(0),
+            total_stderr_bytes: row.get::<_, Option<i64>>(21)?.unwrap_or(0),
+            completion_state: row.get(22)?,
+            output_archive_path: row.get(23)?,
+            progress: row
+                .get::<_, Option<String>>(24)?
+                .and_then(|p| serde_json::from_str(&p).ok()),
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Jobs interrupted by a shutdown whose agent session can be resumed, unless
+/// a later job already ran for the same entity and job type
+pub fn get_resumable_jobs(conn: &Connection) -> SqliteResult<Vec<Job>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, job_type, entity_id, entity_label, status, prompt, model, working_dir,
+                output_path, exit_code, error_message, created_at, started_at, completed_at,
+                pid, claude_session_id, claude_model, last_event_index,
+                stdout_truncated, stderr_truncated, total_stdout_bytes, total_stderr_bytes, completion_state,
+                output_archive_path, progress
+         FROM jobs j
+         WHERE status = 'interrupted' AND claude_session_id IS NOT NULL
+           AND NOT EXISTS (
+               SELECT 1 FROM jobs later
+               WHERE later.entity_id = j.entity_id AND later.job_type = j.job_type
+                 AND later.rowid > j.rowid
+           )
          ORDER BY created_at DESC"
     )?;
 
@@ -924,6 +2400,10 @@ pub fn get_active_jobs_db(conn: &Connection) -> SqliteResult<Vec<Job>> {
             total_stdout_bytes: row.get::<_, Option<i64>>(20)?.unwrap_or(0),
             total_stderr_bytes: row.get::<_, Option<i64>>(21)?.unwrap_or(0),
             completion_state: row.get(22)?,
+            output_archive_path: row.get(23)?,
+            progress: row
+                .get::<_, Option<String>>(24)?
+                .and_then(|p| serde_json::from_str(&p).ok()),
         })
     })?;
 
@@ -935,7 +2415,8 @@ pub fn get_recent_jobs(conn: &Connection, limit: i64) -> SqliteResult<Vec<Job>>
         "SELECT id, job_type, entity_id, entity_label, status, prompt, model, working_dir,
                 output_path, exit_code, error_message, created_at, started_at, completed_at,
                 pid, claude_session_id, claude_model, last_event_index,
-                stdout_truncated, stderr_truncated, total_stdout_bytes, total_stderr_bytes, completion_state
+                stdout_truncated, stderr_truncated, total_stdout_bytes, total_stderr_bytes, completion_state,
+                output_archive_path, progress
          FROM jobs
          ORDER BY created_at DESC
          LIMIT ?1"
@@ -966,6 +2447,10 @@ pub fn get_recent_jobs(conn: &Connection, limit: i64) -> SqliteResult<Vec<Job>>
             total_stdout_bytes: row.get::<_, Option<i64>>(20)?.unwrap_or(0),
             total_stderr_bytes: row.get::<_, Option<i64>>(21)?.unwrap_or(0),
             completion_state: row.get(22)?,
+            output_archive_path: row.get(23)?,
+            progress: row
+                .get::<_, Option<String>>(24)?
+                .and_then(|p| serde_json::from_str(&p).ok()),
         })
     })?;
 
@@ -975,11 +2460,23 @@ pub fn get_recent_jobs(conn: &Connection, limit: i64) -> SqliteResult<Vec<Job>>
 pub fn cleanup_old_jobs(conn: &Connection, days: i64) -> SqliteResult<usize> {
     let cutoff = chrono::Utc::now().timestamp() - (days * 24 * 60 * 60);
 
-    // First delete logs for old jobs
+    // First delete logs, events, compacted logs and raw copies for old jobs
     conn.execute(
         "DELETE FROM job_logs WHERE job_id IN (SELECT id FROM jobs WHERE created_at < ?1)",
         params![cutoff],
     )?;
+    conn.execute(
+        "DELETE FROM job_events WHERE job_id IN (SELECT id FROM jobs WHERE created_at < ?1)",
+        params![cutoff],
+    )?;
+    conn.execute(
+        "DELETE FROM job_log_archives WHERE job_id IN (SELECT id FROM jobs WHERE created_at < ?1)",
+        params![cutoff],
+    )?;
+    conn.execute(
+        "DELETE FROM raw_copies WHERE job_id IN (SELECT id FROM jobs WHERE created_at < ?1)",
+        params![cutoff],
+    )?;
 
     // Then delete old jobs
     let deleted = conn.execute("DELETE FROM jobs WHERE created_at < ?1", params![cutoff])?;
@@ -1142,8 +2639,14 @@ pub fn get_job_log_count(conn: &Connection, job_id: &str) -> SqliteResult<i64> {
 }
 
 pub fn delete_job(conn: &Connection, job_id: &str) -> SqliteResult<()> {
-    // Delete logs first (foreign key constraint)
+    // Delete logs, events, compacted logs and raw copies first (foreign key constraint)
     conn.execute("DELETE FROM job_logs WHERE job_id = ?1", params![job_id])?;
+    conn.execute("DELETE FROM job_events WHERE job_id = ?1", params![job_id])?;
+    conn.execute(
+        "DELETE FROM job_log_archives WHERE job_id = ?1",
+        params![job_id],
+    )?;
+    conn.execute("DELETE FROM raw_copies WHERE job_id = ?1", params![job_id])?;
 
     // Delete the job
     conn.execute("DELETE FROM jobs WHERE id = ?1", params![job_id])?;
@@ -1151,13 +2654,322 @@ pub fn delete_job(conn: &Connection, job_id: &str) -> SqliteResult<()> {
     Ok(())
 }
 
+// ============================================================================
+// Job Event Queries
+// ============================================================================
+
+/// Typed event to insert alongside a job log line
+pub struct BatchJobEvent {
+    pub job_id: String,
+    pub sequence: i64,
+    pub kind: String,
+    pub tool_use_id: Option<String>,
+    pub tool_name: Option<String>,
+    pub content: Option<String>,
+    pub input: Option<String>,
+    pub is_error: bool,
+    pub timestamp: i64,
+    pub duration_ms: Option<i64>,
+}
+
+/// Insert events; a tool_result also completes its tool_use row with the
+/// result's error flag and the call duration
+pub fn insert_job_events(conn: &Connection, events: &[BatchJobEvent]) -> SqliteResult<()> {
+    let mut insert = conn.prepare(
+        "INSERT INTO job_events
+            (job_id, sequence, kind, tool_use_id, tool_name, content, input, is_error, timestamp, duration_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+    )?;
+    let mut complete_call = conn.prepare(
+        "UPDATE job_events SET is_error = ?3, duration_ms = ?4 - timestamp
+         WHERE job_id = ?1 AND tool_use_id = ?2 AND kind = 'tool_use'",
+    )?;
+
+    for event in events {
+        insert.execute(params![
+            event.job_id,
+            event.sequence,
+            event.kind,
+            event.tool_use_id,
+            event.tool_name,
+            event.content,
+            event.input,
+            event.is_error,
+            event.timestamp,
+            event.duration_ms
+        ])?;
+        if event.kind == "tool_result" {
+            if let Some(tool_use_id) = &event.tool_use_id {
+                complete_call.execute(params![
+                    event.job_id,
+                    tool_use_id,
+                    event.is_error,
+                    event.timestamp
+                ])?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the job's stream reported a successful final result
+pub fn job_reported_result(conn: &Connection, job_id: &str) -> SqliteResult<bool> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM job_events
+                        WHERE job_id = ?1 AND kind = 'result' AND is_error = 0)",
+        params![job_id],
+        |row| row.get(0),
+    )
+}
+
+pub fn get_job_events(conn: &Connection, job_id: &str) -> SqliteResult<Vec<JobEvent>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, job_id, sequence, kind, tool_use_id, tool_name, content, input, is_error, timestamp, duration_ms
+         FROM job_events WHERE job_id = ?1 ORDER BY sequence ASC, id ASC",
+    )?;
+    let rows = stmt.query_map(params![job_id], |row| {
+        Ok(JobEvent {
+            id: row.get(0)?,
+            job_id: row.get(1)?,
+            sequence: row.get(2)?,
+            kind: row.get(3)?,
+            tool_use_id: row.get(4)?,
+            tool_name: row.get(5)?,
+            content: row.get(6)?,
+            input: row.get(7)?,
+            is_error: row.get(8)?,
+            timestamp: row.get(9)?,
+            duration_ms: row.get(10)?,
+        })
+    })?;
+    rows.collect()
+}
+
+// ============================================================================
+// Job Log Retention Queries
+// ============================================================================
+
+/// Finished jobs created before `cutoff` (seconds) that still have full logs,
+/// oldest first
+pub fn get_jobs_with_full_logs(conn: &Connection, cutoff: i64) -> SqliteResult<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM jobs
+         WHERE created_at < ?1 AND status NOT IN ('queued', 'running')
+           AND EXISTS (SELECT 1 FROM job_logs WHERE job_logs.job_id = jobs.id)
+         ORDER BY created_at ASC",
+    )?;
+    let rows = stmt.query_map(params![cutoff], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Replace a job's log lines with their compressed form
+pub fn store_compacted_logs(
+    conn: &Connection,
+    job_id: &str,
+    line_count: i64,
+    raw_bytes: i64,
+    compressed: &[u8],
+) -> SqliteResult<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "INSERT OR REPLACE INTO job_log_archives (job_id, line_count, raw_bytes, compressed, compacted_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            job_id,
+            line_count,
+            raw_bytes,
+            compressed,
+            chrono::Utc::now().timestamp()
+        ],
+    )?;
+    tx.execute("DELETE FROM job_logs WHERE job_id = ?1", params![job_id])?;
+    tx.commit()
+}
+
+pub fn get_compacted_logs(conn: &Connection, job_id: &str) -> SqliteResult<Option<Vec<u8>>> {
+    conn.query_row(
+        "SELECT compressed FROM job_log_archives WHERE job_id = ?1",
+        params![job_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .map(Option::flatten)
+}
+
+/// Jobs whose compressed logs are still kept, oldest compaction first
+pub fn get_compacted_log_jobs(conn: &Connection) -> SqliteResult<Vec<(String, i64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT job_id, length(compressed) FROM job_log_archives
+         WHERE compressed IS NOT NULL
+         ORDER BY compacted_at ASC",
+    )?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// Drop a job's compressed logs, leaving only the summary row and its events
+pub fn drop_compacted_logs(conn: &Connection, job_id: &str) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE job_log_archives SET compressed = NULL WHERE job_id = ?1",
+        params![job_id],
+    )?;
+    Ok(())
+}
+
+/// Bytes of job logs that retention can reclaim: full lines, their search
+/// index and compressed logs. Timeline events stay as each job's summary and
+/// are not counted.
+pub fn get_log_storage_bytes(conn: &Connection) -> SqliteResult<i64> {
+    conn.query_row(
+        "SELECT
+            (SELECT COALESCE(SUM(length(content)), 0) FROM job_logs)
+          + (SELECT COALESCE(SUM(length(block)), 0) FROM job_logs_fts_data)
+          + (SELECT COALESCE(SUM(length(compressed)), 0) FROM job_log_archives)",
+        [],
+        |row| row.get(0),
+    )
+}
+
+pub fn get_log_storage_by_job_type(conn: &Connection) -> SqliteResult<Vec<LogStorageUsage>> {
+    let mut stmt = conn.prepare(
+        "SELECT j.job_type,
+                COUNT(*),
+                COALESCE(SUM(l.lines), 0),
+                COALESCE(SUM(l.bytes), 0),
+                COUNT(a.job_id),
+                COALESCE(SUM(length(a.compressed)), 0),
+                COALESCE(SUM(e.bytes), 0)
+         FROM jobs j
+         LEFT JOIN (
+             SELECT job_id, COUNT(*) AS lines, SUM(length(content)) AS bytes
+             FROM job_logs GROUP BY job_id
+         ) l ON l.job_id = j.id
+         LEFT JOIN job_log_archives a ON a.job_id = j.id
+         LEFT JOIN (
+             SELECT job_id, SUM(COALESCE(length(content), 0) + COALESCE(length(input), 0)) AS bytes
+             FROM job_events GROUP BY job_id
+         ) e ON e.job_id = j.id
+         GROUP BY j.job_type
+         ORDER BY j.job_type",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(LogStorageUsage {
+            job_type: row.get(0)?,
+            jobs: row.get(1)?,
+            log_lines: row.get(2)?,
+            log_bytes: row.get(3)?,
+            compacted_jobs: row.get(4)?,
+            compacted_bytes: row.get(5)?,
+            event_bytes: row.get(6)?,
+        })
+    })?;
+    rows.collect()
+}
+
+// ============================================================================
+// Redaction Queries
+// ============================================================================
+
+pub fn get_redaction_rules(conn: &Connection) -> SqliteResult<Vec<RedactionRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, pattern, enabled, builtin, created_at
+         FROM redaction_rules ORDER BY builtin DESC, id ASC",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(RedactionRule {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            pattern: row.get(2)?,
+            enabled: row.get::<_, i64>(3)? != 0,
+            builtin: row.get::<_, i64>(4)? != 0,
+            created_at: row.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn insert_redaction_rule(
+    conn: &Connection,
+    name: &str,
+    pattern: &str,
+    builtin: bool,
+) -> SqliteResult<i64> {
+    conn.execute(
+        "INSERT INTO redaction_rules (name, pattern, enabled, builtin, created_at)
+         VALUES (?1, ?2, 1, ?3, ?4)",
+        params![
+            name,
+            pattern,
+            builtin as i64,
+            chrono::Utc::now().timestamp_millis()
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn set_redaction_rule_enabled(conn: &Connection, id: i64, enabled: bool) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE redaction_rules SET enabled = ?1 WHERE id = ?2",
+        params![enabled as i64, id],
+    )?;
+    Ok(())
+}
+
+/// Delete a user-defined rule. Returns false for built-in or unknown rules.
+pub fn delete_redaction_rule(conn: &Connection, id: i64) -> SqliteResult<bool> {
+    let deleted = conn.execute(
+        "DELETE FROM redaction_rules WHERE id = ?1 AND builtin = 0",
+        params![id],
+    )?;
+    Ok(deleted > 0)
+}
+
+pub fn insert_raw_copy(conn: &Connection, job_id: &str, copy: &RawCopy) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO raw_copies (job_id, kind, sequence, nonce, ciphertext, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            job_id,
+            copy.kind,
+            copy.sequence,
+            copy.nonce,
+            copy.ciphertext,
+            chrono::Utc::now().timestamp_millis()
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn get_raw_copies(conn: &Connection, job_id: &str) -> SqliteResult<Vec<RawCopy>> {
+    let mut stmt = conn.prepare(
+        "SELECT kind, sequence, nonce, ciphertext FROM raw_copies
+         WHERE job_id = ?1 ORDER BY kind DESC, sequence ASC",
+    )?;
+    let rows = stmt.query_map(params![job_id], |row| {
+        Ok(RawCopy {
+            kind: row.get(0)?,
+            sequence: row.get(1)?,
+            nonce: row.get(2)?,
+            ciphertext: row.get(3)?,
+        })
+    })?;
+    rows.collect()
+}
+
 // ============================================================================
 // Settings Queries
 // ============================================================================
 
 pub fn get_settings(conn: &Connection) -> SqliteResult<Settings> {
-    let mut stmt =
-        conn.prepare("SELECT model, use_chrome, updated_at FROM settings WHERE id = 1")?;
+    let mut stmt = conn.prepare(
+        "SELECT model, use_chrome, updated_at, output_retention_days, orphan_process_policy,
+                log_retention_days, log_storage_cap_mb, redaction_enabled, raw_copy_policy,
+                max_concurrent_jobs, job_timeout_secs, queue_timeout_secs, graceful_shutdown_secs,
+                stream_drain_timeout_secs, max_accumulated_output_mb, stale_job_threshold_secs,
+                notify_job_completed, notify_job_failed, notify_hot_lead
+         FROM settings WHERE id = 1",
+    )?;
 
     let mut rows = stmt.query([])?;
 
@@ -1166,6 +2978,27 @@ pub fn get_settings(conn: &Connection) -> SqliteResult<Settings> {
             model: row.get(0)?,
             use_chrome: row.get::<_, i64>(1)? != 0,
             updated_at: row.get(2)?,
+            output_retention_days: row.get(3)?,
+            orphan_process_policy: OrphanProcessPolicy::parse(&row.get::<_, String>(4)?)
+                .unwrap_or_default(),
+            log_retention_days: row.get(5)?,
+            log_storage_cap_mb: row.get(6)?,
+            redaction_enabled: row.get::<_, i64>(7)? != 0,
+            raw_copy_policy: RawCopyPolicy::parse(&row.get::<_, String>(8)?).unwrap_or_default(),
+            job_limits: JobLimits {
+                max_concurrent_jobs: row.get(9)?,
+                job_timeout_secs: row.get(10)?,
+                queue_timeout_secs: row.get(11)?,
+                graceful_shutdown_secs: row.get(12)?,
+                stream_drain_timeout_secs: row.get(13)?,
+                max_accumulated_output_mb: row.get(14)?,
+                stale_job_threshold_secs: row.get(15)?,
+            },
+            notifications: NotificationSettings {
+                job_completed: row.get::<_, i64>(16)? != 0,
+                job_failed: row.get::<_, i64>(17)? != 0,
+                hot_lead: row.get::<_, i64>(18)? != 0,
+            },
         })
     } else {
         // Return defaults if no settings exist
@@ -1173,6 +3006,14 @@ pub fn get_settings(conn: &Connection) -> SqliteResult<Settings> {
             model: crate::model_config::default_model().to_string(),
             use_chrome: false,
             updated_at: chrono::Utc::now().timestamp_millis(),
+            output_retention_days: DEFAULT_OUTPUT_RETENTION_DAYS,
+            orphan_process_policy: OrphanProcessPolicy::default(),
+            log_retention_days: DEFAULT_LOG_RETENTION_DAYS,
+            log_storage_cap_mb: DEFAULT_LOG_STORAGE_CAP_MB,
+            redaction_enabled: true,
+            raw_copy_policy: RawCopyPolicy::default(),
+            job_limits: JobLimits::default(),
+            notifications: NotificationSettings::default(),
         })
     }
 }
@@ -1184,8 +3025,12 @@ pub fn update_settings(conn: &Connection, model: &str, use_chrome: bool) -> Sqli
         model, use_chrome
     );
     let rows_affected = conn.execute(
-        "INSERT OR REPLACE INTO settings (id, model, use_chrome, updated_at)
-         VALUES (1, ?1, ?2, ?3)",
+        "INSERT INTO settings (id, model, use_chrome, updated_at)
+         VALUES (1, ?1, ?2, ?3)
+         ON CONFLICT(id) DO UPDATE SET
+            model = excluded.model,
+            use_chrome = excluded.use_chrome,
+            updated_at = excluded.updated_at",
         params![model, use_chrome as i64, now],
     )?;
     eprintln!(
@@ -1195,70 +3040,334 @@ pub fn update_settings(conn: &Connection, model: &str, use_chrome: bool) -> Sqli
     Ok(())
 }
 
+pub fn update_output_retention_days(conn: &Connection, days: i64) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE settings SET output_retention_days = ?1, updated_at = ?2 WHERE id = 1",
+        params![days, chrono::Utc::now().timestamp_millis()],
+    )?;
+    Ok(())
+}
+
+pub fn update_orphan_process_policy(
+    conn: &Connection,
+    policy: OrphanProcessPolicy,
+) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE settings SET orphan_process_policy = ?1, updated_at = ?2 WHERE id = 1",
+        params![policy.as_str(), chrono::Utc::now().timestamp_millis()],
+    )?;
+    Ok(())
+}
+
+pub fn update_redaction_settings(
+    conn: &Connection,
+    enabled: bool,
+    raw_copy_policy: RawCopyPolicy,
+) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE settings SET redaction_enabled = ?1, raw_copy_policy = ?2, updated_at = ?3
+         WHERE id = 1",
+        params![
+            enabled as i64,
+            raw_copy_policy.as_str(),
+            chrono::Utc::now().timestamp_millis()
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn update_log_retention(
+    conn: &Connection,
+    retention_days: i64,
+    storage_cap_mb: i64,
+) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE settings SET log_retention_days = ?1, log_storage_cap_mb = ?2, updated_at = ?3
+         WHERE id = 1",
+        params![
+            retention_days,
+            storage_cap_mb,
+            chrono::Utc::now().timestamp_millis()
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn update_notification_settings(
+    conn: &Connection,
+    notifications: &NotificationSettings,
+) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE settings SET notify_job_completed = ?1, notify_job_failed = ?2,
+                notify_hot_lead = ?3, updated_at = ?4
+         WHERE id = 1",
+        params![
+            notifications.job_completed as i64,
+            notifications.job_failed as i64,
+            notifications.hot_lead as i64,
+            chrono::Utc::now().timestamp_millis()
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn update_job_limits(conn: &Connection, limits: &JobLimits) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE settings SET max_concurrent_jobs = ?1, job_timeout_secs = ?2,
+                queue_timeout_secs = ?3, graceful_shutdown_secs = ?4,
+                stream_drain_timeout_secs = ?5, max_accumulated_output_mb = ?6,
+                stale_job_threshold_secs = ?7, updated_at = ?8
+         WHERE id = 1",
+        params![
+            limits.max_concurrent_jobs,
+            limits.job_timeout_secs,
+            limits.queue_timeout_secs,
+            limits.graceful_shutdown_secs,
+            limits.stream_drain_timeout_secs,
+            limits.max_accumulated_output_mb,
+            limits.stale_job_threshold_secs,
+            chrono::Utc::now().timestamp_millis()
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn get_job_limit_overrides(conn: &Connection) -> SqliteResult<Vec<JobLimitOverrides>> {
+    let mut stmt = conn.prepare(
+        "SELECT job_type, job_timeout_secs, queue_timeout_secs, graceful_shutdown_secs,
+                stream_drain_timeout_secs, max_accumulated_output_mb, stale_job_threshold_secs
+         FROM job_type_limits
+         ORDER BY job_type",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(JobLimitOverrides {
+            job_type: row.get(0)?,
+            job_timeout_secs: row.get(1)?,
+            queue_timeout_secs: row.get(2)?,
+            graceful_shutdown_secs: row.get(3)?,
+            stream_drain_timeout_secs: row.get(4)?,
+            max_accumulated_output_mb: row.get(5)?,
+            stale_job_threshold_secs: row.get(6)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Replace a job type's overrides; overrides with no fields set are removed
+pub fn set_job_limit_overrides(
+    conn: &Connection,
+    overrides: &JobLimitOverrides,
+) -> SqliteResult<()> {
+    if overrides.is_empty() {
+        conn.execute(
+            "DELETE FROM job_type_limits WHERE job_type = ?1",
+            params![overrides.job_type],
+        )?;
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO job_type_limits
+            (job_type, job_timeout_secs, queue_timeout_secs, graceful_shutdown_secs,
+             stream_drain_timeout_secs, max_accumulated_output_mb, stale_job_threshold_secs,
+             updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            overrides.job_type,
+            overrides.job_timeout_secs,
+            overrides.queue_timeout_secs,
+            overrides.graceful_shutdown_secs,
+            overrides.stream_drain_timeout_secs,
+            overrides.max_accumulated_output_mb,
+            overrides.stale_job_threshold_secs,
+            chrono::Utc::now().timestamp_millis()
+        ],
+    )?;
+    Ok(())
+}
+
+/// Limits in effect for jobs of `job_type`
+pub fn get_job_limits(conn: &Connection, job_type: &str) -> SqliteResult<JobLimits> {
+    let limits = get_settings(conn)?.job_limits;
+    let overrides = get_job_limit_overrides(conn)?;
+    Ok(match overrides.iter().find(|o| o.job_type == job_type) {
+        Some(o) => limits.with_overrides(o),
+        None => limits,
+    })
+}
+
 // ============================================================================
 // Enrichment Queries
 // ============================================================================
 
-/// Enrich lead data, only updating fields that are currently NULL
-/// This preserves any user-entered or previously enriched data
-pub fn enrich_lead<C: std::ops::Deref<Target = Connection>>(
-    conn: &C,
+/// Enrich lead data from a research job. Empty fields are always filled;
+/// existing values are only replaced when the lead merge policy allows it.
+pub fn enrich_lead(
+    conn: &Connection,
     lead_id: i64,
     e: &LeadEnrichment,
+    job_id: Option<&str>,
 ) -> SqliteResult<usize> {
-    conn.execute(
-        "UPDATE leads SET
-            website = COALESCE(website, ?1),
-            industry = COALESCE(industry, ?2),
-            sub_industry = COALESCE(sub_industry, ?3),
-            employees = COALESCE(employees, ?4),
-            employee_range = COALESCE(employee_range, ?5),
-            revenue = COALESCE(revenue, ?6),
-            revenue_range = COALESCE(revenue_range, ?7),
-            company_linkedin_url = COALESCE(company_linkedin_url, ?8),
-            city = COALESCE(city, ?9),
-            state = COALESCE(state, ?10),
-            country = COALESCE(country, ?11)
-         WHERE id = ?12",
-        params![
-            e.website,
-            e.industry,
-            e.sub_industry,
-            e.employees,
-            e.employee_range,
-            e.revenue,
-            e.revenue_range,
-            e.company_linkedin_url,
-            e.city,
-            e.state,
-            e.country,
-            lead_id
-        ],
-    )
+    let text = |v: &Option<String>| v.clone().map(Value::Text).unwrap_or(Value::Null);
+    let values = [
+        ("website", text(&e.website)),
+        ("industry", text(&e.industry)),
+        ("subIndustry", text(&e.sub_industry)),
+        (
+            "employees",
+            e.employees.map(Value::Integer).unwrap_or(Value::Null),
+        ),
+        ("employeeRange", text(&e.employee_range)),
+        ("revenue", e.revenue.map(Value::Real).unwrap_or(Value::Null)),
+        ("revenueRange", text(&e.revenue_range)),
+        ("companyLinkedinUrl", text(&e.company_linkedin_url)),
+        ("city", text(&e.city)),
+        ("state", text(&e.state)),
+        ("country", text(&e.country)),
+    ];
+    apply_enrichment(conn, "lead", lead_id, &values, &e.confidence, job_id)
 }
 
-/// Enrich person data, only updating fields that are currently NULL
-/// This preserves any user-entered or previously enriched data
-pub fn enrich_person<C: std::ops::Deref<Target = Connection>>(
-    conn: &C,
+/// Enrich person data from a research job (see `enrich_lead`)
+pub fn enrich_person(
+    conn: &Connection,
     person_id: i64,
     e: &PersonEnrichment,
+    job_id: Option<&str>,
 ) -> SqliteResult<usize> {
-    conn.execute(
-        "UPDATE people SET
-            email = COALESCE(email, ?1),
-            title = COALESCE(title, ?2),
-            management_level = COALESCE(management_level, ?3),
-            linkedin_url = COALESCE(linkedin_url, ?4),
-            year_joined = COALESCE(year_joined, ?5)
-         WHERE id = ?6",
-        params![
-            e.email,
-            e.title,
-            e.management_level,
-            e.linkedin_url,
-            e.year_joined,
-            person_id
-        ],
-    )
+    let text = |v: &Option<String>| v.clone().map(Value::Text).unwrap_or(Value::Null);
+    let values = [
+        ("email", text(&e.email)),
+        ("title", text(&e.title)),
+        ("managementLevel", text(&e.management_level)),
+        ("linkedinUrl", text(&e.linkedin_url)),
+        (
+            "yearJoined",
+            e.year_joined.map(Value::Integer).unwrap_or(Value::Null),
+        ),
+    ];
+    apply_enrichment(conn, "person", person_id, &values, &e.confidence, job_id)
+}
+
+// ============================================================================
+// Search Queries
+// ============================================================================
+
+/// Convert free text into an FTS5 match expression.
+/// Every term is quoted so punctuation can't break the query syntax; terms are
+/// ANDed together, quoted phrases are kept intact and an uppercase `OR` is
+/// passed through as an operator.
+pub fn build_fts_query(input: &str) -> Option<String> {
+    let mut terms: Vec<String> = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let (raw, quoted) = if c == '"' {
+            chars.next();
+            let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+            (phrase, true)
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '"' {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            (word, false)
+        };
+
+        let raw = raw.trim();
+        if raw.is_empty() {
+            continue;
+        }
+        if !quoted && raw.eq_ignore_ascii_case("and") {
+            continue;
+        }
+        if !quoted && raw == "OR" {
+            if terms.last().is_some_and(|t| t != "OR") {
+                terms.push("OR".to_string());
+            }
+            continue;
+        }
+        terms.push(format!("\"{}\"", raw.replace('"', "\"\"")));
+    }
+
+    if terms.last().map(|t| t == "OR").unwrap_or(false) {
+        terms.pop();
+    }
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Full-text search across leads and people, ranked by bm25 (best match first).
+/// `entity_types` restricts the search to "lead" and/or "person"; None searches both.
+pub fn search(
+    conn: &Connection,
+    query: &str,
+    entity_types: Option<&[String]>,
+    limit: i64,
+) -> SqliteResult<Vec<SearchHit>> {
+    let fts_query = match build_fts_query(query) {
+        Some(q) => q,
+        None => return Ok(Vec::new()),
+    };
+
+    let wants = |entity_type: &str| {
+        entity_types
+            .map(|types| types.iter().any(|t| t == entity_type))
+            .unwrap_or(true)
+    };
+
+    let mut selects = Vec::new();
+    if wants("lead") {
+        selects.push(
+            "SELECT 'lead', l.id, l.id, l.company_name, l.industry,
+                    snippet(leads_fts, -1, '<mark>', '</mark>', '…', 16),
+                    bm25(leads_fts, 10.0, 4.0, 1.0, 2.0)
+             FROM leads_fts
+             JOIN leads l ON l.id = leads_fts.rowid
+             WHERE leads_fts MATCH ?1",
+        );
+    }
+    if wants("person") {
+        selects.push(
+            "SELECT 'person', p.id, p.lead_id, p.first_name || ' ' || p.last_name,
+                    COALESCE(p.title || ' at ' || l.company_name, p.title, l.company_name),
+                    snippet(people_fts, -1, '<mark>', '</mark>', '…', 16),
+                    bm25(people_fts, 10.0, 10.0, 4.0, 1.0, 1.0)
+             FROM people_fts
+             JOIN people p ON p.id = people_fts.rowid
+             LEFT JOIN leads l ON l.id = p.lead_id
+             WHERE people_fts MATCH ?1",
+        );
+    }
+    if selects.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sql = format!("{} ORDER BY 7 ASC LIMIT ?2", selects.join(" UNION ALL "));
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![fts_query, limit], |row| {
+        Ok(SearchHit {
+            entity_type: row.get(0)?,
+            entity_id: row.get(1)?,
+            lead_id: row.get(2)?,
+            title: row.get(3)?,
+            subtitle: row.get(4)?,
+            snippet: row.get(5)?,
+            rank: row.get(6)?,
+        })
+    })?;
+
+    rows.collect()
 }
@@ -195,6 +195,29 @@ pub struct Job {
     pub total_stdout_bytes: i64,
     pub total_stderr_bytes: i64,
     pub completion_state: Option<String>,
+    /// Compressed archive of the job's raw output files
+    pub output_archive_path: Option<String>,
+    /// Last progress reported while the job ran
+    pub progress: Option<JobProgress>,
+}
+
+/// Progress signals of a running job, derived from its stream output
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobProgress {
+    /// Agent turns taken so far
+    pub turns: i64,
+    /// File names of the expected outputs, and those already on disk
+    pub outputs_expected: Vec<String>,
+    pub outputs_written: Vec<String>,
+    pub elapsed_secs: i64,
+    pub timeout_secs: i64,
+    /// Tool the agent is waiting on, if any
+    pub current_tool: Option<String>,
+    /// When the job last produced output, in milliseconds
+    pub last_activity_at: i64,
+    /// Rough completion estimate between 0 and 1
+    pub estimate: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -227,14 +250,990 @@ pub struct JobLog {
     pub source: String, // "stdout" | "stderr" | "internal"
 }
 
+/// Log storage of one job type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogStorageUsage {
+    pub job_type: String,
+    pub jobs: i64,
+    /// Lines still stored in job_logs
+    pub log_lines: i64,
+    pub log_bytes: i64,
+    /// Jobs whose logs were compacted (including summary-only ones)
+    pub compacted_jobs: i64,
+    pub compacted_bytes: i64,
+    pub event_bytes: i64,
+}
+
+// ============================================================================
+// Job Log Search
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LogSearchQuery {
+    /// Full-text match on log content (same syntax as the global search)
+    pub text: Option<String>,
+    pub job_types: Vec<String>,
+    pub job_ids: Vec<String>,
+    pub entity_id: Option<i64>,
+    pub tool_names: Vec<String>,
+    pub sources: Vec<String>,
+    pub log_types: Vec<String>,
+    /// Log timestamp range in milliseconds, inclusive
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    /// Lines of the same job returned before and after each match
+    pub context_lines: Option<i64>,
+    /// Only return matches with a log id below this (for the next page)
+    pub before_id: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// A matching log line with the job it belongs to and surrounding lines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSearchMatch {
+    pub log: JobLog,
+    pub job_type: String,
+    pub entity_id: i64,
+    pub entity_label: String,
+    /// Content excerpt with the matched terms in <mark> tags
+    pub snippet: Option<String>,
+    pub before: Vec<JobLog>,
+    pub after: Vec<JobLog>,
+}
+
+/// One page of log search results
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSearchResult {
+    pub matches: Vec<LogSearchMatch>,
+    /// Jobs within the job type, job, entity and time filters whose logs were
+    /// compacted by retention and so could not be searched
+    pub compacted_jobs: i64,
+}
+
+/// A job listed by a job-level log query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSearchJob {
+    pub job_id: String,
+    pub job_type: String,
+    pub entity_id: i64,
+    pub entity_label: String,
+    pub status: String,
+    pub created_at: i64,
+}
+
+// ============================================================================
+// Job Event Table
+// ============================================================================
+
+/// Typed stream-json event of a job; tool calls carry their result's timing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobEvent {
+    pub id: i64,
+    pub job_id: String,
+    /// Sequence of the job_logs line the event came from
+    pub sequence: i64,
+    pub kind: String, // "text" | "thinking" | "tool_use" | "tool_result" | "result"
+    pub tool_use_id: Option<String>,
+    /// Tool name for tool_use, result subtype for result
+    pub tool_name: Option<String>,
+    pub content: Option<String>,
+    /// Tool input as JSON
+    pub input: Option<String>,
+    pub is_error: bool,
+    pub timestamp: i64,
+    pub duration_ms: Option<i64>,
+}
+
 // ============================================================================
 // Settings Table
 // ============================================================================
 
+/// Default for `Settings::output_retention_days`
+pub const DEFAULT_OUTPUT_RETENTION_DAYS: i64 = 30;
+
+/// Default for `Settings::log_retention_days`
+pub const DEFAULT_LOG_RETENTION_DAYS: i64 = 14;
+
+/// Default for `Settings::log_storage_cap_mb`
+pub const DEFAULT_LOG_STORAGE_CAP_MB: i64 = 512;
+
+/// What startup recovery does with agent processes left running by a previous session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanProcessPolicy {
+    /// Track the process until it exits, then import its outputs
+    #[default]
+    Adopt,
+    /// Stop the process and fail its job
+    Terminate,
+}
+
+impl OrphanProcessPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OrphanProcessPolicy::Adopt => "adopt",
+            OrphanProcessPolicy::Terminate => "terminate",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "adopt" => Some(OrphanProcessPolicy::Adopt),
+            "terminate" => Some(OrphanProcessPolicy::Terminate),
+            _ => None,
+        }
+    }
+}
+
+/// What happens to the original text when redaction changes a prompt or log line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RawCopyPolicy {
+    /// Only the redacted text is kept
+    #[default]
+    Discard,
+    /// The original is kept in `raw_copies`, encrypted with a local key
+    Encrypted,
+}
+
+impl RawCopyPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RawCopyPolicy::Discard => "discard",
+            RawCopyPolicy::Encrypted => "encrypted",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "discard" => Some(RawCopyPolicy::Discard),
+            "encrypted" => Some(RawCopyPolicy::Encrypted),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Settings {
     pub model: String,
     pub use_chrome: bool,
     pub updated_at: i64,
+    /// Days to keep archived job output files
+    pub output_retention_days: i64,
+    pub orphan_process_policy: OrphanProcessPolicy,
+    /// Days job logs are kept line by line before being compacted
+    pub log_retention_days: i64,
+    /// Total size job logs may take up before the oldest are compacted or dropped
+    pub log_storage_cap_mb: i64,
+    /// Apply redaction rules to prompts and logs before they are stored or emitted
+    pub redaction_enabled: bool,
+    pub raw_copy_policy: RawCopyPolicy,
+    pub job_limits: JobLimits,
+    pub notifications: NotificationSettings,
+}
+
+/// Which events raise a desktop notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationSettings {
+    /// A research, scoring or conversation job finished
+    pub job_completed: bool,
+    /// Jobs ended in error or timed out; failures close together are grouped
+    pub job_failed: bool,
+    /// Scoring moved a lead into the hot tier
+    pub hot_lead: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            job_completed: true,
+            job_failed: true,
+            hot_lead: true,
+        }
+    }
+}
+
+/// Concurrency, timeouts and output limits applied to agent jobs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobLimits {
+    /// Jobs running at the same time; the rest wait in the queue
+    pub max_concurrent_jobs: i64,
+    pub job_timeout_secs: i64,
+    /// How long a job waits for a free slot before failing
+    pub queue_timeout_secs: i64,
+    /// Time between SIGTERM and SIGKILL when a job is stopped
+    pub graceful_shutdown_secs: i64,
+    /// Time allowed to read remaining output after the process exits
+    pub stream_drain_timeout_secs: i64,
+    /// Output kept in memory for the completion handler
+    pub max_accumulated_output_mb: i64,
+    /// Age after which an unfinished job is considered stale by recovery
+    pub stale_job_threshold_secs: i64,
+}
+
+impl Default for JobLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrent_jobs: 5,
+            job_timeout_secs: 600,
+            queue_timeout_secs: 30,
+            graceful_shutdown_secs: 2,
+            stream_drain_timeout_secs: 5,
+            max_accumulated_output_mb: 10,
+            stale_job_threshold_secs: 600,
+        }
+    }
+}
+
+impl JobLimits {
+    /// These limits with a job type's overrides applied
+    pub fn with_overrides(self, overrides: &JobLimitOverrides) -> Self {
+        Self {
+            max_concurrent_jobs: self.max_concurrent_jobs,
+            job_timeout_secs: overrides.job_timeout_secs.unwrap_or(self.job_timeout_secs),
+            queue_timeout_secs: overrides
+                .queue_timeout_secs
+                .unwrap_or(self.queue_timeout_secs),
+            graceful_shutdown_secs: overrides
+                .graceful_shutdown_secs
+                .unwrap_or(self.graceful_shutdown_secs),
+            stream_drain_timeout_secs: overrides
+                .stream_drain_timeout_secs
+                .unwrap_or(self.stream_drain_timeout_secs),
+            max_accumulated_output_mb: overrides
+                .max_accumulated_output_mb
+                .unwrap_or(self.max_accumulated_output_mb),
+            stale_job_threshold_secs: overrides
+                .stale_job_threshold_secs
+                .unwrap_or(self.stale_job_threshold_secs),
+        }
+    }
+
+    /// Check every limit is in range and that a job can't be taken for stale
+    /// while it is still within its timeout
+    pub fn validate(&self) -> Result<(), String> {
+        check_limit("Concurrent jobs", self.max_concurrent_jobs, 1, 20)?;
+        check_limit("Job timeout", self.job_timeout_secs, 30, 4 * 60 * 60)?;
+        check_limit("Queue timeout", self.queue_timeout_secs, 1, 60 * 60)?;
+        check_limit("Graceful shutdown time", self.graceful_shutdown_secs, 1, 60)?;
+        check_limit(
+            "Stream drain timeout",
+            self.stream_drain_timeout_secs,
+            1,
+            120,
+        )?;
+        check_limit(
+            "Accumulated output limit",
+            self.max_accumulated_output_mb,
+            1,
+            512,
+        )?;
+        check_limit(
+            "Stale job threshold",
+            self.stale_job_threshold_secs,
+            60,
+            24 * 60 * 60,
+        )?;
+        if self.stale_job_threshold_secs < self.job_timeout_secs {
+            return Err("Stale job threshold must be at least the job timeout".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn check_limit(label: &str, value: i64, min: i64, max: i64) -> Result<(), String> {
+    if value < min || value > max {
+        return Err(format!("{label} must be between {min} and {max}"));
+    }
+    Ok(())
+}
+
+/// Per job type replacements for the global `JobLimits`; unset fields
+/// fall back to the global value. Concurrency is always global.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct JobLimitOverrides {
+    pub job_type: String,
+    pub job_timeout_secs: Option<i64>,
+    pub queue_timeout_secs: Option<i64>,
+    pub graceful_shutdown_secs: Option<i64>,
+    pub stream_drain_timeout_secs: Option<i64>,
+    pub max_accumulated_output_mb: Option<i64>,
+    pub stale_job_threshold_secs: Option<i64>,
+}
+
+impl JobLimitOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.job_timeout_secs.is_none()
+            && self.queue_timeout_secs.is_none()
+            && self.graceful_shutdown_secs.is_none()
+            && self.stream_drain_timeout_secs.is_none()
+            && self.max_accumulated_output_mb.is_none()
+            && self.stale_job_threshold_secs.is_none()
+    }
+
+    /// Check the limits these overrides produce on top of `global`
+    pub fn validate(&self, global: &JobLimits) -> Result<(), String> {
+        global
+            .with_overrides(self)
+            .validate()
+            .map_err(|e| format!("{}: {}", self.job_type, e))
+    }
+}
+
+// ============================================================================
+// Model Routing
+// ============================================================================
+
+/// Model used for every job of a type, instead of `Settings::model`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobTypeModel {
+    pub job_type: String,
+    pub model: String,
+}
+
+/// Picks a model for jobs matching all of its set conditions. Rules are tried
+/// by ascending priority; the first match wins over the job type's model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelRoutingRule {
+    pub id: i64,
+    pub job_type: Option<String>,
+    /// Tier of the lead's latest score ("hot", "warm", ...)
+    pub tier: Option<String>,
+    /// Lead industry, compared case-insensitively
+    pub industry: Option<String>,
+    pub model: String,
+    pub priority: i64,
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NewModelRoutingRule {
+    pub job_type: Option<String>,
+    pub tier: Option<String>,
+    pub industry: Option<String>,
+    pub model: String,
+    pub priority: i64,
+}
+
+/// Model chosen for a job and what decided it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedModel {
+    pub model: String,
+    /// "rule" | "job_type" | "default"
+    pub source: String,
+    pub rule_id: Option<i64>,
+}
+
+// ============================================================================
+// Redaction
+// ============================================================================
+
+/// Pattern whose matches are masked in persisted prompts and logs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionRule {
+    pub id: i64,
+    pub name: String,
+    /// Regular expression; a capture group named `keep` is left in place
+    pub pattern: String,
+    pub enabled: bool,
+    /// Shipped with the app; can be disabled but not deleted
+    pub builtin: bool,
+    pub created_at: i64,
+}
+
+/// Encrypted original of a redacted prompt, log line or archived output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawCopy {
+    pub kind: String, // "prompt" | "log" | "output""
+    pub sequence: i64,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+// ============================================================================
+// Search
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub entity_type: String, // "lead" | "person"
+    pub entity_id: i64,
+    pub lead_id: Option<i64>,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub snippet: String,
+    pub rank: f64,
+}
+
+// ============================================================================
+// List Queries (filtering, sorting, pagination)
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeadSortField {
+    CompanyName,
+    Industry,
+    Country,
+    Employees,
+    Revenue,
+    ResearchStatus,
+    UserStatus,
+    CreatedAt,
+    ResearchedAt,
+    Score,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeadSort {
+    pub field: LeadSortField,
+    #[serde(default)]
+    pub direction: SortDirection,
+}
+
+/// Filters for lead lists. Empty lists and None values don't filter.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LeadFilter {
+    /// Full-text match against the search index
+    pub search: Option<String>,
+    pub industries: Vec<String>,
+    pub countries: Vec<String>,
+    pub employee_ranges: Vec<String>,
+    pub employees_min: Option<i64>,
+    pub employees_max: Option<i64>,
+    /// Score tiers; "unscored" matches leads without a score
+    pub tiers: Vec<String>,
+    pub research_statuses: Vec<String>,
+    pub user_statuses: Vec<String>,
+    pub score_min: Option<i64>,
+    pub score_max: Option<i64>,
+    pub has_people: Option<bool>,
+    /// Leads must carry every one of these tags
+    pub tag_ids: Vec<i64>,
+    pub custom_fields: Vec<CustomFieldFilter>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LeadQuery {
+    pub filter: LeadFilter,
+    /// Sort columns in priority order; defaults to company name ascending
+    pub sort: Vec<LeadSort>,
+    /// Opaque cursor from a previous page's `next_cursor`
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PersonSortField {
+    LastName,
+    FirstName,
+    Title,
+    CompanyName,
+    ResearchStatus,
+    UserStatus,
+    CreatedAt,
+    ResearchedAt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonSort {
+    pub field: PersonSortField,
+    #[serde(default)]
+    pub direction: SortDirection,
+}
+
+/// Filters for people lists. Empty lists and None values don't filter.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PersonFilter {
+    /// Full-text match against the search index
+    pub search: Option<String>,
+    pub lead_id: Option<i64>,
+    /// Industry of the person's company
+    pub industries: Vec<String>,
+    pub management_levels: Vec<String>,
+    pub research_statuses: Vec<String>,
+    pub user_statuses: Vec<String>,
+    pub has_conversation_topics: Option<bool>,
+    /// People must carry every one of these tags
+    pub tag_ids: Vec<i64>,
+    pub custom_fields: Vec<CustomFieldFilter>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PersonQuery {
+    pub filter: PersonFilter,
+    /// Sort columns in priority order; defaults to last name, first name ascending
+    pub sort: Vec<PersonSort>,
+    /// Opaque cursor from a previous page's `next_cursor`
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    /// Total number of rows matching the filter (ignoring the cursor)
+    pub total: i64,
+}
+
+// ============================================================================
+// Saved Views Table
+// ============================================================================
+
+/// A named lead query ("smart list") that can be reopened, pinned to the tray
+/// and used as the target of bulk actions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedView {
+    pub id: i64,
+    pub name: String,
+    pub filter: LeadFilter,
+    pub sort: Vec<LeadSort>,
+    pub pinned: bool,
+    pub position: i64,
+    /// Live number of leads currently matching the filter, unless broken
+    pub count: Option<i64>,
+    /// Why the view can't be applied; its filter and sort are then empty
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewSavedView {
+    pub name: String,
+    #[serde(default)]
+    pub filter: LeadFilter,
+    #[serde(default)]
+    pub sort: Vec<LeadSort>,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+// ============================================================================
+// Tags and Custom Fields
+// ============================================================================
+
+/// Entity types that can carry tags and custom fields ("lead" or "person")
+pub const TAGGABLE_ENTITY_TYPES: &[&str] = &["lead", "person"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    /// Number of leads and people carrying the tag
+    pub usage_count: i64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    /// ISO date (YYYY-MM-DD)
+    Date,
+    /// One of the field's `options`
+    Select,
+}
+
+impl CustomFieldType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Number => "number",
+            CustomFieldType::Date => "date",
+            CustomFieldType::Select => "select",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(CustomFieldType::Text),
+            "number" => Some(CustomFieldType::Number),
+            "date" => Some(CustomFieldType::Date),
+            "select" => Some(CustomFieldType::Select),
+            _ => None,
+        }
+    }
+}
+
+/// A user-defined field on leads or people
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomField {
+    pub id: i64,
+    pub entity_type: String,
+    /// Stable identifier used in exports and research enrichment output
+    pub key: String,
+    pub label: String,
+    pub field_type: CustomFieldType,
+    /// Allowed values for select fields
+    pub options: Vec<String>,
+    /// Whether research jobs are asked to fill this field
+    pub research_fillable: bool,
+    /// Guidance for research on what the field means
+    pub description: Option<String>,
+    pub position: i64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewCustomField {
+    pub entity_type: String,
+    pub key: String,
+    pub label: String,
+    pub field_type: CustomFieldType,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub research_fillable: bool,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldValue {
+    pub field_id: i64,
+    pub key: String,
+    /// Number for number fields, string otherwise
+    pub value: serde_json::Value,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomFieldOp {
+    Eq,
+    Ne,
+    /// Case-insensitive substring match
+    Contains,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    /// Value is one of an array of values
+    In,
+    IsSet,
+    IsNotSet,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldFilter {
+    pub field_id: i64,
+    pub op: CustomFieldOp,
+    #[serde(default)]
+    pub value: serde_json::Value,
+}
+
+// ============================================================================
+// Deduplication
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateCandidate {
+    pub id: i64,
+    pub label: String,
+}
+
+/// Records that look like the same lead or person. Members are ordered by id,
+/// so the first member is the oldest (suggested primary).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub entity_type: String,
+    pub members: Vec<DuplicateCandidate>,
+    /// What matched: "domain", "linkedin", "email" and/or "name"
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeResult {
+    pub primary_id: i64,
+    pub merged_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeRecord {
+    pub id: i64,
+    pub entity_type: String,
+    pub primary_id: i64,
+    pub merged_id: i64,
+    /// The merged row as it was before the merge
+    pub snapshot: serde_json::Value,
+    pub merged_at: i64,
+}
+
+// ============================================================================
+// Research Runs Table
+// ============================================================================
+
+/// One stored version of a company or person research profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResearchRun {
+    pub id: i64,
+    pub entity_type: String,
+    pub entity_id: i64,
+    /// 1-based version number per entity
+    pub version: i64,
+    /// Job that produced the profile (None for restores and pre-existing profiles)
+    pub job_id: Option<String>,
+    pub model: Option<String>,
+    /// `updated_at` of the prompt used, or "default" for the built-in prompt
+    pub prompt_version: Option<String>,
+    /// Version this run was restored from, if it is a restore
+    pub restored_from: Option<i64>,
+    pub profile: String,
+    pub created_at: i64,
+}
+
+/// A source cited by a research run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Source {
+    pub id: i64,
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub research_run_id: Option<i64>,
+    pub job_id: Option<String>,
+    pub url: String,
+    pub title: Option<String>,
+    /// As reported by the agent (free-form date text)
+    pub retrieved_at: Option<String>,
+    pub claim: Option<String>,
+    /// Enrichment fields this source backs
+    pub fields: Vec<String>,
+    pub created_at: i64,
+}
+
+/// Sources backing one enrichment field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldSources {
+    pub field: String,
+    pub sources: Vec<Source>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileDiff {
+    pub from_version: i64,
+    pub to_version: i64,
+    pub lines: Vec<DiffLine>,
+    pub added: usize,
+    pub removed: usize,
+}
+
+// ============================================================================
+// Lead Changes
+// ============================================================================
+
+/// An enrichment field whose researched value differs from the stored one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonChange {
+    /// Person row, when it already exists in the database
+    pub person_id: Option<i64>,
+    pub name: String,
+    pub title: Option<String>,
+    pub management_level: Option<String>,
+}
+
+/// What a company research re-run found compared with the previous state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeadChangeSummary {
+    pub field_changes: Vec<FieldChange>,
+    pub people_added: Vec<PersonChange>,
+    /// People no longer listed by the research
+    pub people_gone: Vec<PersonChange>,
+    pub sections_added: Vec<String>,
+    pub sections_removed: Vec<String>,
+    pub sections_changed: Vec<String>,
+    /// Sales-relevant headlines, e.g. new executives or funding rounds
+    pub highlights: Vec<String>,
+}
+
+impl LeadChangeSummary {
+    pub fn is_empty(&self) -> bool {
+        self.field_changes.is_empty()
+            && self.people_added.is_empty()
+            && self.people_gone.is_empty()
+            && self.sections_added.is_empty()
+            && self.sections_removed.is_empty()
+            && self.sections_changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeadChange {
+    pub id: i64,
+    pub lead_id: i64,
+    pub company_name: String,
+    pub job_id: Option<String>,
+    pub summary: LeadChangeSummary,
+    pub created_at: i64,
+}
+
+/// All lead changes detected on one (local) day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeDigest {
+    /// YYYY-MM-DD
+    pub date: String,
+    pub changes: Vec<LeadChange>,
+}
+
+// ============================================================================
+// Field Provenance
+// ============================================================================
+
+/// Where an enrichment field value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldSourceType {
+    User,
+    Import,
+    Research,
+}
+
+impl FieldSourceType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FieldSourceType::User => "user",
+            FieldSourceType::Import => "import",
+            FieldSourceType::Research => "research",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "user" => Some(FieldSourceType::User),
+            "import" => Some(FieldSourceType::Import),
+            "research" => Some(FieldSourceType::Research),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldProvenance {
+    /// Enrichment key, e.g. "employees" or "linkedinUrl"
+    pub field: String,
+    pub source_type: FieldSourceType,
+    pub job_id: Option<String>,
+    /// 0.0-1.0 as reported by the research job
+    pub confidence: Option<f64>,
+    pub updated_at: i64,
+}
+
+/// How research enrichment treats fields that already have a value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergePolicy {
+    /// Only fill empty fields
+    #[default]
+    FillEmpty,
+    /// Also overwrite values that came from earlier research
+    OverwriteResearch,
+    /// Overwrite research and imported values, never user-entered ones
+    OverwriteUnlessUser,
+}
+
+impl MergePolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MergePolicy::FillEmpty => "fill_empty",
+            MergePolicy::OverwriteResearch => "overwrite_research",
+            MergePolicy::OverwriteUnlessUser => "overwrite_unless_user",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "fill_empty" => Some(MergePolicy::FillEmpty),
+            "overwrite_research" => Some(MergePolicy::OverwriteResearch),
+            "overwrite_unless_user" => Some(MergePolicy::OverwriteUnlessUser),
+            _ => None,
+        }
+    }
+}
+
+/// Merge policy for one entity type ("lead" or "person")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergePolicySetting {
+    pub entity_type: String,
+    pub policy: MergePolicy,
+    /// Overwrites (never fills) require at least this reported confidence
+    pub min_confidence: Option<f64>,
 }
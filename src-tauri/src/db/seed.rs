@@ -1,4 +1,5 @@
 use crate::prompts;
+use crate::redaction;
 use rusqlite::{params, Connection, Result as SqliteResult};
 
 /// Seed default data into the database on fresh install.
@@ -6,6 +7,21 @@ use rusqlite::{params, Connection, Result as SqliteResult};
 pub fn seed_defaults(conn: &Connection) -> SqliteResult<()> {
     seed_prompts(conn)?;
     seed_scoring_config(conn)?;
+    seed_redaction_rules(conn)?;
+    Ok(())
+}
+
+/// Seed the built-in redaction rules. Existing rules (possibly disabled by the
+/// user) are left alone.
+fn seed_redaction_rules(conn: &Connection) -> SqliteResult<()> {
+    let now = chrono::Utc::now().timestamp_millis();
+    for (name, pattern, _) in redaction::BUILTIN_RULES {
+        conn.execute(
+            "INSERT OR IGNORE INTO redaction_rules (name, pattern, enabled, builtin, created_at)
+             VALUES (?1, ?2, 1, 1, ?3)",
+            params![name, pattern, now],
+        )?;
+    }
     Ok(())
 }
 
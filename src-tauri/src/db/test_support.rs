@@ -0,0 +1,57 @@
+//! Fixtures shared by unit tests across modules
+
+use rusqlite::Connection;
+use std::path::PathBuf;
+
+use super::{init_schema, insert_job, insert_lead, update_job_status, NewJob, NewLead};
+
+/// In-memory database with the full schema
+pub fn memory_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    init_schema(&conn).unwrap();
+    conn
+}
+
+pub fn new_lead(name: &str) -> NewLead {
+    NewLead {
+        company_name: name.to_string(),
+        website: None,
+        city: None,
+        state: None,
+        country: None,
+    }
+}
+
+pub fn add_lead(conn: &Connection, name: &str) -> i64 {
+    insert_lead(conn, &new_lead(name)).unwrap()
+}
+
+/// Job on the "Acme" entity with a placeholder prompt
+pub fn new_job(id: &str, job_type: &str, entity_id: i64) -> NewJob {
+    NewJob {
+        id: id.to_string(),
+        job_type: job_type.to_string(),
+        entity_id,
+        entity_label: "Acme".to_string(),
+        prompt: "research".to_string(),
+        model: None,
+        working_dir: ".".to_string(),
+        output_path: None,
+    }
+}
+
+/// Insert a job and move it to `status`
+pub fn add_job(conn: &Connection, id: &str, job_type: &str, entity_id: i64, status: &str) {
+    insert_job(conn, &new_job(id, job_type, entity_id)).unwrap();
+    if status != "queued" {
+        update_job_status(conn, id, status, Some(0), None).unwrap();
+    }
+}
+
+/// Empty scratch directory unique to this test process
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("qualify-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -1,6 +1,9 @@
 use serde::Serialize;
 use tauri::{AppHandle, Emitter};
 
+use crate::db::JobProgress;
+use crate::jobs::QueueStatus;
+
 #[derive(Clone, Serialize)]
 pub struct LeadUpdatedPayload {
     pub id: i64,
@@ -38,6 +41,11 @@ pub struct PersonDeletedPayload {
     pub ids: Vec<i64>,
 }
 
+#[derive(Clone, Serialize)]
+pub struct SavedViewPayload {
+    pub id: i64,
+}
+
 pub fn emit_lead_updated(app: &AppHandle, id: i64) {
     let _ = app.emit("lead-updated", LeadUpdatedPayload { id });
 }
@@ -66,6 +74,55 @@ pub fn emit_person_deleted(app: &AppHandle, ids: Vec<i64>) {
     let _ = app.emit("person-deleted", PersonDeletedPayload { ids });
 }
 
+pub fn emit_saved_views_changed(app: &AppHandle) {
+    let _ = app.emit("saved-views-changed", ());
+}
+
+/// Ask the frontend to open a saved view (e.g. picked from the tray menu)
+pub fn emit_open_saved_view(app: &AppHandle, id: i64) {
+    let _ = app.emit("open-saved-view", SavedViewPayload { id });
+}
+
+/// What the frontend should navigate to: a lead, a person or the job list
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenEntityPayload {
+    pub entity_type: String,
+    pub id: Option<i64>,
+    pub lead_id: Option<i64>,
+}
+
+impl OpenEntityPayload {
+    pub fn lead(id: i64) -> Self {
+        Self {
+            entity_type: "lead".to_string(),
+            id: Some(id),
+            lead_id: Some(id),
+        }
+    }
+
+    pub fn person(id: i64, lead_id: Option<i64>) -> Self {
+        Self {
+            entity_type: "person".to_string(),
+            id: Some(id),
+            lead_id,
+        }
+    }
+
+    pub fn jobs() -> Self {
+        Self {
+            entity_type: "jobs".to_string(),
+            id: None,
+            lead_id: None,
+        }
+    }
+}
+
+/// Ask the frontend to open an entity (e.g. the subject of a notification)
+pub fn emit_open_entity(app: &AppHandle, target: OpenEntityPayload) {
+    let _ = app.emit("open-entity", target);
+}
+
 // ============================================================================
 // Job Events
 // ============================================================================
@@ -139,3 +196,33 @@ pub fn emit_job_created(
         },
     );
 }
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobProgressPayload {
+    pub job_id: String,
+    #[serde(flatten)]
+    pub progress: JobProgress,
+}
+
+pub fn emit_job_progress(app: &AppHandle, job_id: String, progress: JobProgress) {
+    let _ = app.emit("job-progress", JobProgressPayload { job_id, progress });
+}
+
+pub fn emit_job_queue_changed(app: &AppHandle, status: QueueStatus) {
+    let _ = app.emit("job-queue-changed", status);
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputsHarvestedPayload {
+    pub salvaged: usize,
+    pub failed: usize,
+}
+
+pub fn emit_outputs_harvested(app: &AppHandle, salvaged: usize, failed: usize) {
+    let _ = app.emit(
+        "outputs-harvested",
+        OutputsHarvestedPayload { salvaged, failed },
+    );
+}
@@ -0,0 +1,385 @@
+//! Archive of raw job output files
+//!
+//! Before a finished job's output files are parsed (and, on success, deleted),
+//! they are bundled into a gzip-compressed JSON document named after the job
+//! id. Archives outlive the output directory so rejected outputs can be
+//! inspected and re-run through the parse and database phases, and are purged
+//! after the configured retention period.
+//!
+//! Outputs hold the same personal data as job logs, so they pass through the
+//! configured redaction before they are archived. The original of a redacted
+//! file is sealed into the archive whatever the raw copy policy, so archives
+//! stay reprocessable; only when the key can't be opened is it left out.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+use super::completion_handler::VerifiedOutputs;
+use super::result_parser::{JobMetadata, JobType};
+use crate::db::{self, RawCopy};
+use crate::redaction::{RawVault, Redaction};
+
+const ARCHIVE_EXTENSION: &str = "json.gz";
+
+/// One archived output file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedFile {
+    /// "primary", "secondary", "enrichment" or "sources"
+    pub role: String,
+    pub path: PathBuf,
+    pub content: String,
+    /// Whether `content` had text redacted
+    #[serde(default)]
+    pub redacted: bool,
+    /// Encrypted original of a redacted file, unless the key was unavailable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sealed: Option<RawCopy>,
+}
+
+impl ArchivedFile {
+    /// The file as the job wrote it, decrypting a redacted file's original
+    fn original(&self, vault: &mut Option<Arc<RawVault>>) -> io::Result<String> {
+        if !self.redacted {
+            return Ok(self.content.clone());
+        }
+        let sealed = self.sealed.as_ref().ok_or_else(|| {
+            io::Error::other(format!(
+                "The {} output was redacted and its original discarded",
+                self.role
+            ))
+        })?;
+        let vault = match vault {
+            Some(vault) => vault,
+            None => vault.insert(RawVault::open()?),
+        };
+        vault
+            .unseal(sealed)
+            .ok_or_else(|| io::Error::other(format!("Failed to decrypt the {} output", self.role)))
+    }
+}
+
+/// Everything a job wrote, plus what is needed to process it again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputArchive {
+    pub job_id: String,
+    pub job_type: JobType,
+    pub entity_id: i64,
+    pub archived_at: i64,
+    pub files: Vec<ArchivedFile>,
+}
+
+impl OutputArchive {
+    fn file(&self, role: &str) -> Option<&ArchivedFile> {
+        self.files.iter().find(|f| f.role == role)
+    }
+
+    /// Rebuild the job metadata, pointing at the original output paths
+    pub fn metadata(&self) -> JobMetadata {
+        JobMetadata {
+            job_type: self.job_type,
+            entity_id: self.entity_id,
+            primary_output_path: self
+                .file("primary")
+                .map(|f| f.path.clone())
+                .unwrap_or_default(),
+            secondary_output_path: self.file("secondary").map(|f| f.path.clone()),
+            enrichment_output_path: self.file("enrichment").map(|f| f.path.clone()),
+            sources_output_path: self.file("sources").map(|f| f.path.clone()),
+        }
+    }
+
+    /// The original contents, as the completion handler's verify phase would
+    /// read them. None when there is no primary output; fails when a redacted
+    /// file's original was not kept.
+    pub fn outputs(&self) -> io::Result<Option<VerifiedOutputs>> {
+        self.outputs_with(None)
+    }
+
+    /// `outputs`, decrypting with `vault` instead of opening the keychain's
+    fn outputs_with(
+        &self,
+        mut vault: Option<Arc<RawVault>>,
+    ) -> io::Result<Option<VerifiedOutputs>> {
+        let mut content = |role: &str| self.file(role).map(|f| f.original(&mut vault)).transpose();
+        let Some(primary_content) = content("primary")?.filter(|c| !c.trim().is_empty()) else {
+            return Ok(None);
+        };
+        Ok(Some(VerifiedOutputs {
+            primary_content,
+            secondary_content: content("secondary")?,
+            enrichment_content: content("enrichment")?,
+            sources_content: content("sources")?,
+        }))
+    }
+}
+
+/// Directory holding output archives
+pub fn archive_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("archive")
+}
+
+fn archive_path(dir: &Path, job_id: &str) -> PathBuf {
+    dir.join(format!("{}.{}", job_id, ARCHIVE_EXTENSION))
+}
+
+/// Mask the file's content, sealing the original when the key is available
+fn redact_file(file: &mut ArchivedFile, redaction: &Redaction) {
+    let masked = match redaction.redact(&file.content) {
+        Cow::Owned(masked) => masked,
+        Cow::Borrowed(_) => return,
+    };
+    file.sealed = redaction.seal("output", 0, &file.content);
+    file.content = masked;
+    file.redacted = true;
+}
+
+/// Archive whichever output files of the job exist, redacted when `redaction`
+/// is given. Returns the archive path, or None when the job wrote nothing.
+pub fn write_archive(
+    dir: &Path,
+    job_id: &str,
+    metadata: &JobMetadata,
+    redaction: Option<&Redaction>,
+) -> io::Result<Option<PathBuf>> {
+    let candidates = [
+        ("primary", Some(&metadata.primary_output_path)),
+        ("secondary", metadata.secondary_output_path.as_ref()),
+        ("enrichment", metadata.enrichment_output_path.as_ref()),
+        ("sources", metadata.sources_output_path.as_ref()),
+    ];
+    let files: Vec<ArchivedFile> = candidates
+        .into_iter()
+        .filter_map(|(role, path)| {
+            let path = path?;
+            let content = fs::read_to_string(path).ok()?;
+            let mut file = ArchivedFile {
+                role: role.to_string(),
+                path: path.clone(),
+                content,
+                redacted: false,
+                sealed: None,
+            };
+            if let Some(redaction) = redaction {
+                redact_file(&mut file, redaction);
+            }
+            Some(file)
+        })
+        .collect();
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let archive = OutputArchive {
+        job_id: job_id.to_string(),
+        job_type: metadata.job_type,
+        entity_id: metadata.entity_id,
+        archived_at: chrono::Utc::now().timestamp(),
+        files,
+    };
+    let json = serde_json::to_vec(&archive).map_err(io::Error::other)?;
+
+    fs::create_dir_all(dir)?;
+    let path = archive_path(dir, job_id);
+    let mut encoder = GzEncoder::new(fs::File::create(&path)?, Compression::default());
+    encoder.write_all(&json)?;
+    encoder.finish()?;
+    Ok(Some(path))
+}
+
+pub fn read_archive(path: &Path) -> io::Result<OutputArchive> {
+    let mut json = String::new();
+    GzDecoder::new(fs::File::open(path)?).read_to_string(&mut json)?;
+    serde_json::from_str(&json).map_err(io::Error::other)
+}
+
+/// Delete archives older than `retention_days` and archives whose job row is gone.
+/// Returns the number of archives removed.
+pub fn purge_archives(conn: &Connection, dir: &Path, retention_days: i64) -> io::Result<usize> {
+    let cutoff = chrono::Utc::now().timestamp() - retention_days * 24 * 60 * 60;
+    let mut removed = 0;
+
+    let expired = db::take_expired_output_archives(conn, cutoff).map_err(io::Error::other)?;
+    for path in expired {
+        match fs::remove_file(&path) {
+            Ok(()) => removed += 1,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => eprintln!("[archive] Failed to delete {}: {}", path, e),
+        }
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(removed);
+    };
+    let known: HashSet<String> = db::get_job_ids(conn)
+        .map_err(io::Error::other)?
+        .into_iter()
+        .collect();
+    let suffix = format!(".{}", ARCHIVE_EXTENSION);
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(job_id) = name.strip_suffix(&suffix) else {
+            continue;
+        };
+        if !known.contains(job_id) && fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Purge expired archives using the configured retention period. Run by the
+/// maintenance scheduler alongside log retention.
+pub fn purge_expired(conn: &Mutex<Connection>, app: &AppHandle) {
+    let Ok(data_dir) = app.path().app_data_dir() else {
+        return;
+    };
+    let conn = match conn.lock() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("[archive] Failed to lock database: {}", e);
+            return;
+        }
+    };
+    let retention_days = db::get_settings(&conn)
+        .map(|s| s.output_retention_days)
+        .unwrap_or(db::DEFAULT_OUTPUT_RETENTION_DAYS);
+    match purge_archives(&conn, &archive_dir(&data_dir), retention_days) {
+        Ok(count) if count > 0 => eprintln!("[archive] Purged {} expired output archives", count),
+        Err(e) => eprintln!("[archive] Failed to purge output archives: {}", e),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{purge_archives, read_archive, write_archive};
+    use crate::db::seed::seed_defaults;
+    use crate::db::test_support::{memory_db, new_job, temp_dir};
+    use crate::db::{get_job, insert_job, set_job_output_archive};
+    use crate::jobs::{JobMetadata, JobType};
+    use crate::redaction::{RawVault, Redaction};
+
+    #[test]
+    fn job_outputs_are_archived_and_purged_after_retention() {
+        let conn = memory_db();
+        let dir = temp_dir("archive");
+        let output_dir = dir.join("output");
+        std::fs::create_dir_all(&output_dir).unwrap();
+        std::fs::write(
+            output_dir.join("score.json"),
+            r#"{"passesRequirements":true}"#,
+        )
+        .unwrap();
+
+        insert_job(&conn, &new_job("job-1", "scoring", 7)).unwrap();
+        let metadata = JobMetadata {
+            job_type: JobType::Scoring,
+            entity_id: 7,
+            primary_output_path: output_dir.join("score.json"),
+            secondary_output_path: None,
+            enrichment_output_path: None,
+            sources_output_path: Some(output_dir.join("sources.json")),
+        };
+        let archive_dir = dir.join("archive");
+        let path = write_archive(&archive_dir, "job-1", &metadata, None)
+            .unwrap()
+            .unwrap();
+        set_job_output_archive(&conn, "job-1", Some(&path.to_string_lossy())).unwrap();
+        std::fs::remove_dir_all(&output_dir).unwrap();
+
+        let archive = read_archive(&path).unwrap();
+        assert_eq!(archive.files.len(), 1);
+        assert_eq!(
+            archive.metadata().primary_output_path,
+            metadata.primary_output_path
+        );
+        assert!(archive
+            .outputs()
+            .unwrap()
+            .unwrap()
+            .primary_content
+            .contains("passesRequirements"));
+
+        // Within retention nothing is purged; archives of deleted jobs are
+        std::fs::write(archive_dir.join("gone.json.gz"), b"").unwrap();
+        assert_eq!(purge_archives(&conn, &archive_dir, 30).unwrap(), 1);
+        assert!(path.exists());
+
+        // Age counts from when the archive was written, not when the job ran
+        conn.execute("UPDATE jobs SET created_at = created_at - 31 * 86400", [])
+            .unwrap();
+        assert_eq!(purge_archives(&conn, &archive_dir, 30).unwrap(), 0);
+        conn.execute(
+            "UPDATE jobs SET output_archived_at = output_archived_at - 31 * 86400",
+            [],
+        )
+        .unwrap();
+        assert_eq!(purge_archives(&conn, &archive_dir, 30).unwrap(), 1);
+        assert!(!path.exists());
+        let job = get_job(&conn, "job-1").unwrap().unwrap();
+        assert!(job.output_archive_path.is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn archived_outputs_are_redacted_and_reprocessed_from_sealed_originals() {
+        let conn = memory_db();
+        seed_defaults(&conn).unwrap();
+        let dir = temp_dir("archive-redacted");
+        std::fs::write(dir.join("person.md"), "# Jane Doe\nEmail: jane@acme.test").unwrap();
+        std::fs::write(dir.join("sources.json"), "[]").unwrap();
+        let metadata = JobMetadata {
+            job_type: JobType::PersonResearch,
+            entity_id: 3,
+            primary_output_path: dir.join("person.md"),
+            secondary_output_path: None,
+            enrichment_output_path: None,
+            sources_output_path: Some(dir.join("sources.json")),
+        };
+
+        // Default settings redact and discard raw copies of logs, but archives
+        // still seal their originals
+        let vault = RawVault::ephemeral();
+        let redaction = Redaction::load(&conn)
+            .unwrap()
+            .unwrap()
+            .sealing(|| Ok(vault.clone()));
+        let path = write_archive(&dir, "job-1", &metadata, Some(&redaction))
+            .unwrap()
+            .unwrap();
+        let archive = read_archive(&path).unwrap();
+        let primary = &archive.files[0];
+        assert_eq!(primary.content, "# Jane Doe\nEmail: [REDACTED:email]");
+        assert!(primary.redacted && primary.sealed.is_some());
+        assert!(!archive.files[1].redacted);
+        let outputs = archive.outputs_with(Some(vault)).unwrap().unwrap();
+        assert_eq!(outputs.primary_content, "# Jane Doe\nEmail: jane@acme.test");
+
+        // Without the key the redacted archive can be read but not reprocessed
+        let redaction = Redaction::load(&conn)
+            .unwrap()
+            .unwrap()
+            .sealing(|| Err(std::io::Error::other("no keychain")));
+        let path = write_archive(&dir, "job-2", &metadata, Some(&redaction))
+            .unwrap()
+            .unwrap();
+        let archive = read_archive(&path).unwrap();
+        assert!(archive.files[0].sealed.is_none());
+        assert!(archive.outputs_with(None).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
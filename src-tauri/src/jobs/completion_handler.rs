@@ -8,18 +8,21 @@
 //! 4. Cleanup output files only after DB is confirmed
 //! 5. Record completion state for recovery
 
-use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 
-use super::enrichment::{LeadEnrichment, PersonEnrichment};
+use super::archive;
+use super::enrichment::{LeadEnrichment, PersonEnrichment, ResearchSource};
+use super::output_schema::{self, OutputKind};
 use super::result_parser::{JobMetadata, JobType};
 use super::stream_processor::CompletionContext;
 use crate::db;
 use crate::events;
+use crate::redaction::{RawVault, Redaction};
 
 /// Completion phases for recovery tracking
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -63,6 +66,7 @@ pub struct VerifiedOutputs {
     pub primary_content: String,
     pub secondary_content: Option<String>,
     pub enrichment_content: Option<String>,
+    pub sources_content: Option<String>,
 }
 
 /// Parsed output content based on job type
@@ -71,10 +75,12 @@ pub enum ParsedOutput {
         profile: String,
         people: Option<Vec<serde_json::Value>>,
         enrichment: Option<LeadEnrichment>,
+        sources: Vec<ResearchSource>,
     },
     PersonResearch {
         profile: String,
         enrichment: Option<PersonEnrichment>,
+        sources: Vec<ResearchSource>,
     },
     Scoring {
         score_data: serde_json::Value,
@@ -113,6 +119,9 @@ impl CompletionHandler {
         // Update completion state: started
         self.update_completion_state(&ctx.job_id, CompletionPhase::Started);
 
+        // Keep the raw outputs, whatever happens to them below
+        self.archive_outputs(&ctx.job_id, metadata);
+
         // If job failed, mark entity as failed and return early
         if !ctx.success {
             self.mark_entity_failed(metadata);
@@ -120,29 +129,150 @@ impl CompletionHandler {
             return Ok(());
         }
 
+        self.complete_from_files(&ctx.job_id, metadata)
+    }
+
+    /// Import outputs a job left on disk without ever completing, e.g. because
+    /// the app quit while the agent was still writing them
+    pub fn harvest(&self, job_id: &str, metadata: &JobMetadata) -> Result<(), CompletionError> {
+        self.update_completion_state(job_id, CompletionPhase::Started);
+        self.archive_outputs(job_id, metadata);
+        self.complete_from_files(job_id, metadata)
+    }
+
+    /// Phases 1-5 on the output files of a job whose agent run succeeded
+    fn complete_from_files(
+        &self,
+        job_id: &str,
+        metadata: &JobMetadata,
+    ) -> Result<(), CompletionError> {
         // Phase 1: Verify output files
         let outputs = self.verify_output_files(metadata)?;
-        self.update_completion_state(&ctx.job_id, CompletionPhase::FilesVerified);
+        self.update_completion_state(job_id, CompletionPhase::FilesVerified);
+
+        self.finish_from_outputs(job_id, metadata, &outputs)
+    }
+
+    /// Continue a completion that stopped after `phase`, e.g. because the app
+    /// quit mid-way. Outputs are read from disk, falling back to the job's
+    /// archive when the files are gone. The database phase runs in a single
+    /// transaction, so it is only repeated if it never committed.
+    pub fn resume_completion(
+        &self,
+        job_id: &str,
+        metadata: &JobMetadata,
+        phase: &CompletionPhase,
+        archived: Option<VerifiedOutputs>,
+    ) -> Result<(), CompletionError> {
+        let archived_missing = archived.is_none();
+        match phase {
+            CompletionPhase::Started
+            | CompletionPhase::FilesVerified
+            | CompletionPhase::ContentParsed => {
+                let outputs = match self.verify_output_files(metadata) {
+                    Ok(outputs) => outputs,
+                    Err(e) => archived.ok_or(e)?,
+                };
+                if archived_missing {
+                    self.archive_outputs(job_id, metadata);
+                }
+                self.update_completion_state(job_id, CompletionPhase::FilesVerified);
+                self.finish_from_outputs(job_id, metadata, &outputs)
+            }
+            CompletionPhase::DatabaseUpdated => self.finish_after_database(job_id, metadata),
+            CompletionPhase::FilesCleanedUp => {
+                self.emit_completion_events(metadata);
+                self.update_completion_state(job_id, CompletionPhase::Completed);
+                Ok(())
+            }
+            CompletionPhase::Completed | CompletionPhase::Failed => Ok(()),
+        }
+    }
 
+    /// Phases 2-5: parse, update the database, clean up and mark complete
+    fn finish_from_outputs(
+        &self,
+        job_id: &str,
+        metadata: &JobMetadata,
+        outputs: &VerifiedOutputs,
+    ) -> Result<(), CompletionError> {
         // Phase 2: Parse and validate content
-        let parsed = self.parse_output_content(&outputs, metadata)?;
-        self.update_completion_state(&ctx.job_id, CompletionPhase::ContentParsed);
+        let parsed = self.parse_output_content(outputs, metadata)?;
+        self.update_completion_state(job_id, CompletionPhase::ContentParsed);
 
-        // Phase 3: Update database
-        self.update_database(&parsed, metadata)?;
-        self.update_completion_state(&ctx.job_id, CompletionPhase::DatabaseUpdated);
+        // Phase 3: Update database (records DatabaseUpdated in its transaction)
+        self.update_database(job_id, &parsed, metadata)?;
 
+        self.finish_after_database(job_id, metadata)
+    }
+
+    /// Phases 4-5: cleanup files and mark complete
+    fn finish_after_database(
+        &self,
+        job_id: &str,
+        metadata: &JobMetadata,
+    ) -> Result<(), CompletionError> {
         // Phase 4: Cleanup files (only after DB confirmed)
         self.cleanup_files(metadata)?;
-        self.update_completion_state(&ctx.job_id, CompletionPhase::FilesCleanedUp);
+        self.update_completion_state(job_id, CompletionPhase::FilesCleanedUp);
 
         // Phase 5: Emit events and mark complete
         self.emit_completion_events(metadata);
-        self.update_completion_state(&ctx.job_id, CompletionPhase::Completed);
+        self.update_completion_state(job_id, CompletionPhase::Completed);
 
         Ok(())
     }
 
+    /// Re-run the parse and database phases on archived outputs of a finished job
+    pub fn reprocess(
+        &self,
+        job_id: &str,
+        metadata: &JobMetadata,
+        outputs: &VerifiedOutputs,
+    ) -> Result<(), CompletionError> {
+        self.update_completion_state(job_id, CompletionPhase::FilesVerified);
+
+        let parsed = self.parse_output_content(outputs, metadata)?;
+        self.update_completion_state(job_id, CompletionPhase::ContentParsed);
+
+        self.update_database(job_id, &parsed, metadata)?;
+
+        self.emit_completion_events(metadata);
+        self.update_completion_state(job_id, CompletionPhase::Completed);
+
+        Ok(())
+    }
+
+    /// Archive the job's output files and link the archive from the job row
+    fn archive_outputs(&self, job_id: &str, metadata: &JobMetadata) {
+        let Ok(data_dir) = self.app_handle.path().app_data_dir() else {
+            return;
+        };
+        let redaction = match self.db_conn.lock() {
+            Ok(conn) => Redaction::load(&conn)
+                .unwrap_or_else(|e| {
+                    eprintln!("[completion_handler] Failed to load redaction rules: {}", e);
+                    None
+                })
+                .map(|redaction| redaction.sealing(RawVault::open)),
+            Err(_) => None,
+        };
+        let dir = archive::archive_dir(&data_dir);
+        match archive::write_archive(&dir, job_id, metadata, redaction.as_ref()) {
+            Ok(Some(path)) => {
+                if let Ok(conn) = self.db_conn.lock() {
+                    let _ =
+                        db::set_job_output_archive(&conn, job_id, Some(&path.to_string_lossy()));
+                }
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!(
+                "[completion_handler] Warning: Failed to archive outputs of job {}: {}",
+                job_id, e
+            ),
+        }
+    }
+
     /// Verify that output files exist and are readable
     fn verify_output_files(
         &self,
@@ -181,30 +311,17 @@ impl CompletionHandler {
             None
         };
 
-        // Read enrichment content if path provided (optional, don't fail if missing)
-        let enrichment_content = if let Some(enrichment_path) = &metadata.enrichment_output_path {
-            if enrichment_path.exists() {
-                match fs::read_to_string(enrichment_path) {
-                    Ok(content) => Some(content),
-                    Err(e) => {
-                        eprintln!(
-                            "[completion_handler] Warning: Failed to read enrichment file {:?}: {}",
-                            enrichment_path, e
-                        );
-                        None
-                    }
-                }
-            } else {
-                None
-            }
-        } else {
-            None
-        };
+        // Enrichment and sources are optional, don't fail if missing
+        let enrichment_content =
+            read_optional_output(metadata.enrichment_output_path.as_ref(), "enrichment");
+        let sources_content =
+            read_optional_output(metadata.sources_output_path.as_ref(), "sources");
 
         Ok(VerifiedOutputs {
             primary_content,
             secondary_content,
             enrichment_content,
+            sources_content,
         })
     }
 
@@ -214,84 +331,59 @@ impl CompletionHandler {
         outputs: &VerifiedOutputs,
         metadata: &JobMetadata,
     ) -> Result<ParsedOutput, CompletionError> {
-        match metadata.job_type {
-            JobType::CompanyResearch => {
-                let people = if let Some(ref people_json) = outputs.secondary_content {
-                    match serde_json::from_str::<Vec<serde_json::Value>>(people_json) {
-                        Ok(p) => Some(p),
-                        Err(e) => {
-                            eprintln!(
-                                "[completion_handler] Warning: Failed to parse people JSON: {}",
-                                e
-                            );
-                            None
-                        }
-                    }
-                } else {
-                    None
-                };
+        let sources = parse_json::<Vec<ResearchSource>>(
+            OutputKind::Sources,
+            metadata.sources_output_path.as_ref(),
+            outputs.sources_content.as_deref(),
+        )?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| !s.url.trim().is_empty())
+        .collect();
 
-                // Parse enrichment data (optional)
-                let enrichment = if let Some(ref enrichment_json) = outputs.enrichment_content {
-                    match serde_json::from_str::<LeadEnrichment>(enrichment_json) {
-                        Ok(e) => Some(e),
-                        Err(e) => {
-                            eprintln!("[completion_handler] Warning: Failed to parse lead enrichment JSON: {}", e);
-                            None
-                        }
-                    }
-                } else {
-                    None
-                };
-
-                Ok(ParsedOutput::CompanyResearch {
-                    profile: outputs.primary_content.clone(),
-                    people,
-                    enrichment,
-                })
-            }
-            JobType::PersonResearch => {
-                // Parse enrichment data (optional)
-                let enrichment = if let Some(ref enrichment_json) = outputs.enrichment_content {
-                    match serde_json::from_str::<PersonEnrichment>(enrichment_json) {
-                        Ok(e) => Some(e),
-                        Err(e) => {
-                            eprintln!("[completion_handler] Warning: Failed to parse person enrichment JSON: {}", e);
-                            None
-                        }
-                    }
-                } else {
-                    None
-                };
-
-                Ok(ParsedOutput::PersonResearch {
-                    profile: outputs.primary_content.clone(),
-                    enrichment,
-                })
-            }
+        match metadata.job_type {
+            JobType::CompanyResearch => Ok(ParsedOutput::CompanyResearch {
+                profile: outputs.primary_content.clone(),
+                people: parse_json(
+                    OutputKind::People,
+                    metadata.secondary_output_path.as_ref(),
+                    outputs.secondary_content.as_deref(),
+                )?,
+                enrichment: parse_json::<LeadEnrichment>(
+                    OutputKind::LeadEnrichment,
+                    metadata.enrichment_output_path.as_ref(),
+                    outputs.enrichment_content.as_deref(),
+                )?,
+                sources,
+            }),
+            JobType::PersonResearch => Ok(ParsedOutput::PersonResearch {
+                profile: outputs.primary_content.clone(),
+                enrichment: parse_json::<PersonEnrichment>(
+                    OutputKind::PersonEnrichment,
+                    metadata.enrichment_output_path.as_ref(),
+                    outputs.enrichment_content.as_deref(),
+                )?,
+                sources,
+            }),
             JobType::Scoring => {
-                let score_data: serde_json::Value = serde_json::from_str(&outputs.primary_content)
-                    .map_err(|e| {
-                        CompletionError::ParseError(format!("Invalid score JSON: {}", e))
-                    })?;
-
-                // Validate required fields
-                if score_data.get("passesRequirements").is_none() {
-                    return Err(CompletionError::ValidationError(
-                        "Score JSON missing 'passesRequirements' field".to_string(),
-                    ));
-                }
-
+                let score_data = parse_json(
+                    OutputKind::Score,
+                    Some(&metadata.primary_output_path),
+                    Some(&outputs.primary_content),
+                )?
+                .unwrap_or_default();
                 Ok(ParsedOutput::Scoring { score_data })
             }
             JobType::Conversation => Ok(ParsedOutput::Conversation {
                 topics: outputs.primary_content.clone(),
             }),
             JobType::LeadFinder => {
-                let leads: Vec<serde_json::Value> = serde_json::from_str(&outputs.primary_content)
-                    .map_err(|e| {
-                        CompletionError::ParseError(format!("Invalid leads JSON: {}", e))
-                    })?;
+                let leads = parse_json(
+                    OutputKind::LeadFinder,
+                    Some(&metadata.primary_output_path),
+                    Some(&outputs.primary_content),
+                )?
+                .unwrap_or_default();
                 Ok(ParsedOutput::LeadFinder { leads })
             }
         }
@@ -300,6 +392,7 @@ impl CompletionHandler {
     /// Update database with parsed output (wrapped in a transaction for atomicity)
     fn update_database(
         &self,
+        job_id: &str,
         parsed: &ParsedOutput,
         metadata: &JobMetadata,
     ) -> Result<(), CompletionError> {
@@ -312,7 +405,15 @@ impl CompletionHandler {
             CompletionError::DatabaseError(format!("Failed to start transaction: {}", e))
         })?;
 
-        let result = self.update_database_in_tx(&tx, parsed, metadata);
+        // Record the phase in the same transaction, so a resumed completion can
+        // never apply the same results twice
+        let result = self
+            .update_database_in_tx(&tx, job_id, parsed, metadata)
+            .and_then(|()| {
+                let state = serde_json::to_string(&CompletionPhase::DatabaseUpdated).ok();
+                db::update_job_completion_state(&tx, job_id, state.as_deref())
+                    .map_err(|e| CompletionError::DatabaseError(e.to_string()))
+            });
 
         match result {
             Ok(()) => {
@@ -336,6 +437,7 @@ impl CompletionHandler {
     fn update_database_in_tx(
         &self,
         tx: &rusqlite::Transaction,
+        job_id: &str,
         parsed: &ParsedOutput,
         metadata: &JobMetadata,
     ) -> Result<(), CompletionError> {
@@ -344,80 +446,20 @@ impl CompletionHandler {
                 profile,
                 people,
                 enrichment,
-            } => {
-                let lead_id = metadata.entity_id;
-
-                // Upsert people by (lead_id, first_name, last_name) so existing IDs and
-                // already-researched data survive a re-run of company research.
-                if let Some(people_data) = people {
-                    let now = chrono::Utc::now().timestamp();
-                    for p in people_data {
-                        let first_name = extract_first_name(p);
-                        let last_name = extract_last_name(p);
-                        let email = p.get("email").and_then(|v| v.as_str());
-                        let title = p.get("title").and_then(|v| v.as_str());
-                        let linkedin_url = p.get("linkedinUrl").and_then(|v| v.as_str());
-                        let management_level = p.get("managementLevel").and_then(|v| v.as_str());
-                        let year_joined = p.get("yearJoined").and_then(|v| v.as_i64());
-
-                        let existing_id: Option<i64> = tx.query_row(
-                            "SELECT id FROM people WHERE lead_id = ?1 AND first_name = ?2 AND last_name = ?3 LIMIT 1",
-                            rusqlite::params![lead_id, first_name, last_name],
-                            |row| row.get(0),
-                        ).optional().map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
-
-                        if let Some(id) = existing_id {
-                            // Only fill in NULL fields — preserve any user edits or prior enrichment
-                            tx.execute(
-                                "UPDATE people SET
-                                    email = COALESCE(email, ?1),
-                                    title = COALESCE(title, ?2),
-                                    linkedin_url = COALESCE(linkedin_url, ?3),
-                                    management_level = COALESCE(management_level, ?4),
-                                    year_joined = COALESCE(year_joined, ?5)
-                                 WHERE id = ?6",
-                                rusqlite::params![
-                                    email,
-                                    title,
-                                    linkedin_url,
-                                    management_level,
-                                    year_joined,
-                                    id
-                                ],
-                            )
-                            .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
-                        } else {
-                            tx.execute(
-                                "INSERT INTO people (first_name, last_name, email, title, linkedin_url, management_level, year_joined, lead_id, research_status, user_status, created_at)
-                                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 'pending', 'new', ?9)",
-                                rusqlite::params![first_name, last_name, email, title, linkedin_url, management_level, year_joined, lead_id, now],
-                            ).map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
-                        }
-                    }
-                }
-
-                // Update lead with company profile
-                let now = chrono::Utc::now().timestamp();
-                let rows = tx.execute(
-                    "UPDATE leads SET research_status = ?1, company_profile = ?2, researched_at = ?3 WHERE id = ?4",
-                    rusqlite::params!["completed", profile, now, lead_id],
-                ).map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
-                if rows == 0 {
-                    return Err(CompletionError::ValidationError(format!(
-                        "Lead {} no longer exists; company research output discarded",
-                        lead_id
-                    )));
-                }
-
-                // Apply enrichment data if available (only updates NULL fields)
-                if let Some(e) = enrichment {
-                    db::enrich_lead(tx, lead_id, e)
-                        .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
-                }
-            }
+                sources,
+            } => apply_company_research(
+                tx,
+                job_id,
+                metadata.entity_id,
+                profile,
+                people.as_deref(),
+                enrichment.as_ref(),
+                sources,
+            )?,
             ParsedOutput::PersonResearch {
                 profile,
                 enrichment,
+                sources,
             } => {
                 let person_id = metadata.entity_id;
                 let now = chrono::Utc::now().timestamp();
@@ -432,9 +474,17 @@ impl CompletionHandler {
                     )));
                 }
 
-                // Apply enrichment data if available (only updates NULL fields)
+                let run_id =
+                    db::insert_research_run(tx, "person", person_id, Some(job_id), profile, None)
+                        .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+                db::insert_sources(tx, "person", person_id, Some(run_id), Some(job_id), sources)
+                    .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+
+                // Apply enrichment data if available (subject to the merge policy)
                 if let Some(e) = enrichment {
-                    db::enrich_person(tx, person_id, e)
+                    db::enrich_person(tx, person_id, e, Some(job_id))
+                        .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+                    db::enrich_custom_fields(tx, "person", person_id, &e.custom_fields)
                         .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
                 }
             }
@@ -590,6 +640,23 @@ impl CompletionHandler {
                     let country = lead_data.get("country").and_then(|v| v.as_str());
                     let industry = lead_data.get("industry").and_then(|v| v.as_str());
 
+                    // Skip companies that are already leads, filling in anything missing
+                    let existing_id = db::find_matching_lead(tx, company_name, website)
+                        .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+                    if let Some(existing_id) = existing_id {
+                        let update = LeadEnrichment {
+                            website: website.map(String::from),
+                            city: city.map(String::from),
+                            state: state.map(String::from),
+                            country: country.map(String::from),
+                            industry: industry.map(String::from),
+                            ..Default::default()
+                        };
+                        db::enrich_lead(tx, existing_id, &update, Some(job_id))
+                            .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+                        continue;
+                    }
+
                     tx.execute(
                         "INSERT INTO leads (company_name, website, city, state, country, industry, research_status, user_status, created_at)
                          VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'pending', 'new', ?7)",
@@ -597,6 +664,15 @@ impl CompletionHandler {
                     ).map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
 
                     let lead_id = tx.last_insert_rowid();
+                    db::record_inserted_fields(
+                        tx,
+                        "lead",
+                        lead_id,
+                        db::FieldSourceType::Research,
+                        Some(job_id),
+                    )
+                    .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+
                     // Emit lead-created event so frontend updates incrementally
                     events::emit_lead_created(&self.app_handle, lead_id);
                 }
@@ -711,6 +787,168 @@ impl CompletionHandler {
     }
 }
 
+/// Database phase of company research: upsert people, store the profile and
+/// enrichment, and record what changed since the previous run
+fn apply_company_research(
+    tx: &rusqlite::Transaction,
+    job_id: &str,
+    lead_id: i64,
+    profile: &str,
+    people: Option<&[serde_json::Value]>,
+    enrichment: Option<&LeadEnrichment>,
+    sources: &[ResearchSource],
+) -> Result<(), CompletionError> {
+    // Snapshot the previous state so a re-run can be summarized as changes
+    let before = db::get_lead(tx, lead_id)
+        .map_err(|e| CompletionError::DatabaseError(e.to_string()))?
+        .filter(|l| l.company_profile.is_some());
+    let previous_people = match before {
+        Some(_) => db::get_people_for_lead(tx, lead_id)
+            .map_err(|e| CompletionError::DatabaseError(e.to_string()))?,
+        None => Vec::new(),
+    };
+    let mut matched_people = HashSet::new();
+    let mut people_added = Vec::new();
+
+    // Upsert people matched by email, LinkedIn URL or exact name so existing
+    // IDs and already-researched data survive a re-run of company research.
+    if let Some(people_data) = people {
+        let now = chrono::Utc::now().timestamp();
+        for p in people_data {
+            let first_name = extract_first_name(p);
+            let last_name = extract_last_name(p);
+            let email = p.get("email").and_then(|v| v.as_str());
+            let title = p.get("title").and_then(|v| v.as_str());
+            let linkedin_url = p.get("linkedinUrl").and_then(|v| v.as_str());
+            let management_level = p.get("managementLevel").and_then(|v| v.as_str());
+            let year_joined = p.get("yearJoined").and_then(|v| v.as_i64());
+
+            let existing_id =
+                db::find_matching_person(tx, lead_id, &first_name, &last_name, email, linkedin_url)
+                    .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+
+            if let Some(id) = existing_id {
+                matched_people.insert(id);
+                // Apply through the merge policy so user edits are preserved
+                let update = PersonEnrichment {
+                    email: email.map(String::from),
+                    title: title.map(String::from),
+                    management_level: management_level.map(String::from),
+                    linkedin_url: linkedin_url.map(String::from),
+                    year_joined,
+                    ..Default::default()
+                };
+                db::enrich_person(tx, id, &update, Some(job_id))
+                    .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+            } else {
+                tx.execute(
+                    "INSERT INTO people (first_name, last_name, email, title, linkedin_url, management_level, year_joined, lead_id, research_status, user_status, created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 'pending', 'new', ?9)",
+                    rusqlite::params![first_name, last_name, email, title, linkedin_url, management_level, year_joined, lead_id, now],
+                ).map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+                let person_id = tx.last_insert_rowid();
+                db::record_inserted_fields(
+                    tx,
+                    "person",
+                    person_id,
+                    db::FieldSourceType::Research,
+                    Some(job_id),
+                )
+                .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+                people_added.push(db::PersonChange {
+                    person_id: Some(person_id),
+                    name: format!("{} {}", first_name, last_name).trim().to_string(),
+                    title: title.map(String::from),
+                    management_level: management_level.map(String::from),
+                });
+            }
+        }
+    }
+
+    // Update lead with company profile
+    let now = chrono::Utc::now().timestamp();
+    let rows = tx.execute(
+        "UPDATE leads SET research_status = ?1, company_profile = ?2, researched_at = ?3 WHERE id = ?4",
+        rusqlite::params!["completed", profile, now, lead_id],
+    ).map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+    if rows == 0 {
+        return Err(CompletionError::ValidationError(format!(
+            "Lead {} no longer exists; company research output discarded",
+            lead_id
+        )));
+    }
+
+    // Keep every profile version, not just the latest, with the sources it cites
+    let run_id = db::insert_research_run(tx, "lead", lead_id, Some(job_id), profile, None)
+        .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+    db::insert_sources(tx, "lead", lead_id, Some(run_id), Some(job_id), sources)
+        .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+
+    // Apply enrichment data if available (subject to the merge policy)
+    if let Some(e) = enrichment {
+        db::enrich_lead(tx, lead_id, e, Some(job_id))
+            .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+        db::enrich_custom_fields(tx, "lead", lead_id, &e.custom_fields)
+            .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+    }
+
+    // Record what changed since the previous research, for the daily digest
+    if let Some(before) = before {
+        let people_gone = match people {
+            Some(_) => previous_people
+                .iter()
+                .filter(|p| !matched_people.contains(&p.id))
+                .map(db::person_change)
+                .collect(),
+            None => Vec::new(),
+        };
+        let after = db::get_lead(tx, lead_id)
+            .map_err(|e| CompletionError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| {
+                CompletionError::ValidationError(format!("Lead {} no longer exists", lead_id))
+            })?;
+        let summary = db::summarize_lead_changes(&before, &after, people_added, people_gone);
+        if !summary.is_empty() {
+            db::insert_lead_change(tx, lead_id, Some(job_id), &summary)
+                .map_err(|e| CompletionError::DatabaseError(e.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Read an optional output file, warning (not failing) when it cannot be read
+fn read_optional_output(path: Option<&PathBuf>, label: &str) -> Option<String> {
+    let path = path.filter(|p| p.exists())?;
+    match fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(e) => {
+            eprintln!(
+                "[completion_handler] Warning: Failed to read {} file {:?}: {}",
+                label, path, e
+            );
+            None
+        }
+    }
+}
+
+/// Validate an optional JSON output file against its schema and deserialize it
+fn parse_json<T: serde::de::DeserializeOwned>(
+    kind: OutputKind,
+    path: Option<&PathBuf>,
+    content: Option<&str>,
+) -> Result<Option<T>, CompletionError> {
+    let Some(content) = content else {
+        return Ok(None);
+    };
+    let file = path.cloned().unwrap_or_default();
+    let value = output_schema::parse_validated(kind, &file, content).map_err(|violations| {
+        CompletionError::ValidationError(output_schema::describe_violations(&violations))
+    })?;
+    serde_json::from_value(value)
+        .map(Some)
+        .map_err(|e| CompletionError::ParseError(format!("Invalid {}: {}", kind.label(), e)))
+}
+
 /// Helper to extract first name from people JSON
 fn extract_first_name(p: &serde_json::Value) -> String {
     if let Some(fn_val) = p.get("firstName").and_then(|v| v.as_str()) {
@@ -734,3 +972,64 @@ fn extract_last_name(p: &serde_json::Value) -> String {
         String::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::apply_company_research;
+    use crate::db::test_support::{add_lead, memory_db};
+    use crate::db::{get_lead_changes, get_people_for_lead, Person};
+
+    const PEOPLE: &str = r#"[
+        {"firstName": "Jane", "lastName": "Doe", "title": "CEO"},
+        {"name": "John Smith", "title": "CTO", "email": "john@acme.com"}
+    ]"#;
+
+    /// Run the database phase of company research in its own transaction
+    fn research(
+        conn: &mut rusqlite::Connection,
+        job_id: &str,
+        lead_id: i64,
+        profile: &str,
+        people: &str,
+    ) {
+        let people: Vec<serde_json::Value> = serde_json::from_str(people).unwrap();
+        let tx = conn.transaction().unwrap();
+        apply_company_research(&tx, job_id, lead_id, profile, Some(&people), None, &[]).unwrap();
+        tx.commit().unwrap();
+    }
+
+    #[test]
+    fn rerunning_research_keeps_people_without_contact_details() {
+        let mut conn = memory_db();
+        let lead_id = add_lead(&conn, "Acme");
+
+        research(&mut conn, "job-1", lead_id, "# Acme", PEOPLE);
+        let first = get_people_for_lead(&conn, lead_id).unwrap();
+        research(&mut conn, "job-2", lead_id, "# Acme", PEOPLE);
+        let second = get_people_for_lead(&conn, lead_id).unwrap();
+
+        assert_eq!(first.len(), 2);
+        let ids = |people: &[Person]| people.iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_eq!(ids(&second), ids(&first));
+    }
+
+    #[test]
+    fn rerunning_research_with_the_same_people_reports_no_people_changes() {
+        let mut conn = memory_db();
+        let lead_id = add_lead(&conn, "Acme");
+        let profile = |about: &str| format!("# Acme\n\n## About\n{}\n", about);
+
+        research(&mut conn, "job-1", lead_id, &profile("Widgets"), PEOPLE);
+        research(&mut conn, "job-2", lead_id, &profile("Widgets"), PEOPLE);
+        assert!(get_lead_changes(&conn, lead_id).unwrap().is_empty());
+
+        // A profile change is reported without touching the unchanged people
+        research(&mut conn, "job-3", lead_id, &profile("Gadgets"), PEOPLE);
+        let changes = get_lead_changes(&conn, lead_id).unwrap();
+        assert_eq!(changes.len(), 1);
+        let summary = &changes[0].summary;
+        assert!(!summary.sections_changed.is_empty());
+        assert!(summary.people_added.is_empty() && summary.people_gone.is_empty());
+        assert!(summary.highlights.is_empty());
+    }
+}
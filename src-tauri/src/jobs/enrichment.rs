@@ -4,6 +4,7 @@
 //! during research and used to populate database columns.
 
 use serde::Deserialize;
+use std::collections::HashMap;
 
 /// Enrichment data for leads (companies)
 #[derive(Debug, Deserialize, Default, Clone)]
@@ -20,6 +21,12 @@ pub struct LeadEnrichment {
     pub city: Option<String>,
     pub state: Option<String>,
     pub country: Option<String>,
+    /// Values for research-fillable custom fields, keyed by field key
+    #[serde(default)]
+    pub custom_fields: HashMap<String, serde_json::Value>,
+    /// Confidence (0.0-1.0) per enrichment key, e.g. {"employees": 0.7}
+    #[serde(default)]
+    pub confidence: HashMap<String, f64>,
 }
 
 /// Enrichment data for people
@@ -31,4 +38,25 @@ pub struct PersonEnrichment {
     pub management_level: Option<String>,
     pub linkedin_url: Option<String>,
     pub year_joined: Option<i64>,
+    /// Values for research-fillable custom fields, keyed by field key
+    #[serde(default)]
+    pub custom_fields: HashMap<String, serde_json::Value>,
+    /// Confidence (0.0-1.0) per enrichment key, e.g. {"employees": 0.7}
+    #[serde(default)]
+    pub confidence: HashMap<String, f64>,
+}
+
+/// A source cited by research output (sources.json)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResearchSource {
+    pub url: String,
+    pub title: Option<String>,
+    #[serde(alias = "retrieved_at")]
+    pub retrieved_at: Option<String>,
+    /// The fact taken from this source
+    pub claim: Option<String>,
+    /// Enrichment fields backed by this source, e.g. "employees" or a custom field key
+    #[serde(default)]
+    pub fields: Vec<String>,
 }
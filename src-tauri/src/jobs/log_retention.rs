@@ -0,0 +1,444 @@
+//! Retention, compaction and size limits for job logs
+//!
+//! Logs are kept line by line for the configured number of days. After that a
+//! job's lines are compacted into one gzip-compressed JSON document in
+//! `job_log_archives`. When total log storage goes over the cap, younger jobs
+//! are compacted as well and then the oldest compressed logs are dropped,
+//! leaving the job row and its timeline events as the summary. A scheduled
+//! pass applies the policy and keeps the database file itself in shape,
+//! returning free pages a few at a time so the shared connection is never
+//! held for long. A full VACUUM only runs when maintenance is started by hand.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::db::{self, JobLog, LogStorageUsage};
+
+/// How often the maintenance pass runs
+const MAINTENANCE_INTERVAL_SECS: u64 = 6 * 60 * 60;
+
+/// Vacuum once this share of the database file is free pages
+const VACUUM_FREE_RATIO: f64 = 0.25;
+
+/// Free pages returned per incremental vacuum step, each under a short lock
+const INCREMENTAL_VACUUM_PAGES: i64 = 1024;
+
+/// `PRAGMA auto_vacuum` value of a database in incremental mode
+const AUTO_VACUUM_INCREMENTAL: i64 = 2;
+
+/// Outcome of one maintenance pass
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceReport {
+    pub ran_at: i64,
+    pub compacted_jobs: usize,
+    /// Jobs whose compressed logs were dropped to stay under the cap
+    pub dropped_jobs: usize,
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+    pub vacuumed: bool,
+}
+
+/// Last maintenance pass, kept for the storage report
+#[derive(Default)]
+pub struct LogMaintenanceState(pub Mutex<Option<MaintenanceReport>>);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogStorageReport {
+    pub by_job_type: Vec<LogStorageUsage>,
+    /// Log storage measured against the cap (events excluded)
+    pub log_bytes: i64,
+    pub log_storage_cap_bytes: i64,
+    pub database_bytes: i64,
+    pub free_bytes: i64,
+    pub last_maintenance: Option<MaintenanceReport>,
+}
+
+fn lock(conn: &Mutex<Connection>) -> io::Result<std::sync::MutexGuard<'_, Connection>> {
+    conn.lock().map_err(|e| io::Error::other(e.to_string()))
+}
+
+pub fn compress_logs(logs: &[JobLog]) -> io::Result<Vec<u8>> {
+    let json = serde_json::to_vec(logs).map_err(io::Error::other)?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&json)?;
+    encoder.finish()
+}
+
+pub fn decompress_logs(compressed: &[u8]) -> io::Result<Vec<JobLog>> {
+    let mut json = String::new();
+    GzDecoder::new(compressed).read_to_string(&mut json)?;
+    serde_json::from_str(&json).map_err(io::Error::other)
+}
+
+/// Logs of a job, read from the compressed form once the job was compacted
+pub fn job_logs(
+    conn: &Connection,
+    job_id: &str,
+    after_sequence: Option<i64>,
+    limit: Option<i64>,
+) -> io::Result<Vec<JobLog>> {
+    let logs = db::get_job_logs(conn, job_id, after_sequence, limit).map_err(io::Error::other)?;
+    if !logs.is_empty() {
+        return Ok(logs);
+    }
+    let Some(compressed) = db::get_compacted_logs(conn, job_id).map_err(io::Error::other)? else {
+        return Ok(logs);
+    };
+    let after = after_sequence.unwrap_or(i64::MIN);
+    Ok(decompress_logs(&compressed)?
+        .into_iter()
+        .filter(|log| log.sequence > after)
+        .take(limit.map_or(usize::MAX, |l| l.max(0) as usize))
+        .collect())
+}
+
+/// Compact one job's logs. Returns the number of bytes saved.
+pub fn compact_job(conn: &Connection, job_id: &str) -> io::Result<i64> {
+    let logs = db::get_job_logs(conn, job_id, None, None).map_err(io::Error::other)?;
+    if logs.is_empty() {
+        return Ok(0);
+    }
+    let raw_bytes: i64 = logs.iter().map(|l| l.content.len() as i64).sum();
+    let compressed = compress_logs(&logs)?;
+    db::store_compacted_logs(conn, job_id, logs.len() as i64, raw_bytes, &compressed)
+        .map_err(io::Error::other)?;
+    Ok(raw_bytes - compressed.len() as i64)
+}
+
+/// Compact logs past the retention period, then compact and drop the oldest
+/// logs until storage fits under the cap. The lock is taken per job so running
+/// jobs can keep writing their logs.
+pub fn enforce_retention(
+    conn: &Mutex<Connection>,
+    retention_days: i64,
+    cap_bytes: i64,
+) -> io::Result<MaintenanceReport> {
+    let mut report = MaintenanceReport {
+        ran_at: chrono::Utc::now().timestamp_millis(),
+        ..Default::default()
+    };
+    let mut bytes = db::get_log_storage_bytes(&*lock(conn)?).map_err(io::Error::other)?;
+    report.bytes_before = bytes;
+
+    let cutoff = chrono::Utc::now().timestamp() - retention_days * 24 * 60 * 60;
+    let expired = db::get_jobs_with_full_logs(&*lock(conn)?, cutoff).map_err(io::Error::other)?;
+    for job_id in expired {
+        bytes -= compact_job(&*lock(conn)?, &job_id)?;
+        report.compacted_jobs += 1;
+    }
+
+    if bytes > cap_bytes {
+        let finished =
+            db::get_jobs_with_full_logs(&*lock(conn)?, i64::MAX).map_err(io::Error::other)?;
+        for job_id in finished {
+            if bytes <= cap_bytes {
+                break;
+            }
+            bytes -= compact_job(&*lock(conn)?, &job_id)?;
+            report.compacted_jobs += 1;
+        }
+    }
+
+    // Compaction savings above leave out the search index, so measure again
+    // before dropping anything
+    bytes = db::get_log_storage_bytes(&*lock(conn)?).map_err(io::Error::other)?;
+    if bytes > cap_bytes {
+        let compacted = db::get_compacted_log_jobs(&*lock(conn)?).map_err(io::Error::other)?;
+        for (job_id, size) in compacted {
+            if bytes <= cap_bytes {
+                break;
+            }
+            db::drop_compacted_logs(&*lock(conn)?, &job_id).map_err(io::Error::other)?;
+            bytes -= size;
+            report.dropped_jobs += 1;
+        }
+    }
+
+    report.bytes_after = db::get_log_storage_bytes(&*lock(conn)?).map_err(io::Error::other)?;
+    Ok(report)
+}
+
+/// Database file size and free space, in bytes
+fn database_size(conn: &Connection) -> rusqlite::Result<(i64, i64)> {
+    let page_size: i64 = conn.pragma_query_value(None, "page_size", |row| row.get(0))?;
+    let page_count: i64 = conn.pragma_query_value(None, "page_count", |row| row.get(0))?;
+    let free_pages: i64 = conn.pragma_query_value(None, "freelist_count", |row| row.get(0))?;
+    Ok((page_count * page_size, free_pages * page_size))
+}
+
+/// Refresh query planner statistics and vacuum when enough of the file is
+/// free pages. In incremental mode the pages are returned in bounded steps,
+/// releasing the lock in between. A full VACUUM rewrites the whole file under
+/// the lock, so it only runs when `full` is set; it also moves a database
+/// created before incremental mode over to it. Returns whether pages were
+/// returned to the file system.
+pub fn optimize(conn: &Mutex<Connection>, full: bool) -> io::Result<bool> {
+    let (size, free, auto_vacuum) = {
+        let conn = lock(conn)?;
+        conn.execute_batch("PRAGMA optimize")
+            .map_err(io::Error::other)?;
+        let (size, free) = database_size(&conn).map_err(io::Error::other)?;
+        let auto_vacuum: i64 = conn
+            .pragma_query_value(None, "auto_vacuum", |row| row.get(0))
+            .map_err(io::Error::other)?;
+        (size, free, auto_vacuum)
+    };
+    if size == 0 || (free as f64) < size as f64 * VACUUM_FREE_RATIO {
+        return Ok(false);
+    }
+
+    if full {
+        let conn = lock(conn)?;
+        conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")
+            .map_err(io::Error::other)?;
+        conn.execute_batch("VACUUM").map_err(io::Error::other)?;
+        return Ok(true);
+    }
+    if auto_vacuum != AUTO_VACUUM_INCREMENTAL {
+        return Ok(false);
+    }
+    loop {
+        let conn = lock(conn)?;
+        let free_pages: i64 = conn
+            .pragma_query_value(None, "freelist_count", |row| row.get(0))
+            .map_err(io::Error::other)?;
+        if free_pages == 0 {
+            return Ok(true);
+        }
+        conn.execute_batch(&format!(
+            "PRAGMA incremental_vacuum({})",
+            INCREMENTAL_VACUUM_PAGES
+        ))
+        .map_err(io::Error::other)?;
+    }
+}
+
+/// Apply the configured retention policy and optimize the database, with a
+/// full VACUUM if `full_vacuum` is set
+pub fn run_maintenance(
+    conn: &Mutex<Connection>,
+    full_vacuum: bool,
+) -> io::Result<MaintenanceReport> {
+    let settings = db::get_settings(&*lock(conn)?).map_err(io::Error::other)?;
+    let mut report = enforce_retention(
+        conn,
+        settings.log_retention_days,
+        settings.log_storage_cap_mb * 1024 * 1024,
+    )?;
+    report.vacuumed = optimize(conn, full_vacuum)?;
+    Ok(report)
+}
+
+/// Run maintenance and remember the result for the storage report
+pub fn run_and_record(
+    conn: &Mutex<Connection>,
+    app: &AppHandle,
+    full_vacuum: bool,
+) -> io::Result<MaintenanceReport> {
+    let report = run_maintenance(conn, full_vacuum)?;
+    if report.compacted_jobs > 0 || report.dropped_jobs > 0 || report.vacuumed {
+        eprintln!(
+            "[log_retention] Compacted {} jobs, dropped {}, {} -> {} bytes{}",
+            report.compacted_jobs,
+            report.dropped_jobs,
+            report.bytes_before,
+            report.bytes_after,
+            if report.vacuumed { ", vacuumed" } else { "" }
+        );
+    }
+    if let Ok(mut last) = app.state::<LogMaintenanceState>().0.lock() {
+        *last = Some(report.clone());
+    }
+    Ok(report)
+}
+
+/// Run maintenance now and then every few hours on a background thread,
+/// purging expired output archives on the same schedule
+pub fn spawn_scheduler(conn: Arc<Mutex<Connection>>, app: AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("log-maintenance".to_string())
+        .spawn(move || loop {
+            if let Err(e) = run_and_record(&conn, &app, false) {
+                eprintln!("[log_retention] Maintenance failed: {}", e);
+            }
+            super::archive::purge_expired(&conn, &app);
+            std::thread::sleep(Duration::from_secs(MAINTENANCE_INTERVAL_SECS));
+        });
+    if let Err(e) = spawned {
+        eprintln!("[log_retention] Failed to start maintenance thread: {}", e);
+    }
+}
+
+pub fn storage_report(
+    conn: &Connection,
+    last_maintenance: Option<MaintenanceReport>,
+) -> rusqlite::Result<LogStorageReport> {
+    let settings = db::get_settings(conn)?;
+    let (database_bytes, free_bytes) = database_size(conn)?;
+    Ok(LogStorageReport {
+        by_job_type: db::get_log_storage_by_job_type(conn)?,
+        log_bytes: db::get_log_storage_bytes(conn)?,
+        log_storage_cap_bytes: settings.log_storage_cap_mb * 1024 * 1024,
+        database_bytes,
+        free_bytes,
+        last_maintenance,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{enforce_retention, job_logs, optimize};
+    use crate::db::test_support::{add_job, memory_db};
+    use crate::db::{
+        get_compacted_logs, get_log_storage_by_job_type, insert_job_events,
+        insert_job_logs_batch_full, BatchJobEvent, BatchLogEntry,
+    };
+    use rusqlite::Connection;
+    use std::sync::Mutex;
+
+    #[test]
+    fn job_logs_are_compacted_after_retention_and_capped() {
+        let conn = memory_db();
+        for id in ["old", "new"] {
+            add_job(&conn, id, "company", 1, "completed");
+            let logs: Vec<BatchLogEntry> = (0..50)
+                .map(|sequence| BatchLogEntry {
+                    job_id: id.to_string(),
+                    log_type: "assistant".to_string(),
+                    content: format!(r#"{{"type":"assistant","line":{}}}"#, sequence),
+                    tool_name: None,
+                    sequence,
+                    source: "stdout".to_string(),
+                })
+                .collect();
+            insert_job_logs_batch_full(&conn, &logs).unwrap();
+        }
+        conn.execute(
+            "UPDATE jobs SET created_at = created_at - 20 * 86400 WHERE id = 'old'",
+            [],
+        )
+        .unwrap();
+        let conn = Mutex::new(conn);
+
+        // Only the job past retention is compacted; its logs stay readable
+        let report = enforce_retention(&conn, 14, i64::MAX).unwrap();
+        assert_eq!((report.compacted_jobs, report.dropped_jobs), (1, 0));
+        assert!(report.bytes_after < report.bytes_before);
+        let conn = conn.into_inner().unwrap();
+        let logs = job_logs(&conn, "old", Some(44), Some(3)).unwrap();
+        assert_eq!(
+            logs.iter().map(|l| l.sequence).collect::<Vec<_>>(),
+            [45, 46, 47]
+        );
+        let usage = get_log_storage_by_job_type(&conn).unwrap();
+        assert_eq!(
+            (usage[0].jobs, usage[0].log_lines, usage[0].compacted_jobs),
+            (2, 50, 1)
+        );
+
+        // Over the cap the younger job is compacted and the oldest dropped
+        let conn = Mutex::new(conn);
+        let report = enforce_retention(&conn, 14, 0).unwrap();
+        assert_eq!((report.compacted_jobs, report.dropped_jobs), (1, 2));
+        let conn = conn.into_inner().unwrap();
+        assert!(get_compacted_logs(&conn, "old").unwrap().is_none());
+        assert!(job_logs(&conn, "new", None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn timeline_events_do_not_count_against_the_log_cap() {
+        let conn = memory_db();
+        add_job(&conn, "job-1", "company", 1, "completed");
+        insert_job_logs_batch_full(
+            &conn,
+            &[BatchLogEntry {
+                job_id: "job-1".to_string(),
+                log_type: "assistant".to_string(),
+                content: "x".repeat(100),
+                tool_name: None,
+                sequence: 0,
+                source: "stdout".to_string(),
+            }],
+        )
+        .unwrap();
+        insert_job_events(
+            &conn,
+            &[BatchJobEvent {
+                job_id: "job-1".to_string(),
+                sequence: 0,
+                kind: "message".to_string(),
+                tool_use_id: None,
+                tool_name: None,
+                content: Some("y".repeat(10_000)),
+                input: None,
+                is_error: false,
+                timestamp: 0,
+                duration_ms: None,
+            }],
+        )
+        .unwrap();
+
+        let conn = Mutex::new(conn);
+        let report = enforce_retention(&conn, 14, 1_000).unwrap();
+        assert!((100..1_000).contains(&report.bytes_before));
+        assert_eq!((report.compacted_jobs, report.dropped_jobs), (0, 0));
+        let usage = get_log_storage_by_job_type(&conn.into_inner().unwrap()).unwrap();
+        assert_eq!(usage[0].event_bytes, 10_000);
+    }
+
+    /// Database holding `rows` rows of filler, then all deleted again
+    fn database_with_free_pages(auto_vacuum: &str, rows: i64) -> Mutex<Connection> {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "auto_vacuum", auto_vacuum)
+            .unwrap();
+        conn.execute_batch("CREATE TABLE filler (data BLOB)")
+            .unwrap();
+        conn.execute(
+            "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < ?1)
+             INSERT INTO filler SELECT zeroblob(4000) FROM n",
+            [rows],
+        )
+        .unwrap();
+        conn.execute("DELETE FROM filler", []).unwrap();
+        Mutex::new(conn)
+    }
+
+    fn free_pages(conn: &Mutex<Connection>) -> i64 {
+        conn.lock()
+            .unwrap()
+            .pragma_query_value(None, "freelist_count", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn free_pages_are_returned_in_steps_and_full_vacuum_is_on_demand() {
+        // More free pages than one step returns
+        let conn = database_with_free_pages("INCREMENTAL", 3000);
+        assert!(free_pages(&conn) > 2048);
+        assert!(optimize(&conn, false).unwrap());
+        assert_eq!(free_pages(&conn), 0);
+
+        // A database from before incremental mode is only vacuumed on demand,
+        // which also switches it over
+        let conn = database_with_free_pages("NONE", 100);
+        assert!(!optimize(&conn, false).unwrap());
+        assert!(free_pages(&conn) > 0);
+        assert!(optimize(&conn, true).unwrap());
+        assert_eq!(free_pages(&conn), 0);
+        let mode: i64 = conn
+            .lock()
+            .unwrap()
+            .pragma_query_value(None, "auto_vacuum", |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, 2);
+    }
+}
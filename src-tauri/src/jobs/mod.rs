@@ -1,9 +1,17 @@
+pub mod archive;
 pub mod completion_handler;
 pub mod enrichment;
+pub mod log_retention;
+pub mod output_schema;
+pub mod process;
+pub mod progress;
 pub mod queue;
 pub mod recovery;
 pub mod result_parser;
+pub mod shutdown;
+pub mod stream_events;
 pub mod stream_processor;
+pub mod transcript;
 
 pub use queue::*;
 pub use result_parser::*;
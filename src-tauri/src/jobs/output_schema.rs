@@ -0,0 +1,381 @@
+//! JSON Schemas for agent output files
+//!
+//! Every JSON file a job writes (people, enrichment, sources, score and
+//! lead-finder results) is validated against a schema before it reaches the
+//! database. Violations carry the file and the JSON pointer of the offending
+//! value, so they can be shown to the user and sent back to the agent in a
+//! repair turn.
+
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use super::result_parser::{JobMetadata, JobType};
+
+const PEOPLE_SCHEMA: &str = r#"{
+  "type": "array",
+  "items": {
+    "type": "object",
+    "properties": {
+      "firstName": { "type": "string", "minLength": 1 },
+      "lastName": { "type": ["string", "null"] },
+      "name": { "type": "string", "minLength": 1 },
+      "email": { "type": ["string", "null"] },
+      "title": { "type": ["string", "null"] },
+      "linkedinUrl": { "type": ["string", "null"] },
+      "managementLevel": { "enum": ["C-Level", "VP", "Director", "Manager", "IC", null] },
+      "yearJoined": { "type": ["integer", "null"], "minimum": 1900, "maximum": 2100 }
+    },
+    "anyOf": [{ "required": ["firstName"] }, { "required": ["name"] }]
+  }
+}"#;
+
+const LEAD_ENRICHMENT_SCHEMA: &str = r#"{
+  "type": "object",
+  "properties": {
+    "website": { "type": ["string", "null"] },
+    "industry": { "type": ["string", "null"] },
+    "subIndustry": { "type": ["string", "null"] },
+    "employees": { "type": ["integer", "null"], "minimum": 0 },
+    "employeeRange": { "type": ["string", "null"] },
+    "revenue": { "type": ["number", "null"], "minimum": 0 },
+    "revenueRange": { "type": ["string", "null"] },
+    "companyLinkedinUrl": { "type": ["string", "null"] },
+    "city": { "type": ["string", "null"] },
+    "state": { "type": ["string", "null"] },
+    "country": { "type": ["string", "null"] },
+    "customFields": { "type": "object" },
+    "confidence": {
+      "type": "object",
+      "additionalProperties": { "type": "number", "minimum": 0, "maximum": 1 }
+    }
+  }
+}"#;
+
+const PERSON_ENRICHMENT_SCHEMA: &str = r#"{
+  "type": "object",
+  "properties": {
+    "email": { "type": ["string", "null"] },
+    "title": { "type": ["string", "null"] },
+    "managementLevel": { "enum": ["C-Level", "VP", "Director", "Manager", "IC", null] },
+    "linkedinUrl": { "type": ["string", "null"] },
+    "yearJoined": { "type": ["integer", "null"], "minimum": 1900, "maximum": 2100 },
+    "customFields": { "type": "object" },
+    "confidence": {
+      "type": "object",
+      "additionalProperties": { "type": "number", "minimum": 0, "maximum": 1 }
+    }
+  }
+}"#;
+
+const SOURCES_SCHEMA: &str = r#"{
+  "type": "array",
+  "items": {
+    "type": "object",
+    "required": ["url"],
+    "properties": {
+      "url": { "type": "string", "minLength": 1 },
+      "title": { "type": ["string", "null"] },
+      "retrievedAt": { "type": ["string", "null"] },
+      "claim": { "type": ["string", "null"] },
+      "fields": { "type": "array", "items": { "type": "string" } }
+    }
+  }
+}"#;
+
+const SCORE_SCHEMA: &str = r#"{
+  "type": "object",
+  "required": ["passesRequirements", "totalScore", "tier"],
+  "properties": {
+    "passesRequirements": { "type": "boolean" },
+    "requirementResults": {
+      "type": "array",
+      "items": {
+        "type": "object",
+        "required": ["id", "passed"],
+        "properties": {
+          "id": { "type": "string" },
+          "name": { "type": "string" },
+          "passed": { "type": "boolean" },
+          "reason": { "type": "string" }
+        }
+      }
+    },
+    "totalScore": { "type": "number", "minimum": 0, "maximum": 100 },
+    "scoreBreakdown": {
+      "type": "array",
+      "items": {
+        "type": "object",
+        "required": ["id", "score"],
+        "properties": {
+          "id": { "type": "string" },
+          "name": { "type": "string" },
+          "weight": { "type": "number" },
+          "score": { "type": "number", "minimum": 0, "maximum": 100 },
+          "weightedScore": { "type": "number" },
+          "reason": { "type": "string" }
+        }
+      }
+    },
+    "tier": { "enum": ["hot", "warm", "nurture", "disqualified"] },
+    "scoringNotes": { "type": ["string", "null"] }
+  }
+}"#;
+
+const LEAD_FINDER_SCHEMA: &str = r#"{
+  "type": "array",
+  "items": {
+    "type": "object",
+    "required": ["companyName"],
+    "properties": {
+      "companyName": { "type": "string", "minLength": 1 },
+      "website": { "type": ["string", "null"] },
+      "city": { "type": ["string", "null"] },
+      "state": { "type": ["string", "null"] },
+      "country": { "type": ["string", "null"] },
+      "industry": { "type": ["string", "null"] }
+    }
+  }
+}"#;
+
+/// The kinds of JSON output files agents write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputKind {
+    People,
+    LeadEnrichment,
+    PersonEnrichment,
+    Sources,
+    Score,
+    LeadFinder,
+}
+
+impl OutputKind {
+    pub fn schema(self) -> &'static str {
+        match self {
+            OutputKind::People => PEOPLE_SCHEMA,
+            OutputKind::LeadEnrichment => LEAD_ENRICHMENT_SCHEMA,
+            OutputKind::PersonEnrichment => PERSON_ENRICHMENT_SCHEMA,
+            OutputKind::Sources => SOURCES_SCHEMA,
+            OutputKind::Score => SCORE_SCHEMA,
+            OutputKind::LeadFinder => LEAD_FINDER_SCHEMA,
+        }
+    }
+
+    /// The compiled schema, built once per kind (one slot per variant)
+    fn validator(self) -> &'static jsonschema::Validator {
+        static VALIDATORS: [OnceLock<jsonschema::Validator>; 6] = [const { OnceLock::new() }; 6];
+        VALIDATORS[self as usize].get_or_init(|| {
+            let schema: Value =
+                serde_json::from_str(self.schema()).expect("built-in schema is JSON");
+            jsonschema::validator_for(&schema).expect("built-in schema is valid")
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OutputKind::People => "people JSON",
+            OutputKind::LeadEnrichment => "company enrichment JSON",
+            OutputKind::PersonEnrichment => "person enrichment JSON",
+            OutputKind::Sources => "sources JSON",
+            OutputKind::Score => "score JSON",
+            OutputKind::LeadFinder => "leads JSON",
+        }
+    }
+}
+
+/// One problem found in an output file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    pub kind: Option<OutputKind>,
+    pub file: PathBuf,
+    /// JSON pointer of the offending value ("" for the whole document)
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self
+            .file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.file.display().to_string());
+        if self.path.is_empty() {
+            write!(f, "{}: {}", name, self.message)
+        } else {
+            write!(f, "{} at {}: {}", name, self.path, self.message)
+        }
+    }
+}
+
+/// Join violations into one error message
+pub fn describe_violations(violations: &[SchemaViolation]) -> String {
+    violations
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Parse `content` and validate it against the schema for `kind`
+pub fn parse_validated(
+    kind: OutputKind,
+    file: &Path,
+    content: &str,
+) -> Result<Value, Vec<SchemaViolation>> {
+    let violation = |path: String, message: String| SchemaViolation {
+        kind: Some(kind),
+        file: file.to_path_buf(),
+        path,
+        message,
+    };
+
+    let value: Value = serde_json::from_str(content)
+        .map_err(|e| vec![violation(String::new(), format!("invalid JSON: {}", e))])?;
+
+    let violations: Vec<SchemaViolation> = kind
+        .validator()
+        .iter_errors(&value)
+        .map(|e| violation(e.instance_path().as_str().to_string(), e.to_string()))
+        .collect();
+
+    if violations.is_empty() {
+        Ok(value)
+    } else {
+        Err(violations)
+    }
+}
+
+/// JSON output files of a job, with the schema each must match
+fn json_outputs(metadata: &JobMetadata) -> Vec<(OutputKind, &PathBuf)> {
+    let mut outputs = Vec::new();
+    match metadata.job_type {
+        JobType::CompanyResearch => {
+            if let Some(path) = &metadata.secondary_output_path {
+                outputs.push((OutputKind::People, path));
+            }
+            if let Some(path) = &metadata.enrichment_output_path {
+                outputs.push((OutputKind::LeadEnrichment, path));
+            }
+        }
+        JobType::PersonResearch => {
+            if let Some(path) = &metadata.enrichment_output_path {
+                outputs.push((OutputKind::PersonEnrichment, path));
+            }
+        }
+        JobType::Scoring => outputs.push((OutputKind::Score, &metadata.primary_output_path)),
+        JobType::LeadFinder => {
+            outputs.push((OutputKind::LeadFinder, &metadata.primary_output_path))
+        }
+        JobType::Conversation => {}
+    }
+    if let Some(path) = &metadata.sources_output_path {
+        outputs.push((OutputKind::Sources, path));
+    }
+    outputs
+}
+
+/// Check every output file a finished job wrote. Optional files may be missing;
+/// the primary output must exist and not be empty.
+pub fn check_job_outputs(metadata: &JobMetadata) -> Vec<SchemaViolation> {
+    let primary = &metadata.primary_output_path;
+    let primary_ok = fs::read_to_string(primary)
+        .map(|c| !c.trim().is_empty())
+        .unwrap_or(false);
+    if !primary_ok {
+        return vec![SchemaViolation {
+            kind: None,
+            file: primary.clone(),
+            path: String::new(),
+            message: "file is missing or empty".to_string(),
+        }];
+    }
+
+    let mut violations = Vec::new();
+    for (kind, path) in json_outputs(metadata) {
+        if let Ok(content) = fs::read_to_string(path) {
+            if let Err(found) = parse_validated(kind, path, &content) {
+                violations.extend(found);
+            }
+        }
+    }
+    violations
+}
+
+/// Follow-up prompt asking the agent to fix its output files in the same session
+pub fn repair_prompt(violations: &[SchemaViolation]) -> String {
+    let mut prompt = String::from(
+        "Some of the output files you wrote failed validation. Fix them now.\n\nProblems:\n",
+    );
+    for v in violations {
+        prompt.push_str(&format!("- {}\n", v));
+    }
+
+    let mut files: Vec<(&PathBuf, Option<OutputKind>)> = Vec::new();
+    for v in violations {
+        if !files.iter().any(|(f, _)| *f == &v.file) {
+            files.push((&v.file, v.kind));
+        }
+    }
+    for (file, kind) in files {
+        match kind {
+            Some(kind) => prompt.push_str(&format!(
+                "\nRewrite {} (the {}) so it is valid JSON matching this JSON Schema:\n```json\n{}\n```\n",
+                file.display(),
+                kind.label(),
+                kind.schema()
+            )),
+            None => prompt.push_str(&format!(
+                "\nWrite the missing output file: {}\n",
+                file.display()
+            )),
+        }
+    }
+    prompt.push_str(
+        "\nKeep all correct data. Do not do new research unless a value cannot be fixed otherwise. Write ONLY the corrected files.",
+    );
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_validated, repair_prompt, OutputKind};
+    use std::path::Path;
+
+    #[test]
+    fn violations_point_at_the_offending_value() {
+        let file = Path::new("/tmp/job/people.json");
+        let people = r#"[
+            { "firstName": "Jane", "managementLevel": "C-Level" },
+            { "firstName": "John", "managementLevel": "Vice President", "yearJoined": "2019" }
+        ]"#;
+        let violations = parse_validated(OutputKind::People, file, people).unwrap_err();
+        let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(violations.len(), 2);
+        assert!(paths.contains(&"/1/managementLevel"));
+        assert!(paths.contains(&"/1/yearJoined"));
+
+        let score = r#"{ "passesRequirements": true, "totalScore": 72, "tier": "lukewarm" }"#;
+        let violations = parse_validated(OutputKind::Score, file, score).unwrap_err();
+        assert_eq!(violations[0].path, "/tier");
+
+        let broken = parse_validated(OutputKind::LeadEnrichment, file, "{ not json").unwrap_err();
+        assert!(broken[0].message.starts_with("invalid JSON"));
+
+        let prompt = repair_prompt(&violations);
+        assert!(prompt.contains("people.json at /tier"));
+        assert!(prompt.contains("\"hot\", \"warm\""));
+    }
+
+    #[test]
+    fn valid_outputs_pass() {
+        let file = Path::new("enrichment.json");
+        let enrichment =
+            r#"{ "employees": 120, "industry": null, "confidence": { "employees": 0.8 } }"#;
+        assert!(parse_validated(OutputKind::LeadEnrichment, file, enrichment).is_ok());
+        let leads = r#"[{ "companyName": "Acme", "city": "Berlin" }]"#;
+        assert!(parse_validated(OutputKind::LeadFinder, file, leads).is_ok());
+    }
+}
@@ -0,0 +1,204 @@
+//! Inspecting and stopping agent processes by PID
+//!
+//! After a restart the app is no longer the parent of agent processes it
+//! started, so they can only be checked and signalled through their PID.
+
+/// What a recorded PID refers to now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessStatus {
+    /// No such process
+    Gone,
+    /// A live claude process
+    Claude,
+    /// A live process that is not claude (the PID was reused)
+    Other,
+}
+
+#[cfg(unix)]
+fn is_alive(pid: u32) -> bool {
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    // Signal 0 only checks that the process exists and can be signalled
+    kill(Pid::from_raw(pid as i32), None).is_ok()
+}
+
+#[cfg(not(unix))]
+fn is_alive(_pid: u32) -> bool {
+    false
+}
+
+/// How far a process's start may lie from its job's recorded start and still
+/// be the job's agent. The agent is spawned right after the job is marked
+/// running, once the claude binary has been located.
+const START_TOLERANCE_SECS: i64 = 60;
+
+/// Script runners the claude CLI may be started through
+const INTERPRETERS: &[&str] = &["node", "bun"];
+
+/// Arguments of a live process, argv[0] first
+fn arguments(pid: u32) -> Option<Vec<String>> {
+    if let Ok(cmdline) = std::fs::read(format!("/proc/{}/cmdline", pid)) {
+        return Some(
+            cmdline
+                .split(|b| *b == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                .collect(),
+        );
+    }
+    // ps joins the arguments with spaces, which is enough for the executable
+    let output = std::process::Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "command="])
+        .output()
+        .ok()?;
+    let command = String::from_utf8_lossy(&output.stdout);
+    let args: Vec<String> = command.split_whitespace().map(String::from).collect();
+    (!args.is_empty()).then_some(args)
+}
+
+fn basename(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+/// Whether argv belongs to the claude CLI, run directly or as a script
+fn is_claude_command(args: &[String]) -> bool {
+    let is_claude = |arg: &str| {
+        let name = basename(arg);
+        name == "claude" || name.starts_with("claude-") || name.starts_with("claude.")
+    };
+    match args {
+        [exe, ..] if is_claude(exe) => true,
+        [exe, script, ..] if INTERPRETERS.contains(&basename(exe)) => {
+            is_claude(script) || script.contains("claude-code")
+        }
+        _ => false,
+    }
+}
+
+/// Parse ps `etime` ("[[dd-]hh:]mm:ss") into seconds
+fn parse_elapsed(etime: &str) -> Option<i64> {
+    let (days, clock) = match etime.trim().split_once('-') {
+        Some((days, clock)) => (days.parse::<i64>().ok()?, clock),
+        None => (0, etime.trim()),
+    };
+    let mut secs = 0;
+    for part in clock.split(':') {
+        secs = secs * 60 + part.parse::<i64>().ok()?;
+    }
+    Some(days * 86_400 + secs)
+}
+
+/// Unix time a live process started, to the second
+fn start_time(pid: u32) -> Option<i64> {
+    let output = std::process::Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "etime="])
+        .output()
+        .ok()?;
+    let elapsed = parse_elapsed(&String::from_utf8_lossy(&output.stdout))?;
+    Some(chrono::Utc::now().timestamp() - elapsed)
+}
+
+fn started_with_job(process_started: i64, job_started: i64) -> bool {
+    (process_started - job_started).abs() <= START_TOLERANCE_SECS
+}
+
+/// What `pid` refers to now. With the job's `started_at`, a claude process
+/// that started at a different time is a reused PID too.
+pub fn process_status(pid: u32, job_started_at: Option<i64>) -> ProcessStatus {
+    if !is_alive(pid) {
+        return ProcessStatus::Gone;
+    }
+    let Some(args) = arguments(pid) else {
+        return ProcessStatus::Gone;
+    };
+    if !is_claude_command(&args) {
+        return ProcessStatus::Other;
+    }
+    match (job_started_at, start_time(pid)) {
+        (Some(job), Some(process)) if !started_with_job(process, job) => ProcessStatus::Other,
+        _ => ProcessStatus::Claude,
+    }
+}
+
+/// Stop a process that is not our child: SIGTERM first, then SIGKILL if it is
+/// still alive after `grace_secs`. Returns whether the process is gone.
+pub fn terminate(pid: u32, grace_secs: u64) -> bool {
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::{kill, Signal};
+        use nix::unistd::Pid;
+        use std::time::Duration;
+
+        let target = Pid::from_raw(pid as i32);
+        if kill(target, Signal::SIGTERM).is_err() {
+            return !is_alive(pid);
+        }
+        for _ in 0..grace_secs * 10 {
+            if !is_alive(pid) {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        eprintln!("[process] pid {} ignored SIGTERM, sending SIGKILL", pid);
+        let _ = kill(target, Signal::SIGKILL);
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    #[cfg(not(unix))]
+    let _ = grace_secs;
+    !is_alive(pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProcessStatus;
+    use super::{is_claude_command, parse_elapsed, process_status, started_with_job};
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn claude_is_recognized_by_executable_not_anywhere_in_the_command() {
+        assert!(is_claude_command(&argv(&["/usr/local/bin/claude", "-p"])));
+        assert!(is_claude_command(&argv(&[
+            "node",
+            "/usr/lib/node_modules/@anthropic-ai/claude-code/cli.js",
+        ])));
+        assert!(!is_claude_command(&argv(&["vim", "notes-about-claude.md"])));
+        assert!(!is_claude_command(&argv(&["/usr/bin/claudette"])));
+        assert!(!is_claude_command(&argv(&[
+            "node",
+            "server.js",
+            "--name",
+            "claude"
+        ])));
+    }
+
+    #[test]
+    fn elapsed_time_and_start_window_are_checked() {
+        assert_eq!(parse_elapsed("05:07"), Some(307));
+        assert_eq!(parse_elapsed(" 1:00:00\n"), Some(3600));
+        assert_eq!(parse_elapsed("2-00:00:01"), Some(172_801));
+        assert_eq!(parse_elapsed("soon"), None);
+
+        assert!(started_with_job(1_000_005, 1_000_000));
+        assert!(!started_with_job(1_090_000, 1_000_000));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn live_processes_that_are_not_claude_are_reused_pids() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let now = chrono::Utc::now().timestamp();
+        assert_eq!(process_status(pid, Some(now)), ProcessStatus::Other);
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert_eq!(process_status(pid, Some(now)), ProcessStatus::Gone);
+    }
+}
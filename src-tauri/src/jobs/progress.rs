@@ -0,0 +1,206 @@
+//! Live progress of a running job
+//!
+//! The stream processor feeds every stdout line through a `ProgressTracker`,
+//! which counts agent turns, follows the tool the agent is waiting on and
+//! checks which of the expected output files are already on disk. Together
+//! with elapsed time against the job timeout this lets the UI tell a job that
+//! is slowly working through its turns from one that stopped making progress.
+
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use super::result_parser::JobMetadata;
+use super::stream_events::StreamJsonEvent;
+use crate::db::JobProgress;
+
+/// Report progress at least this often while output keeps arriving, even
+/// when no signal other than elapsed time changed
+const PROGRESS_HEARTBEAT_MS: i64 = 5_000;
+
+/// Share of the estimate turns can account for; turns alone never get a job
+/// past this point since runs have no turn limit to measure against
+const TURNS_ESTIMATE_SHARE: f64 = 0.6;
+
+/// Turns after which half of the turn share is used up
+const TURNS_HALFWAY: f64 = 20.0;
+
+pub struct ProgressTracker {
+    started: Instant,
+    expected: Vec<PathBuf>,
+    /// Tool calls without a result yet, as (tool_use_id, name)
+    pending_tools: Vec<(String, String)>,
+    last_message_id: Option<String>,
+    last_reported_at: i64,
+    progress: JobProgress,
+}
+
+impl ProgressTracker {
+    pub fn new(metadata: &JobMetadata, timeout_secs: u64) -> Self {
+        let expected: Vec<PathBuf> = [
+            Some(&metadata.primary_output_path),
+            metadata.secondary_output_path.as_ref(),
+            metadata.enrichment_output_path.as_ref(),
+            metadata.sources_output_path.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+        let progress = JobProgress {
+            timeout_secs: timeout_secs as i64,
+            outputs_expected: expected.iter().map(|p| file_name(p)).collect(),
+            last_activity_at: chrono::Utc::now().timestamp_millis(),
+            ..Default::default()
+        };
+        Self {
+            started: Instant::now(),
+            expected,
+            pending_tools: Vec::new(),
+            last_message_id: None,
+            last_reported_at: 0,
+            progress,
+        }
+    }
+
+    /// Update the signals from one stdout line and its parsed events. Returns
+    /// the new progress when it should be reported.
+    pub fn observe(
+        &mut self,
+        line: &str,
+        events: &[StreamJsonEvent],
+        now_ms: i64,
+    ) -> Option<JobProgress> {
+        let before = self.progress.clone();
+
+        if let Ok(json) = serde_json::from_str::<Value>(line) {
+            match json.get("type").and_then(|t| t.as_str()) {
+                // One API response can arrive as several lines sharing a message id
+                Some("assistant") => {
+                    let id = json
+                        .get("message")
+                        .and_then(|m| m.get("id"))
+                        .and_then(|id| id.as_str());
+                    if id.is_none() || id != self.last_message_id.as_deref() {
+                        self.progress.turns += 1;
+                        self.last_message_id = id.map(String::from);
+                    }
+                }
+                Some("result") => {
+                    if let Some(turns) = json.get("num_turns").and_then(|n| n.as_i64()) {
+                        self.progress.turns = turns;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for event in events {
+            match event {
+                StreamJsonEvent::ToolUse { id, name, .. } => {
+                    self.pending_tools.push((id.clone(), name.clone()));
+                }
+                StreamJsonEvent::ToolResult { tool_use_id, .. } => {
+                    self.pending_tools.retain(|(id, _)| id != tool_use_id);
+                }
+                StreamJsonEvent::Result { .. } => self.pending_tools.clear(),
+                _ => {}
+            }
+        }
+        self.progress.current_tool = self.pending_tools.last().map(|(_, name)| name.clone());
+
+        self.progress.outputs_written = self
+            .expected
+            .iter()
+            .filter(|p| p.exists())
+            .map(|p| file_name(p))
+            .collect();
+        self.progress.elapsed_secs = self.started.elapsed().as_secs() as i64;
+        self.progress.last_activity_at = now_ms;
+        self.progress.estimate = estimate(&self.progress);
+
+        let changed = self.progress.turns != before.turns
+            || self.progress.current_tool != before.current_tool
+            || self.progress.outputs_written != before.outputs_written;
+        if changed || now_ms - self.last_reported_at >= PROGRESS_HEARTBEAT_MS {
+            self.last_reported_at = now_ms;
+            Some(self.progress.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Current progress with the elapsed time brought up to date
+    pub fn snapshot(&self) -> JobProgress {
+        JobProgress {
+            elapsed_secs: self.started.elapsed().as_secs() as i64,
+            ..self.progress.clone()
+        }
+    }
+}
+
+fn file_name(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+/// Blend of turns taken and outputs written, kept below 1 until the job ends.
+/// Each turn adds less than the one before.
+fn estimate(progress: &JobProgress) -> f64 {
+    let turns = progress.turns.max(0) as f64;
+    let turns = turns / (turns + TURNS_HALFWAY) * TURNS_ESTIMATE_SHARE;
+    let outputs = if progress.outputs_expected.is_empty() {
+        0.0
+    } else {
+        progress.outputs_written.len() as f64 / progress.outputs_expected.len() as f64
+    };
+    turns.max(outputs).min(0.99)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProgressTracker;
+    use crate::jobs::stream_events::parse_line;
+    use crate::jobs::JobMetadata;
+
+    #[test]
+    fn tracks_turns_current_tool_and_written_outputs() {
+        let dir = std::env::temp_dir().join(format!("qualify-progress-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let metadata = JobMetadata::from_job_row(
+            "person_research",
+            1,
+            &dir.join("person_profile.md").to_string_lossy(),
+        )
+        .unwrap();
+        let mut tracker = ProgressTracker::new(&metadata, 600);
+
+        let tool_call = r#"{"type":"assistant","message":{"id":"msg_1","content":[{"type":"tool_use","id":"t1","name":"WebSearch","input":{"query":"acme"}}]}}"#;
+        let progress = tracker
+            .observe(tool_call, &parse_line(tool_call), 1_000)
+            .unwrap();
+        assert_eq!(progress.turns, 1);
+        assert_eq!(progress.current_tool.as_deref(), Some("WebSearch"));
+        assert_eq!(
+            progress.outputs_expected,
+            ["person_profile.md", "enrichment.json", "sources.json"]
+        );
+        assert!(progress.outputs_written.is_empty());
+
+        // Same message id, nothing changed and within the heartbeat: not reported
+        let text = r#"{"type":"assistant","message":{"id":"msg_1","content":[{"type":"text","text":"Searching"}]}}"#;
+        assert!(tracker.observe(text, &parse_line(text), 2_000).is_none());
+
+        std::fs::write(dir.join("person_profile.md"), "# Jane").unwrap();
+        let result = r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"found"}]}}"#;
+        let progress = tracker.observe(result, &parse_line(result), 3_000).unwrap();
+        assert_eq!(progress.turns, 1);
+        assert_eq!(progress.current_tool, None);
+        assert_eq!(progress.outputs_written, ["person_profile.md"]);
+        assert!(progress.estimate > 0.3 && progress.estimate < 0.99);
+        assert_eq!(progress.last_activity_at, 3_000);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
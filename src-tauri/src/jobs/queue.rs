@@ -1,18 +1,24 @@
 use super::completion_handler::CompletionHandler;
+use super::output_schema::{self, SchemaViolation};
+use super::process::{self, ProcessStatus};
+use super::progress::ProgressTracker;
+use super::recovery::{self, LiveAgentProcess};
 use super::result_parser::JobMetadata;
-use super::stream_processor::StreamProcessor;
-use crate::db::{DbState, NewJob};
+use super::stream_processor::{StreamProcessor, StreamProcessorHandle};
+use crate::db::{DbState, JobLimits, NewJob};
 use crate::events;
+use crate::notifications;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::ipc::Channel;
 use tauri::{AppHandle, Manager};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::{Child, Command};
-use tokio::sync::{mpsc, Mutex, Semaphore};
+use tokio::sync::{mpsc, watch, Mutex, OwnedSemaphorePermit, Semaphore};
 use uuid::Uuid;
 
 /// Entity type for tracking which kind of entity a job operates on
@@ -34,11 +40,8 @@ pub struct EntityContext {
 }
 
 // Configuration
-const MAX_CONCURRENT_JOBS: usize = 5;
-const JOB_TIMEOUT_SECS: u64 = 600; // 10 minutes
-const QUEUE_TIMEOUT_SECS: u64 = 30;
-const GRACEFUL_SHUTDOWN_SECS: u64 = 2; // Time to wait for graceful SIGTERM shutdown
-const STREAM_DRAIN_TIMEOUT_SECS: u64 = 5; // Time to wait for stream tasks to complete
+const ADOPTED_POLL_SECS: u64 = 2; // How often adopted processes are checked for exit
+const REPAIR_TIMEOUT_SECS: u64 = 180; // Time allowed for the agent to fix invalid output files
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -52,6 +55,206 @@ pub struct StreamEvent {
 struct ActiveJob {
     cancel_tx: mpsc::Sender<()>,
     status: String,
+    job_type: String,
+    /// Process left running by a previous session rather than spawned here
+    adopted: bool,
+}
+
+/// Whether the queue starts queued jobs and accepts new ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueMode {
+    Running,
+    /// Queued jobs wait, running jobs continue, new jobs are accepted
+    Paused,
+    /// Running jobs finish, queued jobs are cancelled and new jobs are rejected
+    Draining,
+}
+
+impl QueueMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QueueMode::Running => "running",
+            QueueMode::Paused => "paused",
+            QueueMode::Draining => "draining",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueStatus {
+    pub mode: QueueMode,
+    pub running: usize,
+    pub queued: usize,
+    pub max_concurrent_jobs: usize,
+}
+
+async fn queue_status(
+    active_jobs: &Mutex<HashMap<String, ActiveJob>>,
+    mode: &watch::Sender<QueueMode>,
+    slots: &JobSlots,
+) -> QueueStatus {
+    let jobs = active_jobs.lock().await;
+    let running = jobs.values().filter(|j| j.status == "running").count();
+    QueueStatus {
+        mode: *mode.borrow(),
+        running,
+        queued: jobs.len() - running,
+        max_concurrent_jobs: slots.capacity(),
+    }
+}
+
+/// Set the queue mode, returning the previous one. Receivers are only woken
+/// when the mode actually changes, so waiting jobs keep their queue timeout.
+fn replace_mode(sender: &watch::Sender<QueueMode>, mode: QueueMode) -> QueueMode {
+    let mut previous = mode;
+    sender.send_if_modified(|current| {
+        previous = std::mem::replace(current, mode);
+        previous != mode
+    });
+    previous
+}
+
+/// Send the queue state to the frontend and the tray tooltip
+fn publish_status(app: &AppHandle, status: QueueStatus) {
+    crate::notifications::set_running(app, status.running);
+    events::emit_job_queue_changed(app, status);
+}
+
+/// Job slots whose number can change while jobs hold them. Shrinking takes
+/// idle slots away at once and the rest as running jobs release theirs;
+/// growing first cancels what an earlier shrink still owes.
+struct JobSlots {
+    semaphore: Arc<Semaphore>,
+    capacity: AtomicUsize,
+    /// Slots a shrink has yet to take away
+    owed: Arc<AtomicUsize>,
+}
+
+impl JobSlots {
+    fn new(capacity: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(capacity)),
+            capacity: AtomicUsize::new(capacity),
+            owed: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity.load(Ordering::SeqCst)
+    }
+
+    /// Change the number of slots, returning the previous number
+    fn resize(&self, capacity: usize) -> usize {
+        let previous = self.capacity.swap(capacity, Ordering::SeqCst);
+        if capacity > previous {
+            let added = capacity - previous;
+            let cancelled = take_owed(&self.owed, added);
+            self.semaphore.add_permits(added - cancelled);
+        } else if capacity < previous {
+            self.owed.fetch_add(previous - capacity, Ordering::SeqCst);
+            // Idle slots go right away, the rest as jobs release theirs
+            while self.owed.load(Ordering::SeqCst) > 0 {
+                let Ok(permit) = self.semaphore.try_acquire() else {
+                    break;
+                };
+                if take_owed(&self.owed, 1) == 1 {
+                    permit.forget();
+                }
+            }
+        }
+        previous
+    }
+
+    async fn acquire(&self) -> Result<JobSlot, SlotError> {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| SlotError::Closed)?;
+        Ok(JobSlot {
+            permit: Some(permit),
+            owed: self.owed.clone(),
+        })
+    }
+}
+
+/// Take up to `most` from the slots owed, returning how many were taken
+fn take_owed(owed: &AtomicUsize, most: usize) -> usize {
+    owed.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |o| {
+        Some(o - o.min(most))
+    })
+    .map(|o| o.min(most))
+    .unwrap_or(0)
+}
+
+/// A job slot held by a job. On release it is retired instead when a shrink
+/// still owes slots.
+struct JobSlot {
+    permit: Option<OwnedSemaphorePermit>,
+    owed: Arc<AtomicUsize>,
+}
+
+impl Drop for JobSlot {
+    fn drop(&mut self) {
+        if let Some(permit) = self.permit.take() {
+            if take_owed(&self.owed, 1) == 1 {
+                permit.forget();
+            }
+        }
+    }
+}
+
+/// Why a queued job did not get a slot
+enum SlotError {
+    Closed,
+    Timeout,
+}
+
+/// Wait until the queue is running and a job slot is free. The queue timeout
+/// only counts while the queue is running; pausing restarts it.
+async fn acquire_slot(
+    slots: Arc<JobSlots>,
+    mut mode: watch::Receiver<QueueMode>,
+    timeout_secs: u64,
+) -> Result<JobSlot, SlotError> {
+    loop {
+        if mode.wait_for(|m| *m == QueueMode::Running).await.is_err() {
+            return Err(SlotError::Closed);
+        }
+        tokio::select! {
+            slot = slots.acquire() => {
+                let slot = slot?;
+                if *mode.borrow() == QueueMode::Running {
+                    return Ok(slot);
+                }
+                // Paused while waiting: hand the slot back and wait again
+            }
+            _ = mode.changed() => {}
+            _ = tokio::time::sleep(Duration::from_secs(timeout_secs)) => {
+                return Err(SlotError::Timeout);
+            }
+        }
+    }
+}
+
+/// Counts a spawned job task for as long as it lives, so shutdown can wait
+/// for tasks to finish writing their status
+struct TaskCounter(Arc<AtomicUsize>);
+
+impl TaskCounter {
+    fn start(tasks: Arc<AtomicUsize>) -> Self {
+        tasks.fetch_add(1, Ordering::SeqCst);
+        Self(tasks)
+    }
+}
+
+impl Drop for TaskCounter {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 /// RAII guard that ensures job cleanup on drop (including panics).
@@ -163,7 +366,7 @@ impl Drop for JobGuard {
 
 /// Gracefully shutdown a child process: SIGTERM first, then SIGKILL if needed.
 /// Always calls wait() to reap the process and avoid zombies.
-async fn graceful_shutdown(mut child: Child, job_id: &str) {
+async fn graceful_shutdown(mut child: Child, job_id: &str, grace_secs: u64) {
     // Try SIGTERM first on Unix
     #[cfg(unix)]
     {
@@ -182,8 +385,7 @@ async fn graceful_shutdown(mut child: Child, job_id: &str) {
     }
 
     // Wait for graceful exit with timeout
-    let graceful_result =
-        tokio::time::timeout(Duration::from_secs(GRACEFUL_SHUTDOWN_SECS), child.wait()).await;
+    let graceful_result = tokio::time::timeout(Duration::from_secs(grace_secs), child.wait()).await;
 
     match graceful_result {
         Ok(Ok(status)) => {
@@ -227,25 +429,164 @@ async fn graceful_shutdown(mut child: Child, job_id: &str) {
 }
 
 pub struct JobQueue {
-    semaphore: Arc<Semaphore>,
+    slots: Arc<JobSlots>,
+    mode: Arc<watch::Sender<QueueMode>>,
     active_jobs: Arc<Mutex<HashMap<String, ActiveJob>>>,
+    /// Set once shutdown starts interrupting jobs; running jobs stopped from
+    /// then on are recorded as interrupted when their agent session can be
+    /// resumed
+    interrupting: Arc<AtomicBool>,
+    /// Job tasks spawned by this queue that have not returned yet
+    tasks: Arc<AtomicUsize>,
+    /// Stream processors of running jobs, flushed on shutdown
+    streams: Arc<Mutex<HashMap<String, StreamProcessorHandle>>>,
 }
 
 impl JobQueue {
     pub fn new() -> Self {
+        let capacity = JobLimits::default().max_concurrent_jobs as usize;
         Self {
-            semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_JOBS)),
+            slots: Arc::new(JobSlots::new(capacity)),
+            mode: Arc::new(watch::Sender::new(QueueMode::Running)),
             active_jobs: Arc::new(Mutex::new(HashMap::new())),
+            interrupting: Arc::new(AtomicBool::new(false)),
+            tasks: Arc::new(AtomicUsize::new(0)),
+            streams: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn mode(&self) -> QueueMode {
+        *self.mode.borrow()
+    }
+
+    /// Pause, drain or resume dispatching. Running jobs are never stopped;
+    /// draining cancels the queued ones, which would otherwise never start.
+    pub async fn set_mode(&self, app: &AppHandle, mode: QueueMode) {
+        let previous = replace_mode(&self.mode, mode);
+        if previous != mode {
+            eprintln!(
+                "[job_queue] Queue mode changed from {} to {}",
+                previous.as_str(),
+                mode.as_str()
+            );
+        }
+        if mode == QueueMode::Draining && previous != mode {
+            self.cancel_where(|_, job| job.status == "queued" && !job.adopted)
+                .await;
+        }
+        publish_status(app, self.status().await);
+        crate::tray::refresh_menu(app);
+    }
+
+    pub async fn status(&self) -> QueueStatus {
+        queue_status(&self.active_jobs, &self.mode, &self.slots).await
+    }
+
+    /// Cancel active jobs, queued or running, limited to `job_ids` and/or
+    /// `job_type` when given. Returns how many were cancelled.
+    pub async fn cancel_jobs(
+        &self,
+        app: &AppHandle,
+        job_type: Option<&str>,
+        job_ids: Option<&[String]>,
+    ) -> usize {
+        let cancelled = self
+            .cancel_where(|id, job| {
+                job_type.is_none_or(|t| job.job_type == t)
+                    && job_ids.is_none_or(|ids| ids.contains(id))
+            })
+            .await;
+        publish_status(app, self.status().await);
+        cancelled
+    }
+
+    /// Number of jobs spawned by this queue whose process is running
+    pub async fn running_jobs(&self) -> usize {
+        let jobs = self.active_jobs.lock().await;
+        jobs.values()
+            .filter(|j| j.status == "running" && !j.adopted)
+            .count()
+    }
+
+    /// Number of job tasks that have not yet recorded their final status
+    pub fn pending_tasks(&self) -> usize {
+        self.tasks.load(Ordering::SeqCst)
+    }
+
+    /// Stop every queued and running job spawned by this queue. Running jobs
+    /// with an agent session are recorded as interrupted, the rest as
+    /// cancelled. Running processes get SIGTERM and the grace period before
+    /// SIGKILL. Adopted processes keep running and are adopted again on the
+    /// next start. Returns how many jobs were stopped.
+    pub async fn interrupt_jobs(&self) -> usize {
+        self.begin_interrupting();
+        self.cancel_where(|_, job| !job.adopted).await
+    }
+
+    /// Mark jobs cancelled from now on as stopped by shutdown: queued jobs are
+    /// cancelled with a shutdown message, and running jobs whose agent session
+    /// can be resumed are recorded as interrupted
+    pub fn begin_interrupting(&self) {
+        self.interrupting.store(true, Ordering::SeqCst);
+    }
+
+    /// Write buffered log lines of all running jobs to the database
+    pub async fn flush_logs(&self) -> usize {
+        let streams = self.streams.lock().await;
+        for stream in streams.values() {
+            stream.flush_buffer().await;
         }
+        streams.len()
+    }
+
+    async fn cancel_where<P>(&self, predicate: P) -> usize
+    where
+        P: Fn(&String, &ActiveJob) -> bool,
+    {
+        let cancelled: Vec<(String, ActiveJob)> = {
+            let mut jobs = self.active_jobs.lock().await;
+            let ids: Vec<String> = jobs
+                .iter()
+                .filter(|(id, job)| predicate(id, job))
+                .map(|(id, _)| id.clone())
+                .collect();
+            ids.into_iter()
+                .filter_map(|id| jobs.remove_entry(&id))
+                .collect()
+        };
+        for (job_id, job) in &cancelled {
+            if let Err(e) = job.cancel_tx.send(()).await {
+                eprintln!(
+                    "[job_queue] job_id={} Failed to send cancel signal: {}",
+                    job_id, e
+                );
+            }
+        }
+        eprintln!("[job_queue] Cancelled {} jobs", cancelled.len());
+        cancelled.len()
+    }
+
+    /// Change how many jobs may run at once. Growing takes effect immediately;
+    /// shrinking takes slots away as running jobs finish, without stopping them.
+    pub fn resize(&self, max_concurrent_jobs: usize) {
+        let previous = self.slots.resize(max_concurrent_jobs);
+        if max_concurrent_jobs == previous {
+            return;
+        }
+        eprintln!(
+            "[job_queue] Concurrent job limit changed from {} to {}",
+            previous, max_concurrent_jobs
+        );
     }
 
     /// Start a job with a completion callback that receives accumulated output
     /// Now also persists job state and logs to the database using StreamProcessor and CompletionHandler
     ///
-    /// Settings (model, use_chrome) are read from the database at job execution time.
+    /// Settings (use_chrome, job limits) are read from the database at job execution time,
+    /// and the model is resolved from the routing rules, the job type model or the default.
     ///
     /// If `entity_context` is provided, the entity's status will be reset on:
-    /// - Queue timeout (semaphore acquisition fails after 30s)
+    /// - Queue timeout (no job slot frees up within the queue timeout)
     /// - Job cancellation before running
     /// - Any error before job starts
     #[allow(clippy::too_many_arguments)]
@@ -263,9 +604,75 @@ impl JobQueue {
     where
         F: FnOnce(JobMetadata, String, bool) + Send + 'static,
     {
+        self.start_job(
+            app,
+            prompt,
+            working_dir,
+            on_event,
+            metadata,
+            entity_label,
+            entity_context,
+            None,
+            on_complete,
+        )
+        .await
+    }
+
+    /// Start a job that continues the agent session `session_id` of an
+    /// interrupted job, with `prompt` as the next message in that session
+    #[allow(clippy::too_many_arguments)]
+    pub async fn resume_job(
+        &self,
+        app: AppHandle,
+        session_id: String,
+        prompt: String,
+        working_dir: String,
+        on_event: Channel<StreamEvent>,
+        metadata: JobMetadata,
+        entity_label: String,
+        entity_context: Option<EntityContext>,
+    ) -> Result<String, String> {
+        self.start_job(
+            app,
+            prompt,
+            working_dir,
+            on_event,
+            metadata,
+            entity_label,
+            entity_context,
+            Some(session_id),
+            |_meta, _output, _success| {},
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn start_job<F>(
+        &self,
+        app: AppHandle,
+        prompt: String,
+        working_dir: String,
+        on_event: Channel<StreamEvent>,
+        metadata: JobMetadata,
+        entity_label: String,
+        entity_context: Option<EntityContext>,
+        resume_session: Option<String>,
+        on_complete: F,
+    ) -> Result<String, String>
+    where
+        F: FnOnce(JobMetadata, String, bool) + Send + 'static,
+    {
+        if self.mode() == QueueMode::Draining {
+            return Err("The job queue is draining and does not accept new jobs".to_string());
+        }
+
         let job_id = Uuid::new_v4().to_string();
-        let semaphore = self.semaphore.clone();
+        let slots = self.slots.clone();
         let active_jobs = self.active_jobs.clone();
+        let mode = self.mode.clone();
+        let interrupting = self.interrupting.clone();
+        let streams = self.streams.clone();
+        let task_counter = TaskCounter::start(self.tasks.clone());
 
         let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
 
@@ -277,27 +684,25 @@ impl JobQueue {
                 ActiveJob {
                     cancel_tx,
                     status: "queued".to_string(),
+                    job_type: metadata.job_type.as_str().to_string(),
+                    adopted: false,
                 },
             );
         }
 
         // Persist job to database
-        let job_type_str = match metadata.job_type {
-            super::result_parser::JobType::CompanyResearch => "company_research",
-            super::result_parser::JobType::PersonResearch => "person_research",
-            super::result_parser::JobType::Scoring => "scoring",
-            super::result_parser::JobType::Conversation => "conversation",
-            super::result_parser::JobType::LeadFinder => "lead_finder",
-        };
+        let job_type_str = metadata.job_type.as_str();
 
         // Read settings and persist job to database
-        let settings = {
+        let (settings, limits, model, previous_tier) = {
             let db_state: tauri::State<'_, DbState> = app.state();
             let conn = db_state.conn.lock().map_err(|e| e.to_string())?;
             let settings = crate::db::get_settings(&conn).map_err(|e| e.to_string())?;
+            let model = crate::db::resolve_job_model(&conn, job_type_str, metadata.entity_id)
+                .map_err(|e| e.to_string())?;
             eprintln!(
-                "[job_queue] job_id={} Using settings: model='{}', use_chrome={}",
-                job_id, settings.model, settings.use_chrome
+                "[job_queue] job_id={} Using settings: model='{}' ({}), use_chrome={}",
+                job_id, model.model, model.source, settings.use_chrome
             );
             let new_job = NewJob {
                 id: job_id.clone(),
@@ -305,15 +710,27 @@ impl JobQueue {
                 entity_id: metadata.entity_id,
                 entity_label: entity_label.clone(),
                 prompt: prompt.clone(),
-                model: Some(settings.model.clone()),
+                model: Some(model.model.clone()),
                 working_dir: working_dir.clone(),
                 output_path: Some(metadata.primary_output_path.to_string_lossy().to_string()),
             };
             crate::db::insert_job(&conn, &new_job).map_err(|e| e.to_string())?;
-            settings
+            let limits =
+                crate::db::get_job_limits(&conn, job_type_str).map_err(|e| e.to_string())?;
+            // Tier before scoring, so a lead newly scored hot can be told apart
+            let previous_tier = match metadata.job_type {
+                super::result_parser::JobType::Scoring => {
+                    crate::db::get_lead_score(&conn, metadata.entity_id)
+                        .map_err(|e| e.to_string())?
+                        .map(|score| score.tier)
+                }
+                _ => None,
+            };
+            (settings, limits, model.model, previous_tier)
         };
 
         // Emit job created event
+        let notify_label = entity_label.clone();
         events::emit_job_created(
             &app,
             job_id.clone(),
@@ -321,6 +738,7 @@ impl JobQueue {
             metadata.entity_id,
             entity_label,
         );
+        publish_status(&app, queue_status(&active_jobs, &mode, &slots).await);
 
         let job_id_clone = job_id.clone();
         let app_clone = app.clone();
@@ -333,6 +751,8 @@ impl JobQueue {
 
         // Spawn the job task
         tokio::spawn(async move {
+            let _task_counter = task_counter;
+
             // Create JobGuard for panic cleanup - will be defused on normal completion
             let mut job_guard = JobGuard::new(
                 job_id_clone.clone(),
@@ -365,12 +785,12 @@ impl JobQueue {
                     );
                 };
 
-            // Try to acquire semaphore with queue timeout
+            // Wait for the queue to dispatch the job, within the queue timeout
             let permit = tokio::select! {
-                permit = semaphore.acquire_owned() => {
-                    match permit {
+                slot = acquire_slot(slots.clone(), mode.subscribe(), limits.queue_timeout_secs as u64) => {
+                    match slot {
                         Ok(p) => p,
-                        Err(_) => {
+                        Err(SlotError::Closed) => {
                             let _ = on_event.send(StreamEvent {
                                 job_id: job_id_clone.clone(),
                                 event_type: "error".to_string(),
@@ -382,30 +802,39 @@ impl JobQueue {
                                 db_reset_entity_status(&db_conn, ctx, &app_clone);
                             }
                             update_job_status("error", None, Some("Failed to acquire job slot"));
+                            notifications::job_finished(&app_clone, &metadata, &notify_label, "error", None);
+                            job_guard.defuse(); // Cleanup handled manually
+                            on_complete(metadata, String::new(), false);
+                            return;
+                        }
+                        Err(SlotError::Timeout) => {
+                            let _ = on_event.send(StreamEvent {
+                                job_id: job_id_clone.clone(),
+                                event_type: "error".to_string(),
+                                content: "Queue timeout - server busy".to_string(),
+                                timestamp: chrono::Utc::now().timestamp_millis(),
+                            });
+                            active_jobs.lock().await.remove(&job_id_clone);
+                            publish_status(&app_clone, queue_status(&active_jobs, &mode, &slots).await);
+                            // Reset entity status on queue timeout
+                            if let Some(ref ctx) = entity_context {
+                                db_reset_entity_status(&db_conn, ctx, &app_clone);
+                            }
+                            update_job_status("error", None, Some("Queue timeout - server busy"));
+                            notifications::job_finished(&app_clone, &metadata, &notify_label, "error", None);
                             job_guard.defuse(); // Cleanup handled manually
                             on_complete(metadata, String::new(), false);
                             return;
                         }
                     }
                 }
-                _ = tokio::time::sleep(tokio::time::Duration::from_secs(QUEUE_TIMEOUT_SECS)) => {
-                    let _ = on_event.send(StreamEvent {
-                        job_id: job_id_clone.clone(),
-                        event_type: "error".to_string(),
-                        content: "Queue timeout - server busy".to_string(),
-                        timestamp: chrono::Utc::now().timestamp_millis(),
-                    });
-                    active_jobs.lock().await.remove(&job_id_clone);
-                    // Reset entity status on queue timeout
-                    if let Some(ref ctx) = entity_context {
-                        db_reset_entity_status(&db_conn, ctx, &app_clone);
-                    }
-                    update_job_status("error", None, Some("Queue timeout - server busy"));
-                    job_guard.defuse(); // Cleanup handled manually
-                    on_complete(metadata, String::new(), false);
-                    return;
-                }
                 _ = cancel_rx.recv() => {
+                    // A job that never started has no session to resume
+                    let message = if interrupting.load(Ordering::SeqCst) {
+                        "Cancelled by app shutdown while queued"
+                    } else {
+                        "Cancelled while queued"
+                    };
                     let _ = on_event.send(StreamEvent {
                         job_id: job_id_clone.clone(),
                         event_type: "cancelled".to_string(),
@@ -417,7 +846,7 @@ impl JobQueue {
                     if let Some(ref ctx) = entity_context {
                         db_reset_entity_status(&db_conn, ctx, &app_clone);
                     }
-                    update_job_status("cancelled", None, Some("Cancelled while queued"));
+                    update_job_status("cancelled", None, Some(message));
                     job_guard.defuse(); // Cleanup handled manually
                     on_complete(metadata, String::new(), false);
                     return;
@@ -432,6 +861,7 @@ impl JobQueue {
                 }
             }
             update_job_status("running", None, None);
+            publish_status(&app_clone, queue_status(&active_jobs, &mode, &slots).await);
 
             let _ = on_event.send(StreamEvent {
                 job_id: job_id_clone.clone(),
@@ -443,9 +873,9 @@ impl JobQueue {
             // Find claude path
             let claude_path = find_claude_path().unwrap_or_else(|| "claude".to_string());
 
-            // Build arguments using settings
-            // Note: prompt must be last as it's a positional argument
-            let mut args = vec![
+            // Build arguments using settings. The base flags are shared with
+            // the repair turn, which resumes a session of its own.
+            let mut base_args = vec![
                 "-p".to_string(),
                 "--output-format".to_string(),
                 "stream-json".to_string(),
@@ -455,14 +885,21 @@ impl JobQueue {
 
             // Add --chrome flag if enabled in settings
             if settings.use_chrome {
-                args.push("--chrome".to_string());
+                base_args.push("--chrome".to_string());
             }
 
-            // Add model from settings
-            args.push("--model".to_string());
-            args.push(settings.model.clone());
+            // Add the model resolved for this job
+            base_args.push("--model".to_string());
+            base_args.push(model);
+
+            // Continue the session of an interrupted job
+            let mut args = base_args.clone();
+            if let Some(session_id) = resume_session {
+                args.push("--resume".to_string());
+                args.push(session_id);
+            }
 
-            // Add prompt at the end (positional argument)
+            // Note: prompt must be last as it's a positional argument
             args.push(prompt);
 
             // Debug: log the command being executed
@@ -474,15 +911,7 @@ impl JobQueue {
             );
 
             // Spawn the process with kill_on_drop for safety
-            let mut child = match Command::new(&claude_path)
-                .args(&args)
-                .current_dir(&working_dir)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .stdin(Stdio::null())
-                .kill_on_drop(true) // Ensure process is killed if task panics
-                .spawn()
-            {
+            let child = match spawn_claude(&claude_path, &args, &working_dir) {
                 Ok(c) => c,
                 Err(e) => {
                     eprintln!(
@@ -511,121 +940,88 @@ impl JobQueue {
                         None,
                         Some(&format!("Failed to spawn claude: {}", e)),
                     );
+                    notifications::job_finished(
+                        &app_clone,
+                        &metadata,
+                        &notify_label,
+                        "error",
+                        None,
+                    );
                     job_guard.defuse(); // Cleanup handled manually
                     on_complete(metadata, String::new(), false);
                     return;
                 }
             };
 
-            // Store PID in database
-            if let Some(pid) = child.id() {
-                db_update_job_pid(&db_conn, &job_id_clone, pid);
-            }
-
             // Create StreamProcessor for unified stream handling
             let stream_processor =
-                StreamProcessor::new(job_id_clone.clone(), db_conn.clone(), app_clone.clone());
-
-            // Take stdout and stderr
-            let stdout = child.stdout.take();
-            let stderr = child.stderr.take();
-
-            // Create handles for spawned tasks
-            let processor_stdout = stream_processor.clone_for_task();
-            let on_event_stdout = on_event.clone();
-            let stdout_handle = tokio::spawn(async move {
-                if let Some(stdout) = stdout {
-                    let reader = BufReader::new(stdout);
-                    let mut lines = reader.lines();
-                    while let Ok(Some(line)) = lines.next_line().await {
-                        processor_stdout
-                            .process_stdout_line(line, &on_event_stdout)
-                            .await;
-                    }
-                    // Flush any remaining buffered logs
-                    processor_stdout.flush_buffer().await;
-                }
-            });
-
-            // Stderr handler - NOW ALSO PERSISTED via StreamProcessor
-            let processor_stderr = stream_processor.clone_for_task();
-            let on_event_stderr = on_event.clone();
-            let stderr_handle = tokio::spawn(async move {
-                if let Some(stderr) = stderr {
-                    let reader = BufReader::new(stderr);
-                    let mut lines = reader.lines();
-                    while let Ok(Some(line)) = lines.next_line().await {
-                        processor_stderr
-                            .process_stderr_line(line, &on_event_stderr)
-                            .await;
-                    }
-                    // Flush any remaining buffered logs
-                    processor_stderr.flush_buffer().await;
-                }
-            });
-
-            // Wait for completion with timeout
-            let result = tokio::select! {
-                status = child.wait() => {
-                    match status {
-                        Ok(s) => {
-                            let code = s.code().unwrap_or(-1);
-                            eprintln!("[job_queue] job_id={} Process exited with code: {}", job_id_clone, code);
-                            if code == 0 {
-                                ("completed".to_string(), Some(code), true)
-                            } else {
-                                ("error".to_string(), Some(code), false)
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("[job_queue] job_id={} Process wait error: {}", job_id_clone, e);
-                            ("error".to_string(), None, false)
-                        }
+                StreamProcessor::new(job_id_clone.clone(), db_conn.clone(), app_clone.clone())
+                    .with_output_limit(limits.max_accumulated_output_mb as usize * 1024 * 1024)
+                    .with_progress(ProgressTracker::new(
+                        &metadata,
+                        limits.job_timeout_secs as u64,
+                    ));
+            streams
+                .lock()
+                .await
+                .insert(job_id_clone.clone(), stream_processor.clone_for_task());
+
+            let mut result = run_claude(
+                child,
+                &stream_processor,
+                &on_event,
+                &mut cancel_rx,
+                &db_conn,
+                &job_id_clone,
+                limits.job_timeout_secs as u64,
+                &limits,
+            )
+            .await;
+
+            // Validate output files and give the agent one chance to fix them
+            // in the same session before the completion handler rejects them
+            if result.2 {
+                let violations = output_schema::check_job_outputs(&metadata);
+                if !violations.is_empty() {
+                    let repair = repair_outputs(
+                        &claude_path,
+                        &base_args,
+                        &working_dir,
+                        &violations,
+                        &stream_processor,
+                        &on_event,
+                        &mut cancel_rx,
+                        &db_conn,
+                        &job_id_clone,
+                        &limits,
+                    )
+                    .await;
+                    if let Some(repair) = repair.filter(|r| r.0 == "cancelled") {
+                        result = repair;
                     }
                 }
-                _ = tokio::time::sleep(Duration::from_secs(JOB_TIMEOUT_SECS)) => {
-                    eprintln!("[job_queue] job_id={} Job timeout after {} seconds", job_id_clone, JOB_TIMEOUT_SECS);
-                    graceful_shutdown(child, &job_id_clone).await;
-                    ("timeout".to_string(), None, false)
-                }
-                _ = cancel_rx.recv() => {
-                    eprintln!("[job_queue] job_id={} Job cancelled by user", job_id_clone);
-                    graceful_shutdown(child, &job_id_clone).await;
-                    ("cancelled".to_string(), None, false)
-                }
-            };
-
-            // Wait for stream tasks to complete with timeout to prevent hanging
-            if tokio::time::timeout(
-                Duration::from_secs(STREAM_DRAIN_TIMEOUT_SECS),
-                stdout_handle,
-            )
-            .await
-            .is_err()
-            {
-                eprintln!(
-                    "[job_queue] job_id={} Stdout stream drain timeout",
-                    job_id_clone
-                );
-            }
-            if tokio::time::timeout(
-                Duration::from_secs(STREAM_DRAIN_TIMEOUT_SECS),
-                stderr_handle,
-            )
-            .await
-            .is_err()
-            {
-                eprintln!(
-                    "[job_queue] job_id={} Stderr stream drain timeout",
-                    job_id_clone
-                );
             }
 
             // Finalize stream processor and get completion context
             let completion_ctx = stream_processor.finalize(result.2, result.1).await;
+            streams.lock().await.remove(&job_id_clone);
+
+            // A job stopped by shutdown keeps its session and outputs so it
+            // can be resumed, rather than being failed. Without a session
+            // there is nothing to resume and it is cancelled.
+            let stopped_by_shutdown =
+                result.0 == "cancelled" && interrupting.load(Ordering::SeqCst);
+            let interrupted = stopped_by_shutdown && job_session(&db_conn, &job_id_clone).is_some();
+            if interrupted {
+                result.0 = "interrupted".to_string();
+            }
 
             // Update job status in database
-            let error_msg = if !result.2 {
+            let error_msg = if interrupted {
+                Some("Interrupted by app shutdown".to_string())
+            } else if stopped_by_shutdown {
+                Some("Cancelled by app shutdown before the agent session started".to_string())
+            } else if !result.2 {
                 Some(format!("Job {} with code {:?}", result.0, result.1))
             } else {
                 None
@@ -647,13 +1043,21 @@ impl JobQueue {
 
             // Process completion atomically using CompletionHandler
             let completion_handler = CompletionHandler::new(db_conn.clone(), app_clone.clone());
-            if let Err(e) = completion_handler.process_completion(&completion_ctx, &metadata) {
+            let mut final_status = result.0.clone();
+            if interrupted {
+                // Nothing to import yet; the entity goes back to its status before the job
+                if let Some(ref ctx) = entity_context {
+                    db_reset_entity_status(&db_conn, ctx, &app_clone);
+                }
+            } else if let Err(e) = completion_handler.process_completion(&completion_ctx, &metadata)
+            {
                 eprintln!(
                     "[job_queue] job_id={} Completion handler error: {}",
                     job_id_clone, e
                 );
                 // Mark entity as failed when completion handler errors
                 completion_handler.mark_entity_failed(&metadata);
+                final_status = "error".to_string();
                 // Update job status to error
                 db_update_job_status(
                     &db_conn,
@@ -694,6 +1098,15 @@ impl JobQueue {
             // Cleanup active jobs
             active_jobs.lock().await.remove(&job_id_clone);
             drop(permit);
+            publish_status(&app_clone, queue_status(&active_jobs, &mode, &slots).await);
+
+            notifications::job_finished(
+                &app_clone,
+                &metadata,
+                &notify_label,
+                &final_status,
+                previous_tier.as_deref(),
+            );
 
             // Defuse the guard - we're completing normally
             job_guard.defuse();
@@ -705,6 +1118,89 @@ impl JobQueue {
         Ok(job_id)
     }
 
+    /// Track an agent process left running by a previous session. Its job stays
+    /// "running" until the process exits, then the outputs it wrote are imported.
+    /// Cancelling the job or exceeding the job timeout terminates the process.
+    pub fn adopt_process(
+        &self,
+        app: AppHandle,
+        db_conn: Arc<std::sync::Mutex<rusqlite::Connection>>,
+        process: LiveAgentProcess,
+    ) {
+        let active_jobs = self.active_jobs.clone();
+        tauri::async_runtime::spawn(async move {
+            let LiveAgentProcess {
+                job_id,
+                pid,
+                started_at,
+            } = process;
+            let (job_type, limits) = db_conn
+                .lock()
+                .ok()
+                .and_then(|conn| {
+                    let job = crate::db::get_job(&conn, &job_id).ok()??;
+                    let limits = crate::db::get_job_limits(&conn, &job.job_type).ok()?;
+                    Some((job.job_type, limits))
+                })
+                .unwrap_or_default();
+            let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
+            active_jobs.lock().await.insert(
+                job_id.clone(),
+                ActiveJob {
+                    cancel_tx,
+                    status: "running".to_string(),
+                    job_type,
+                    adopted: true,
+                },
+            );
+            let deadline = started_at.unwrap_or_else(|| chrono::Utc::now().timestamp())
+                + limits.job_timeout_secs;
+            let outcome = loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_secs(ADOPTED_POLL_SECS)) => {
+                        let status = tokio::task::spawn_blocking(move || {
+                            process::process_status(pid, started_at)
+                        })
+                        .await
+                        .unwrap_or(ProcessStatus::Gone);
+                        if status != ProcessStatus::Claude {
+                            break None;
+                        }
+                        if chrono::Utc::now().timestamp() > deadline {
+                            break Some(("timeout", "Adopted agent process timed out"));
+                        }
+                    }
+                    _ = cancel_rx.recv() => {
+                        break Some(("cancelled", "Adopted agent process cancelled"));
+                    }
+                }
+            };
+            active_jobs.lock().await.remove(&job_id);
+
+            match outcome {
+                None => {
+                    eprintln!(
+                        "[job_queue] job_id={} Adopted process {} exited",
+                        job_id, pid
+                    );
+                    if let Err(e) = recovery::salvage_adopted_job(&db_conn, &app, &job_id) {
+                        eprintln!(
+                            "[job_queue] job_id={} Adopted job produced no usable output: {}",
+                            job_id, e
+                        );
+                    }
+                }
+                Some((status, message)) => {
+                    let grace_secs = limits.graceful_shutdown_secs as u64;
+                    let _ =
+                        tokio::task::spawn_blocking(move || process::terminate(pid, grace_secs))
+                            .await;
+                    recovery::settle_adopted_job(&db_conn, &app, &job_id, status, message);
+                }
+            }
+        });
+    }
+
     pub async fn kill_job(&self, job_id: &str) -> Result<(), String> {
         eprintln!("[job_queue] Attempting to kill job_id={}", job_id);
         let mut jobs = self.active_jobs.lock().await;
@@ -728,6 +1224,204 @@ impl JobQueue {
 }
 
 // Helper functions for database operations that work with Arc<Mutex<Connection>>
+/// Spawn a claude process with piped output
+fn spawn_claude(claude_path: &str, args: &[String], working_dir: &str) -> std::io::Result<Child> {
+    Command::new(claude_path)
+        .args(args)
+        .current_dir(working_dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::null())
+        .kill_on_drop(true) // Ensure process is killed if task panics
+        .spawn()
+}
+
+/// Pump a claude process's output through the stream processor and wait for it
+/// to exit, time out or be cancelled. Returns (status, exit code, success).
+#[allow(clippy::too_many_arguments)]
+async fn run_claude(
+    mut child: Child,
+    stream_processor: &StreamProcessor,
+    on_event: &Channel<StreamEvent>,
+    cancel_rx: &mut mpsc::Receiver<()>,
+    db_conn: &Arc<std::sync::Mutex<rusqlite::Connection>>,
+    job_id: &str,
+    timeout_secs: u64,
+    limits: &JobLimits,
+) -> (String, Option<i32>, bool) {
+    // Store PID in database
+    if let Some(pid) = child.id() {
+        db_update_job_pid(db_conn, job_id, pid);
+    }
+
+    // Take stdout and stderr
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    // Create handles for spawned tasks
+    let processor_stdout = stream_processor.clone_for_task();
+    let on_event_stdout = on_event.clone();
+    let stdout_handle = tokio::spawn(async move {
+        if let Some(stdout) = stdout {
+            let reader = BufReader::new(stdout);
+            let mut lines = reader.lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                processor_stdout
+                    .process_stdout_line(line, &on_event_stdout)
+                    .await;
+            }
+            // Flush any remaining buffered logs
+            processor_stdout.flush_buffer().await;
+        }
+    });
+
+    // Stderr handler - NOW ALSO PERSISTED via StreamProcessor
+    let processor_stderr = stream_processor.clone_for_task();
+    let on_event_stderr = on_event.clone();
+    let stderr_handle = tokio::spawn(async move {
+        if let Some(stderr) = stderr {
+            let reader = BufReader::new(stderr);
+            let mut lines = reader.lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                processor_stderr
+                    .process_stderr_line(line, &on_event_stderr)
+                    .await;
+            }
+            // Flush any remaining buffered logs
+            processor_stderr.flush_buffer().await;
+        }
+    });
+
+    // Wait for completion with timeout
+    let result = tokio::select! {
+        status = child.wait() => {
+            match status {
+                Ok(s) => {
+                    let code = s.code().unwrap_or(-1);
+                    eprintln!("[job_queue] job_id={} Process exited with code: {}", job_id, code);
+                    if code == 0 {
+                        ("completed".to_string(), Some(code), true)
+                    } else {
+                        ("error".to_string(), Some(code), false)
+                    }
+                }
+                Err(e) => {
+                    eprintln!("[job_queue] job_id={} Process wait error: {}", job_id, e);
+                    ("error".to_string(), None, false)
+                }
+            }
+        }
+        _ = tokio::time::sleep(Duration::from_secs(timeout_secs)) => {
+            eprintln!("[job_queue] job_id={} Job timeout after {} seconds", job_id, timeout_secs);
+            graceful_shutdown(child, job_id, limits.graceful_shutdown_secs as u64).await;
+            ("timeout".to_string(), None, false)
+        }
+        _ = cancel_rx.recv() => {
+            eprintln!("[job_queue] job_id={} Job cancelled by user", job_id);
+            graceful_shutdown(child, job_id, limits.graceful_shutdown_secs as u64).await;
+            ("cancelled".to_string(), None, false)
+        }
+    };
+
+    // Wait for stream tasks to complete with timeout to prevent hanging
+    if tokio::time::timeout(
+        Duration::from_secs(limits.stream_drain_timeout_secs as u64),
+        stdout_handle,
+    )
+    .await
+    .is_err()
+    {
+        eprintln!("[job_queue] job_id={} Stdout stream drain timeout", job_id);
+    }
+    if tokio::time::timeout(
+        Duration::from_secs(limits.stream_drain_timeout_secs as u64),
+        stderr_handle,
+    )
+    .await
+    .is_err()
+    {
+        eprintln!("[job_queue] job_id={} Stderr stream drain timeout", job_id);
+    }
+
+    result
+}
+
+/// The claude session recorded for a job, if the agent got far enough to
+/// start one
+fn job_session(
+    db_conn: &Arc<std::sync::Mutex<rusqlite::Connection>>,
+    job_id: &str,
+) -> Option<String> {
+    db_conn
+        .lock()
+        .ok()
+        .and_then(|conn| crate::db::get_job(&conn, job_id).ok().flatten())
+        .and_then(|job| job.claude_session_id)
+}
+
+/// Resume the job's claude session with a "fix your JSON" turn listing the
+/// schema violations. `base_args` are the flags of the original run without
+/// its `--resume` or prompt. Returns None when no repair turn could be run.
+#[allow(clippy::too_many_arguments)]
+async fn repair_outputs(
+    claude_path: &str,
+    base_args: &[String],
+    working_dir: &str,
+    violations: &[SchemaViolation],
+    stream_processor: &StreamProcessor,
+    on_event: &Channel<StreamEvent>,
+    cancel_rx: &mut mpsc::Receiver<()>,
+    db_conn: &Arc<std::sync::Mutex<rusqlite::Connection>>,
+    job_id: &str,
+    limits: &JobLimits,
+) -> Option<(String, Option<i32>, bool)> {
+    eprintln!(
+        "[job_queue] job_id={} Output validation failed: {}",
+        job_id,
+        output_schema::describe_violations(violations)
+    );
+    let session_id = job_session(db_conn, job_id)?;
+
+    let _ = on_event.send(StreamEvent {
+        job_id: job_id.to_string(),
+        event_type: "repair".to_string(),
+        content: format!(
+            "Output validation failed, asking agent to fix {} problem(s)",
+            violations.len()
+        ),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    });
+
+    // Same flags as the original run, resuming the session it ended in
+    let mut repair_args = base_args.to_vec();
+    repair_args.push("--resume".to_string());
+    repair_args.push(session_id);
+    repair_args.push(output_schema::repair_prompt(violations));
+
+    match spawn_claude(claude_path, &repair_args, working_dir) {
+        Ok(child) => Some(
+            run_claude(
+                child,
+                stream_processor,
+                on_event,
+                cancel_rx,
+                db_conn,
+                job_id,
+                REPAIR_TIMEOUT_SECS,
+                limits,
+            )
+            .await,
+        ),
+        Err(e) => {
+            eprintln!(
+                "[job_queue] job_id={} Failed to spawn repair turn: {}",
+                job_id, e
+            );
+            None
+        }
+    }
+}
+
 fn db_update_job_status(
     conn: &Arc<std::sync::Mutex<rusqlite::Connection>>,
     job_id: &str,
@@ -818,3 +1512,147 @@ fn find_claude_path() -> Option<String> {
     eprintln!("[job_queue] Could not find claude CLI");
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        acquire_slot, job_session, replace_mode, ActiveJob, JobQueue, JobSlots, QueueMode,
+        SlotError,
+    };
+    use crate::db::test_support::{memory_db, new_job};
+    use crate::db::{insert_job, update_job_claude_session};
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::{mpsc, watch};
+
+    #[tokio::test]
+    async fn paused_queue_holds_jobs_without_timing_them_out() {
+        let slots = Arc::new(JobSlots::new(1));
+        let mode = watch::Sender::new(QueueMode::Paused);
+
+        let waiting = tokio::spawn(acquire_slot(slots.clone(), mode.subscribe(), 1));
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!waiting.is_finished());
+
+        mode.send_replace(QueueMode::Running);
+        let permit = tokio::time::timeout(Duration::from_millis(500), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(permit.is_ok());
+
+        // With the only slot taken, a running queue times out as before
+        let busy = acquire_slot(slots.clone(), mode.subscribe(), 1).await;
+        assert!(matches!(busy, Err(SlotError::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn setting_the_same_mode_keeps_the_queue_timeout_running() {
+        let slots = Arc::new(JobSlots::new(0));
+        let mode = watch::Sender::new(QueueMode::Running);
+        let waiting = tokio::spawn(acquire_slot(slots, mode.subscribe(), 1));
+        for _ in 0..3 {
+            tokio::time::sleep(Duration::from_millis(400)).await;
+            assert_eq!(replace_mode(&mode, QueueMode::Running), QueueMode::Running);
+        }
+        // Resetting the timer on each call would still be waiting here
+        assert!(waiting.is_finished());
+        assert!(matches!(waiting.await.unwrap(), Err(SlotError::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn shrinking_retires_released_slots_and_growing_cancels_the_rest() {
+        let slots = Arc::new(JobSlots::new(3));
+        let mode = watch::Sender::new(QueueMode::Running);
+        let mut running = Vec::new();
+        for _ in 0..2 {
+            running.push(slots.acquire().await.ok().unwrap());
+        }
+
+        // The idle slot goes at once, one of the busy ones when it is released
+        assert_eq!(slots.resize(1), 3);
+        assert_eq!(slots.semaphore.available_permits(), 0);
+        running.pop();
+        assert_eq!(slots.semaphore.available_permits(), 0);
+
+        // Nothing is owed any more, so growing adds every new slot
+        slots.resize(4);
+        assert_eq!(slots.semaphore.available_permits(), 3);
+        running.pop();
+        assert_eq!(slots.semaphore.available_permits(), 4);
+
+        // Shrink then grow while every slot is busy: nothing is lost
+        for _ in 0..4 {
+            running.push(slots.acquire().await.ok().unwrap());
+        }
+        slots.resize(2);
+        slots.resize(4);
+        running.clear();
+        assert_eq!(slots.semaphore.available_permits(), 4);
+
+        // A job waiting for a slot gets the first one released after a grow
+        for _ in 0..4 {
+            running.push(slots.acquire().await.ok().unwrap());
+        }
+        slots.resize(3);
+        let waiting = tokio::spawn(acquire_slot(slots.clone(), mode.subscribe(), 5));
+        running.pop();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+        slots.resize(4);
+        let slot = tokio::time::timeout(Duration::from_millis(500), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(slot.is_ok());
+    }
+
+    #[tokio::test]
+    async fn interrupting_stops_spawned_jobs_and_leaves_adopted_ones() {
+        let queue = JobQueue::new();
+        let mut signals = Vec::new();
+        for (id, status, adopted) in [
+            ("queued", "queued", false),
+            ("running", "running", false),
+            ("adopted", "running", true),
+        ] {
+            let (cancel_tx, cancel_rx) = mpsc::channel(1);
+            signals.push((id, cancel_rx));
+            queue.active_jobs.lock().await.insert(
+                id.to_string(),
+                ActiveJob {
+                    cancel_tx,
+                    status: status.to_string(),
+                    job_type: "company_research".to_string(),
+                    adopted,
+                },
+            );
+        }
+        assert_eq!(queue.running_jobs().await, 1);
+
+        assert_eq!(queue.interrupt_jobs().await, 2);
+        assert!(queue.interrupting.load(Ordering::SeqCst));
+        for (id, mut cancel_rx) in signals {
+            assert_eq!(cancel_rx.try_recv().is_ok(), id != "adopted", "{id}");
+        }
+        let left: Vec<String> = queue.active_jobs.lock().await.keys().cloned().collect();
+        assert_eq!(left, ["adopted"]);
+    }
+
+    #[test]
+    fn only_jobs_that_started_a_session_can_be_interrupted() {
+        let conn = memory_db();
+        insert_job(&conn, &new_job("started", "company_research", 1)).unwrap();
+        insert_job(&conn, &new_job("spawning", "company_research", 2)).unwrap();
+        update_job_claude_session(&conn, "started", "session-1", "sonnet").unwrap();
+        let db_conn = Arc::new(std::sync::Mutex::new(conn));
+
+        assert_eq!(
+            job_session(&db_conn, "started").as_deref(),
+            Some("session-1")
+        );
+        assert_eq!(job_session(&db_conn, "spawning"), None);
+        assert_eq!(job_session(&db_conn, "missing"), None);
+    }
+}
@@ -3,14 +3,19 @@
 //! This module provides functions to detect and recover from jobs that were
 //! interrupted (e.g., app crash, system restart) and left in an inconsistent state.
 
+use super::archive;
+use super::completion_handler::{CompletionHandler, CompletionPhase};
+use super::process::{self, ProcessStatus};
+use super::queue::JobQueue;
+use super::result_parser::JobMetadata;
+use crate::db::{self, OrphanProcessPolicy};
 use crate::events;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use tauri::AppHandle;
-
-/// Maximum age (in seconds) for a job to be considered "running" before it's stale.
-/// Jobs older than this are assumed to have died without proper cleanup.
-const STALE_JOB_THRESHOLD_SECS: i64 = 600; // 10 minutes
+use tauri::{AppHandle, Manager};
 
 /// Result of stale job detection
 #[derive(Debug, serde::Serialize)]
@@ -44,23 +49,31 @@ pub struct StuckEntity {
     pub status: String,
 }
 
-/// Detect stale jobs - jobs with "running" or "queued" status that are too old
+/// Detect stale jobs - jobs with "running" or "queued" status older than the
+/// stale job threshold of their job type. Such jobs are assumed to have died
+/// without proper cleanup.
 pub fn detect_stale_jobs(conn: &Connection) -> Result<Vec<StaleJob>, String> {
     let now = chrono::Utc::now().timestamp();
-    let threshold = now - STALE_JOB_THRESHOLD_SECS;
+    let limits = db::get_settings(conn)
+        .map_err(|e| e.to_string())?
+        .job_limits;
+    let overrides = db::get_job_limit_overrides(conn).map_err(|e| e.to_string())?;
+    let threshold = |job_type: &str| match overrides.iter().find(|o| o.job_type == job_type) {
+        Some(o) => limits.with_overrides(o).stale_job_threshold_secs,
+        None => limits.stale_job_threshold_secs,
+    };
 
     let mut stmt = conn
         .prepare(
             "SELECT id, job_type, entity_id, entity_label, status, started_at, created_at
          FROM jobs
          WHERE status IN ('queued', 'running')
-           AND created_at < ?1
          ORDER BY created_at ASC",
         )
         .map_err(|e| e.to_string())?;
 
     let rows = stmt
-        .query_map(params![threshold], |row| {
+        .query_map([], |row| {
             Ok(StaleJob {
                 id: row.get(0)?,
                 job_type: row.get(1)?,
@@ -73,10 +86,473 @@ pub fn detect_stale_jobs(conn: &Connection) -> Result<Vec<StaleJob>, String> {
         })
         .map_err(|e| e.to_string())?;
 
+    let jobs = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(jobs
+        .into_iter()
+        .filter(|job| job.created_at < now - threshold(&job.job_type))
+        .collect())
+}
+
+/// A job whose process finished but whose completion stopped partway
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedCompletion {
+    pub id: String,
+    pub job_type: String,
+    pub entity_id: i64,
+    pub completion_state: String,
+    pub output_path: Option<String>,
+    pub output_archive_path: Option<String>,
+}
+
+/// Detect finished jobs whose completion never reached a final phase
+pub fn detect_interrupted_completions(
+    conn: &Connection,
+) -> Result<Vec<InterruptedCompletion>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, job_type, entity_id, completion_state, output_path, output_archive_path
+         FROM jobs
+         WHERE status = 'completed'
+           AND completion_state IS NOT NULL
+           AND completion_state NOT IN ('\"completed\"', '\"failed\"')
+         ORDER BY created_at ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(InterruptedCompletion {
+                id: row.get(0)?,
+                job_type: row.get(1)?,
+                entity_id: row.get(2)?,
+                completion_state: row.get(3)?,
+                output_path: row.get(4)?,
+                output_archive_path: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
     rows.collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())
 }
 
+/// Resume one interrupted completion from its last finished phase
+fn resume_completion(
+    handler: &CompletionHandler,
+    job: &InterruptedCompletion,
+) -> Result<(), String> {
+    let phase: CompletionPhase =
+        serde_json::from_str(&job.completion_state).map_err(|e| e.to_string())?;
+    let archived = job
+        .output_archive_path
+        .as_deref()
+        .and_then(|path| archive::read_archive(Path::new(path)).ok());
+    let metadata = match &archived {
+        Some(archive) => archive.metadata(),
+        None => job
+            .output_path
+            .as_deref()
+            .and_then(|path| JobMetadata::from_job_row(&job.job_type, job.entity_id, path))
+            .ok_or_else(|| "Job has no recorded output path".to_string())?,
+    };
+    // Without a usable archive the output files are read from disk again
+    let outputs = archived.and_then(|archive| {
+        archive.outputs().unwrap_or_else(|e| {
+            eprintln!("[recovery] Archive of job {} unusable: {}", job.id, e);
+            None
+        })
+    });
+    handler
+        .resume_completion(&job.id, &metadata, &phase, outputs)
+        .map_err(|e| {
+            handler.mark_entity_failed(&metadata);
+            e.to_string()
+        })
+}
+
+/// Finish completions that were interrupted (e.g. the app quit between the
+/// database update and cleanup), continuing from the last finished phase
+pub fn resume_interrupted_completions(
+    conn: &Arc<Mutex<Connection>>,
+    app: &AppHandle,
+) -> Result<usize, String> {
+    let interrupted = {
+        let conn = conn.lock().map_err(|e| e.to_string())?;
+        detect_interrupted_completions(&conn)?
+    };
+    let handler = CompletionHandler::new(conn.clone(), app.clone());
+    let mut resumed = 0;
+
+    for job in &interrupted {
+        match resume_completion(&handler, job) {
+            Ok(()) => {
+                resumed += 1;
+                eprintln!(
+                    "[recovery] Resumed completion of job {} from {}",
+                    job.id, job.completion_state
+                );
+            }
+            Err(e) => {
+                eprintln!(
+                    "[recovery] Failed to resume completion of job {}: {}",
+                    job.id, e
+                );
+                let message = format!("Completion could not be resumed: {}", e);
+                if let Ok(conn) = conn.lock() {
+                    let _ = db::update_job_status(&conn, &job.id, "error", None, Some(&message));
+                    let state = serde_json::to_string(&CompletionPhase::Failed).ok();
+                    let _ = db::update_job_completion_state(&conn, &job.id, state.as_deref());
+                }
+                events::emit_job_status_changed(app, job.id.clone(), "error".to_string(), None);
+            }
+        }
+    }
+
+    Ok(resumed)
+}
+
+/// An output file left behind by a job that never completed
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarvestedOutput {
+    pub job_id: String,
+    pub job_type: String,
+    pub entity_id: i64,
+    pub entity_label: String,
+    pub path: String,
+    /// The job's stream never reported a result, so the output may be
+    /// truncated
+    pub partial: bool,
+    /// Why the output could not be imported
+    pub error: Option<String>,
+}
+
+/// What a scan of the output directories salvaged
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarvestReport {
+    pub scanned_at: i64,
+    pub salvaged: Vec<HarvestedOutput>,
+    pub failed: Vec<HarvestedOutput>,
+}
+
+/// Report of the most recent harvest, kept for the frontend
+#[derive(Default)]
+pub struct HarvestReportState(pub Mutex<HarvestReport>);
+
+/// Primary output files the research commands write, by output directory
+fn primary_output_files(data_dir: &Path) -> Vec<PathBuf> {
+    fn files_in(dir: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .map(|entries| entries.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default()
+    }
+    fn named(path: &Path, prefix: &str, extension: &str) -> bool {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with(prefix) && n.ends_with(extension))
+    }
+
+    let mut files = Vec::new();
+    for dir in files_in(&data_dir.join("research")) {
+        for name in ["company_profile.md", "person_profile.md"] {
+            let path = dir.join(name);
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    for (dir, prefix, extension) in [
+        ("scoring", "score_", ".json"),
+        ("conversations", "conversation_", ".md"),
+        ("lead_finder", "leads_", ".json"),
+    ] {
+        files.extend(
+            files_in(&data_dir.join(dir))
+                .into_iter()
+                .filter(|p| named(p, prefix, extension)),
+        );
+    }
+    files
+}
+
+/// Job statuses whose outputs may be harvested: the app died while the job
+/// ran, or the agent finished but the completion never started. Jobs that
+/// were stopped (cancelled, interrupted, timed out or failed) are left alone;
+/// interrupted ones wait to be resumed from their session.
+const HARVESTABLE_STATUSES: &[&str] = &["queued", "running", "completed"];
+
+/// The newest job writing to `path` whose completion never ran, if the file
+/// was written after that job started
+fn find_orphaning_job(conn: &Connection, path: &Path) -> Result<Option<HarvestedOutput>, String> {
+    let job = conn
+        .query_row(
+            "SELECT id, job_type, entity_id, entity_label, created_at, completion_state, status
+             FROM jobs WHERE output_path = ?1
+             ORDER BY created_at DESC LIMIT 1",
+            params![path.to_string_lossy()],
+            |row| {
+                let output = HarvestedOutput {
+                    job_id: row.get(0)?,
+                    job_type: row.get(1)?,
+                    entity_id: row.get(2)?,
+                    entity_label: row.get(3)?,
+                    path: path.to_string_lossy().to_string(),
+                    partial: false,
+                    error: None,
+                };
+                let created_at: i64 = row.get(4)?;
+                let completion_state: Option<String> = row.get(5)?;
+                let status: String = row.get(6)?;
+                Ok((output, created_at, completion_state, status))
+            },
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let Some((output, created_at, completion_state, status)) = job else {
+        return Ok(None);
+    };
+    let modified = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    if completion_state.is_some()
+        || !HARVESTABLE_STATUSES.contains(&status.as_str())
+        || modified < created_at
+    {
+        return Ok(None);
+    }
+    let finished = db::job_reported_result(conn, &output.job_id).map_err(|e| e.to_string())?;
+    Ok(Some(HarvestedOutput {
+        partial: !finished,
+        ..output
+    }))
+}
+
+/// Outputs of jobs whose completion never ran, except those still `active`
+fn find_orphans(
+    conn: &Connection,
+    data_dir: &Path,
+    active: &HashSet<String>,
+) -> Result<Vec<HarvestedOutput>, String> {
+    let mut orphans = Vec::new();
+    for path in primary_output_files(data_dir) {
+        if let Some(orphan) = find_orphaning_job(conn, &path)? {
+            if !active.contains(&orphan.job_id) {
+                orphans.push(orphan);
+            }
+        }
+    }
+    Ok(orphans)
+}
+
+/// Scan the output directories for files written by jobs whose completion never
+/// ran (e.g. the app quit after the agent finished) and import them through
+/// the normal completion path. Files of runs that never reported a result may
+/// be cut off mid-write, so their jobs are failed instead of imported. Jobs in
+/// `active` are still running and are left alone.
+pub fn harvest_orphaned_outputs(
+    conn: &Arc<Mutex<Connection>>,
+    app: &AppHandle,
+    data_dir: &Path,
+    active: &HashSet<String>,
+) -> Result<HarvestReport, String> {
+    let orphans = {
+        let conn = conn.lock().map_err(|e| e.to_string())?;
+        find_orphans(&conn, data_dir, active)?
+    };
+
+    let handler = CompletionHandler::new(conn.clone(), app.clone());
+    let mut report = HarvestReport {
+        scanned_at: chrono::Utc::now().timestamp(),
+        ..Default::default()
+    };
+    for mut orphan in orphans {
+        let result = if orphan.partial {
+            reject_partial_outputs(conn, app, &handler, &orphan)
+        } else {
+            salvage_outputs(conn, app, &handler, &orphan)
+        };
+        match result {
+            Ok(()) => report.salvaged.push(orphan),
+            Err(e) => {
+                orphan.error = Some(e);
+                report.failed.push(orphan);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Fail a job whose run ended without a result, leaving its outputs on disk
+fn reject_partial_outputs(
+    conn: &Arc<Mutex<Connection>>,
+    app: &AppHandle,
+    handler: &CompletionHandler,
+    orphan: &HarvestedOutput,
+) -> Result<(), String> {
+    let message = "The run ended without reporting a result; its output may be incomplete";
+    if let Some(metadata) =
+        JobMetadata::from_job_row(&orphan.job_type, orphan.entity_id, &orphan.path)
+    {
+        handler.mark_entity_failed(&metadata);
+    }
+    if let Ok(conn) = conn.lock() {
+        let _ = db::update_job_status(&conn, &orphan.job_id, "error", None, Some(message));
+        let state = serde_json::to_string(&CompletionPhase::Failed).ok();
+        let _ = db::update_job_completion_state(&conn, &orphan.job_id, state.as_deref());
+    }
+    events::emit_job_status_changed(app, orphan.job_id.clone(), "error".to_string(), None);
+    eprintln!(
+        "[recovery] Not importing outputs of job {} ({}): no result event",
+        orphan.job_id, orphan.path
+    );
+    Err(message.to_string())
+}
+
+/// Import the outputs a job left on disk and settle the job's status. Partial
+/// imports keep a note on the job that the output may be incomplete.
+fn salvage_outputs(
+    conn: &Arc<Mutex<Connection>>,
+    app: &AppHandle,
+    handler: &CompletionHandler,
+    orphan: &HarvestedOutput,
+) -> Result<(), String> {
+    let metadata = JobMetadata::from_job_row(&orphan.job_type, orphan.entity_id, &orphan.path)
+        .ok_or_else(|| format!("Unknown job type: {}", orphan.job_type))?;
+    let result = handler
+        .harvest(&orphan.job_id, &metadata)
+        .map_err(|e| e.to_string());
+
+    let (status, message) = match &result {
+        Ok(()) if orphan.partial => (
+            "completed",
+            Some(
+                "Imported without a result from the agent; the output may be incomplete"
+                    .to_string(),
+            ),
+        ),
+        Ok(()) => ("completed", None),
+        Err(e) => ("error", Some(format!("Salvaging outputs failed: {}", e))),
+    };
+    if result.is_err() {
+        handler.mark_entity_failed(&metadata);
+    }
+    if let Ok(conn) = conn.lock() {
+        let _ = db::update_job_status(&conn, &orphan.job_id, status, None, message.as_deref());
+        if result.is_err() {
+            let state = serde_json::to_string(&CompletionPhase::Failed).ok();
+            let _ = db::update_job_completion_state(&conn, &orphan.job_id, state.as_deref());
+        }
+    }
+    events::emit_job_status_changed(app, orphan.job_id.clone(), status.to_string(), None);
+
+    eprintln!(
+        "[recovery] Harvested outputs of job {} ({}): {}",
+        orphan.job_id,
+        orphan.path,
+        result
+            .as_ref()
+            .err()
+            .map(String::as_str)
+            .unwrap_or("imported")
+    );
+    result
+}
+
+/// Import the outputs of an adopted job whose agent process has exited. Its
+/// stream was not captured after adoption, so the import is marked partial
+/// unless a result was reported before.
+pub fn salvage_adopted_job(
+    conn: &Arc<Mutex<Connection>>,
+    app: &AppHandle,
+    job_id: &str,
+) -> Result<(), String> {
+    let (job, finished) = {
+        let conn = conn.lock().map_err(|e| e.to_string())?;
+        let job = db::get_job(&conn, job_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Job not found".to_string())?;
+        let finished = db::job_reported_result(&conn, job_id).map_err(|e| e.to_string())?;
+        (job, finished)
+    };
+    let orphan = HarvestedOutput {
+        job_id: job.id,
+        job_type: job.job_type,
+        entity_id: job.entity_id,
+        entity_label: job.entity_label,
+        path: job.output_path.unwrap_or_default(),
+        partial: !finished,
+        error: None,
+    };
+    let handler = CompletionHandler::new(conn.clone(), app.clone());
+    salvage_outputs(conn, app, &handler, &orphan)
+}
+
+/// Fail an adopted job that was cancelled or timed out
+pub fn settle_adopted_job(
+    conn: &Arc<Mutex<Connection>>,
+    app: &AppHandle,
+    job_id: &str,
+    status: &str,
+    message: &str,
+) {
+    let Ok(conn) = conn.lock() else {
+        return;
+    };
+    let Ok(Some(job)) = db::get_job(&conn, job_id) else {
+        return;
+    };
+    let _ = db::update_job_status(&conn, job_id, status, None, Some(message));
+    let state = serde_json::to_string(&CompletionPhase::Failed).ok();
+    let _ = db::update_job_completion_state(&conn, job_id, state.as_deref());
+    let table = match job.job_type.as_str() {
+        "company_research" => "leads",
+        "person_research" => "people",
+        _ => "",
+    };
+    if !table.is_empty() {
+        let _ = conn.execute(
+            &format!(
+                "UPDATE {} SET research_status = 'failed' WHERE id = ?1",
+                table
+            ),
+            params![job.entity_id],
+        );
+    }
+    events::emit_job_status_changed(app, job_id.to_string(), status.to_string(), None);
+    match job.job_type.as_str() {
+        "company_research" => events::emit_lead_updated(app, job.entity_id),
+        "person_research" => {
+            let lead_id = db::get_person_raw(&conn, job.entity_id)
+                .ok()
+                .flatten()
+                .and_then(|p| p.lead_id);
+            events::emit_person_updated(app, job.entity_id, lead_id);
+        }
+        _ => {}
+    }
+}
+
+/// Keep the report for the frontend and announce it
+pub fn publish_harvest_report(app: &AppHandle, report: &HarvestReport) {
+    if let Some(state) = app.try_state::<HarvestReportState>() {
+        if let Ok(mut last) = state.0.lock() {
+            *last = report.clone();
+        }
+    }
+    if !report.salvaged.is_empty() || !report.failed.is_empty() {
+        events::emit_outputs_harvested(app, report.salvaged.len(), report.failed.len());
+    }
+}
+
 /// Detect leads stuck in "in_progress" status without an active job
 pub fn detect_stuck_leads(conn: &Connection) -> Result<Vec<StuckEntity>, String> {
     let mut stmt = conn
@@ -139,49 +615,60 @@ pub fn detect_stuck_people(conn: &Connection) -> Result<Vec<StuckEntity>, String
         .map_err(|e| e.to_string())
 }
 
+/// Mark a job as error and reset its entity's research status
+fn fail_job(
+    conn: &Connection,
+    app: &AppHandle,
+    job: &StaleJob,
+    message: &str,
+) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp();
+    conn.execute(
+        "UPDATE jobs SET status = 'error', error_message = ?1, completed_at = ?2 WHERE id = ?3",
+        params![message, now, job.id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Reset entity status based on job type
+    match job.job_type.as_str() {
+        "company_research" => {
+            conn.execute(
+                "UPDATE leads SET research_status = 'pending' WHERE id = ?1 AND research_status = 'in_progress'",
+                params![job.entity_id],
+            ).map_err(|e| e.to_string())?;
+            events::emit_lead_updated(app, job.entity_id);
+        }
+        "person_research" => {
+            conn.execute(
+                "UPDATE people SET research_status = 'pending' WHERE id = ?1 AND research_status = 'in_progress'",
+                params![job.entity_id],
+            ).map_err(|e| e.to_string())?;
+            // Get lead_id for event
+            let lead_id: Option<i64> = conn
+                .query_row(
+                    "SELECT lead_id FROM people WHERE id = ?1",
+                    params![job.entity_id],
+                    |row| row.get::<_, Option<i64>>(0),
+                )
+                .ok()
+                .flatten();
+            events::emit_person_updated(app, job.entity_id, lead_id);
+        }
+        _ => {
+            // Scoring and conversation jobs don't have a research_status to reset
+        }
+    }
+    events::emit_job_status_changed(app, job.id.clone(), "error".to_string(), None);
+    Ok(())
+}
+
 /// Recover stale jobs - mark them as error and reset associated entity status
 pub fn recover_stale_jobs(conn: &Connection, app: &AppHandle) -> Result<usize, String> {
     let stale_jobs = detect_stale_jobs(conn)?;
-    let now = chrono::Utc::now().timestamp();
     let mut recovered = 0;
 
     for job in &stale_jobs {
-        // Mark job as error
-        conn.execute(
-            "UPDATE jobs SET status = 'error', error_message = 'Recovered stale job', completed_at = ?1 WHERE id = ?2",
-            params![now, job.id],
-        ).map_err(|e| e.to_string())?;
-
-        // Reset entity status based on job type
-        match job.job_type.as_str() {
-            "company_research" => {
-                conn.execute(
-                    "UPDATE leads SET research_status = 'pending' WHERE id = ?1 AND research_status = 'in_progress'",
-                    params![job.entity_id],
-                ).map_err(|e| e.to_string())?;
-                events::emit_lead_updated(app, job.entity_id);
-            }
-            "person_research" => {
-                conn.execute(
-                    "UPDATE people SET research_status = 'pending' WHERE id = ?1 AND research_status = 'in_progress'",
-                    params![job.entity_id],
-                ).map_err(|e| e.to_string())?;
-                // Get lead_id for event
-                let lead_id: Option<i64> = conn
-                    .query_row(
-                        "SELECT lead_id FROM people WHERE id = ?1",
-                        params![job.entity_id],
-                        |row| row.get::<_, Option<i64>>(0),
-                    )
-                    .ok()
-                    .flatten();
-                events::emit_person_updated(app, job.entity_id, lead_id);
-            }
-            _ => {
-                // Scoring and conversation jobs don't have a research_status to reset
-            }
-        }
-
+        fail_job(conn, app, job, "Recovered stale job")?;
         recovered += 1;
         eprintln!(
             "[recovery] Recovered stale job {} (type: {}, entity: {})",
@@ -192,6 +679,141 @@ pub fn recover_stale_jobs(conn: &Connection, app: &AppHandle) -> Result<usize, S
     Ok(recovered)
 }
 
+/// Queued and running jobs left over from a previous session, with their recorded PID
+fn detect_unfinished_jobs(conn: &Connection) -> Result<Vec<(StaleJob, Option<i64>)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, job_type, entity_id, entity_label, status, started_at, created_at, pid
+         FROM jobs
+         WHERE status IN ('queued', 'running')
+         ORDER BY created_at ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                StaleJob {
+                    id: row.get(0)?,
+                    job_type: row.get(1)?,
+                    entity_id: row.get(2)?,
+                    entity_label: row.get(3)?,
+                    status: row.get(4)?,
+                    started_at: row.get(5)?,
+                    created_at: row.get(6)?,
+                },
+                row.get(7)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// An agent process that outlived the session that started it
+#[derive(Debug, Clone)]
+pub struct LiveAgentProcess {
+    pub job_id: String,
+    pub pid: u32,
+    pub started_at: Option<i64>,
+}
+
+/// Check the recorded PID of every unfinished job. Under the terminate policy
+/// live agent processes are stopped and their jobs failed; under the adopt
+/// policy they are returned so the queue can track them. Processes are
+/// inspected and stopped without holding the database lock.
+pub fn check_job_processes(
+    conn: &Arc<Mutex<Connection>>,
+    app: &AppHandle,
+) -> Result<Vec<LiveAgentProcess>, String> {
+    let (jobs, policy) = {
+        let conn = conn.lock().map_err(|e| e.to_string())?;
+        let policy = db::get_settings(&conn)
+            .map(|s| s.orphan_process_policy)
+            .unwrap_or_default();
+        let mut jobs = Vec::new();
+        for (job, pid) in detect_unfinished_jobs(&conn)? {
+            let Some(pid) = pid.and_then(|p| u32::try_from(p).ok()) else {
+                continue;
+            };
+            let grace_secs = db::get_job_limits(&conn, &job.job_type)
+                .unwrap_or_default()
+                .graceful_shutdown_secs;
+            jobs.push((job, pid, grace_secs as u64));
+        }
+        (jobs, policy)
+    };
+
+    let mut live = Vec::new();
+    let mut terminated = Vec::new();
+    for (job, pid, grace_secs) in jobs {
+        if process::process_status(pid, job.started_at) != ProcessStatus::Claude {
+            continue;
+        }
+        match policy {
+            OrphanProcessPolicy::Adopt => {
+                eprintln!(
+                    "[recovery] Adopting agent process {} of job {}",
+                    pid, job.id
+                );
+                live.push(LiveAgentProcess {
+                    job_id: job.id,
+                    pid,
+                    started_at: job.started_at,
+                });
+            }
+            OrphanProcessPolicy::Terminate => {
+                eprintln!(
+                    "[recovery] Terminating agent process {} of job {}",
+                    pid, job.id
+                );
+                process::terminate(pid, grace_secs);
+                terminated.push(job);
+            }
+        }
+    }
+
+    if !terminated.is_empty() {
+        let conn = conn.lock().map_err(|e| e.to_string())?;
+        for job in &terminated {
+            fail_job(
+                &conn,
+                app,
+                job,
+                "Agent process from a previous session was terminated",
+            )?;
+            // Its partial output files must not be harvested
+            let state = serde_json::to_string(&CompletionPhase::Failed).ok();
+            db::update_job_completion_state(&conn, &job.id, state.as_deref())
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(live)
+}
+
+/// Fail unfinished jobs whose agent process is gone, except `live` ones
+pub fn fail_dead_jobs(
+    conn: &Connection,
+    app: &AppHandle,
+    live: &HashSet<String>,
+) -> Result<usize, String> {
+    let mut failed = 0;
+    for (job, _) in detect_unfinished_jobs(conn)? {
+        if live.contains(&job.id) {
+            continue;
+        }
+        let message = if job.status == "queued" {
+            "App closed before the job started"
+        } else {
+            "Agent process ended while the app was closed"
+        };
+        fail_job(conn, app, &job, message)?;
+        failed += 1;
+    }
+    Ok(failed)
+}
+
 /// Recover stuck entities - entities with "in_progress" status but no active job
 pub fn recover_stuck_entities(conn: &Connection, app: &AppHandle) -> Result<usize, String> {
     let stuck_leads = detect_stuck_leads(conn)?;
@@ -242,27 +864,226 @@ pub fn recover_stuck_entities(conn: &Connection, app: &AppHandle) -> Result<usiz
 pub fn recover_on_startup(conn: &Arc<Mutex<Connection>>, app: &AppHandle) {
     eprintln!("[recovery] Running startup recovery...");
 
-    let conn_guard = match conn.lock() {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("[recovery] Failed to lock database: {}", e);
-            return;
+    // Check recorded PIDs first: live agent processes are adopted or
+    // terminated, and their jobs must not be harvested or failed below
+    let live = check_job_processes(conn, app).unwrap_or_else(|e| {
+        eprintln!("[recovery] Failed to check job processes: {}", e);
+        Vec::new()
+    });
+    let live_ids: HashSet<String> = live.iter().map(|p| p.job_id.clone()).collect();
+
+    // Import outputs of jobs that died before their completion ran, before
+    // their jobs are marked failed
+    if let Ok(data_dir) = app.path().app_data_dir() {
+        match harvest_orphaned_outputs(conn, app, &data_dir, &live_ids) {
+            Ok(report) => {
+                if !report.salvaged.is_empty() {
+                    eprintln!(
+                        "[recovery] Salvaged outputs of {} jobs",
+                        report.salvaged.len()
+                    );
+                }
+                publish_harvest_report(app, &report);
+            }
+            Err(e) => eprintln!("[recovery] Failed to harvest orphaned outputs: {}", e),
         }
-    };
+    }
 
-    // Recover stale jobs
-    match recover_stale_jobs(&conn_guard, app) {
-        Ok(count) if count > 0 => eprintln!("[recovery] Recovered {} stale jobs", count),
-        Err(e) => eprintln!("[recovery] Failed to recover stale jobs: {}", e),
-        _ => {}
+    {
+        let conn_guard = match conn.lock() {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("[recovery] Failed to lock database: {}", e);
+                return;
+            }
+        };
+
+        // Jobs whose process is gone will never finish
+        match fail_dead_jobs(&conn_guard, app, &live_ids) {
+            Ok(count) if count > 0 => eprintln!("[recovery] Failed {} dead jobs", count),
+            Err(e) => eprintln!("[recovery] Failed to recover dead jobs: {}", e),
+            _ => {}
+        }
     }
 
-    // Recover stuck entities
-    match recover_stuck_entities(&conn_guard, app) {
-        Ok(count) if count > 0 => eprintln!("[recovery] Recovered {} stuck entities", count),
-        Err(e) => eprintln!("[recovery] Failed to recover stuck entities: {}", e),
+    // Finish interrupted completions before their entities look stuck
+    match resume_interrupted_completions(conn, app) {
+        Ok(count) if count > 0 => eprintln!("[recovery] Resumed {} interrupted completions", count),
+        Err(e) => eprintln!("[recovery] Failed to resume interrupted completions: {}", e),
         _ => {}
     }
 
+    {
+        let conn_guard = match conn.lock() {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("[recovery] Failed to lock database: {}", e);
+                return;
+            }
+        };
+
+        // Recover stuck entities
+        match recover_stuck_entities(&conn_guard, app) {
+            Ok(count) if count > 0 => eprintln!("[recovery] Recovered {} stuck entities", count),
+            Err(e) => eprintln!("[recovery] Failed to recover stuck entities: {}", e),
+            _ => {}
+        }
+
+        // Jobs the last shutdown interrupted wait for the user to resume them
+        match db::get_resumable_jobs(&conn_guard) {
+            Ok(jobs) if !jobs.is_empty() => {
+                eprintln!("[recovery] {} interrupted jobs can be resumed:", jobs.len());
+                for job in &jobs {
+                    eprintln!(
+                        "[recovery]   job_id={} {} ({})",
+                        job.id, job.entity_label, job.job_type
+                    );
+                }
+            }
+            Err(e) => eprintln!("[recovery] Failed to list resumable jobs: {}", e),
+            _ => {}
+        }
+    }
+
+    // Track adopted processes until they exit
+    if let Some(queue) = app.try_state::<JobQueue>() {
+        for process in live {
+            queue.adopt_process(app.clone(), conn.clone(), process);
+        }
+    }
+
     eprintln!("[recovery] Startup recovery complete");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_interrupted_completions, find_orphans, primary_output_files};
+    use crate::db::test_support::{memory_db, new_job, temp_dir};
+    use crate::db::{
+        get_resumable_jobs, insert_job, insert_job_events, update_job_claude_session,
+        update_job_completion_state, update_job_status, BatchJobEvent, NewJob,
+    };
+    use crate::jobs::{JobMetadata, JobType};
+    use std::collections::HashSet;
+
+    #[test]
+    fn interrupted_completions_are_detected_for_resume() {
+        let conn = memory_db();
+        for (id, state) in [
+            ("done", "\"completed\""),
+            ("crashed", "\"database_updated\""),
+        ] {
+            let job = NewJob {
+                output_path: Some("/data/research/company_3/company_profile.md".to_string()),
+                ..new_job(id, "company_research", 3)
+            };
+            insert_job(&conn, &job).unwrap();
+            update_job_status(&conn, id, "completed", Some(0), None).unwrap();
+            update_job_completion_state(&conn, id, Some(state)).unwrap();
+        }
+
+        let interrupted = detect_interrupted_completions(&conn).unwrap();
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].id, "crashed");
+
+        let metadata = JobMetadata::from_job_row(
+            &interrupted[0].job_type,
+            interrupted[0].entity_id,
+            interrupted[0].output_path.as_deref().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(metadata.job_type, JobType::CompanyResearch);
+        assert!(metadata
+            .secondary_output_path
+            .unwrap()
+            .ends_with("company_3/people.json"));
+    }
+
+    #[test]
+    fn orphaned_outputs_are_found_and_unfinished_runs_flagged_partial() {
+        let conn = memory_db();
+        let data_dir = temp_dir("harvest");
+        let company = data_dir.join("research/company_3/company_profile.md");
+        let score = data_dir.join("scoring/score_5.json");
+        let person = data_dir.join("research/person_4/person_profile.md");
+        let leads = data_dir.join("lead_finder/leads_1.json");
+
+        for (id, job_type, entity_id, path) in [
+            ("finished", "company_research", 3, &company),
+            ("cut-off", "scoring", 5, &score),
+            ("imported", "person_research", 4, &person),
+            ("running", "lead_finder", 0, &leads),
+        ] {
+            let job = NewJob {
+                output_path: Some(path.to_string_lossy().to_string()),
+                ..new_job(id, job_type, entity_id)
+            };
+            insert_job(&conn, &job).unwrap();
+        }
+        update_job_completion_state(&conn, "imported", Some("\"completed\"")).unwrap();
+        insert_job_events(
+            &conn,
+            &[BatchJobEvent {
+                job_id: "finished".to_string(),
+                sequence: 9,
+                kind: "result".to_string(),
+                tool_use_id: None,
+                tool_name: None,
+                content: Some("done".to_string()),
+                input: None,
+                is_error: false,
+                timestamp: 0,
+                duration_ms: None,
+            }],
+        )
+        .unwrap();
+
+        for path in [&company, &score, &person, &leads] {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "# Acme").unwrap();
+        }
+        std::fs::write(data_dir.join("scoring/notes.txt"), "").unwrap();
+
+        let mut files = primary_output_files(&data_dir);
+        files.sort();
+        let mut expected = vec![company.clone(), score.clone(), person, leads];
+        expected.sort();
+        assert_eq!(files, expected);
+
+        let active: HashSet<String> = ["running".to_string()].into();
+        let mut orphans = find_orphans(&conn, &data_dir, &active).unwrap();
+        orphans.sort_by(|a, b| a.job_id.cmp(&b.job_id));
+        let found: Vec<_> = orphans
+            .iter()
+            .map(|o| (o.job_id.as_str(), o.partial))
+            .collect();
+        assert_eq!(found, [("cut-off", true), ("finished", false)]);
+
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn outputs_of_interrupted_jobs_are_left_for_resume() {
+        let conn = memory_db();
+        let data_dir = temp_dir("harvest-interrupted");
+        let company = data_dir.join("research/company_3/company_profile.md");
+        let job = NewJob {
+            output_path: Some(company.to_string_lossy().to_string()),
+            ..new_job("interrupted", "company_research", 3)
+        };
+        insert_job(&conn, &job).unwrap();
+        update_job_claude_session(&conn, "interrupted", "session-1", "sonnet").unwrap();
+        update_job_status(&conn, "interrupted", "interrupted", None, None).unwrap();
+        std::fs::create_dir_all(company.parent().unwrap()).unwrap();
+        std::fs::write(&company, "# Acme (half written)").unwrap();
+
+        assert!(find_orphans(&conn, &data_dir, &HashSet::new())
+            .unwrap()
+            .is_empty());
+        let resumable = get_resumable_jobs(&conn).unwrap();
+        assert_eq!(resumable.len(), 1);
+        assert_eq!(resumable[0].id, "interrupted");
+
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+}
@@ -12,6 +12,30 @@ pub enum JobType {
     LeadFinder,
 }
 
+impl JobType {
+    /// The `jobs.job_type` value
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobType::CompanyResearch => "company_research",
+            JobType::PersonResearch => "person_research",
+            JobType::Scoring => "scoring",
+            JobType::Conversation => "conversation",
+            JobType::LeadFinder => "lead_finder",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "company_research" => Some(JobType::CompanyResearch),
+            "person_research" => Some(JobType::PersonResearch),
+            "scoring" => Some(JobType::Scoring),
+            "conversation" => Some(JobType::Conversation),
+            "lead_finder" => Some(JobType::LeadFinder),
+            _ => None,
+        }
+    }
+}
+
 /// Metadata about a job for tracking, including output file paths
 #[derive(Debug, Clone)]
 pub struct JobMetadata {
@@ -26,4 +50,37 @@ pub struct JobMetadata {
     pub secondary_output_path: Option<PathBuf>,
     /// For CompanyResearch/PersonResearch: enrichment.json path for structured data
     pub enrichment_output_path: Option<PathBuf>,
+    /// For CompanyResearch/PersonResearch: sources.json path with cited sources
+    pub sources_output_path: Option<PathBuf>,
+}
+
+impl JobMetadata {
+    /// Rebuild metadata from a job row's type and primary output path, using the
+    /// file names the research commands place next to the primary output
+    pub fn from_job_row(job_type: &str, entity_id: i64, output_path: &str) -> Option<Self> {
+        let job_type = JobType::parse(job_type)?;
+        let primary_output_path = PathBuf::from(output_path);
+        let dir = primary_output_path.parent()?.to_path_buf();
+        let (secondary, enrichment, sources) = match job_type {
+            JobType::CompanyResearch => (
+                Some(dir.join("people.json")),
+                Some(dir.join("enrichment.json")),
+                Some(dir.join("sources.json")),
+            ),
+            JobType::PersonResearch => (
+                None,
+                Some(dir.join("enrichment.json")),
+                Some(dir.join("sources.json")),
+            ),
+            JobType::Scoring | JobType::Conversation | JobType::LeadFinder => (None, None, None),
+        };
+        Some(Self {
+            job_type,
+            entity_id,
+            primary_output_path,
+            secondary_output_path: secondary,
+            enrichment_output_path: enrichment,
+            sources_output_path: sources,
+        })
+    }
 }
@@ -0,0 +1,134 @@
+//! Orderly shutdown of the job queue before the app exits
+//!
+//! Quitting stops dispatching, then gives running jobs until a deadline to
+//! finish. Jobs still running after that are stopped through the normal cancel
+//! path, so the agent gets SIGTERM and the grace period before SIGKILL and its
+//! remaining output is drained and logged. Those that had started an agent
+//! session are recorded as "interrupted" with the session kept instead of
+//! showing as failed; startup lists them and the `resume_job` command queues a
+//! run that continues the session with `--resume`. Queued jobs dropped by the
+//! drain, and running jobs without a session, are recorded as cancelled.
+//! Processes adopted from a previous session are left running and are adopted
+//! again on the next start.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use super::queue::{JobQueue, QueueMode};
+use crate::db::{DbState, JobLimits};
+
+/// How long quitting waits for running jobs to finish on their own
+pub const SHUTDOWN_WAIT_SECS: u64 = 10;
+
+/// How often the coordinator checks on running jobs
+const POLL_MS: u64 = 200;
+
+/// Time on top of the grace and drain periods for interrupted jobs to record
+/// their status
+const SETTLE_MARGIN_SECS: u64 = 3;
+
+/// Outcome of a shutdown, logged before exit
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShutdownReport {
+    /// Jobs that finished within the wait
+    pub finished: usize,
+    /// Jobs stopped at the deadline, interrupted or cancelled
+    pub interrupted: usize,
+    /// Job tasks still not settled when the app exits
+    pub unsettled: usize,
+    pub elapsed_ms: u64,
+}
+
+/// Whether a shutdown has started and whether it is done, so the exit it ends
+/// with is let through
+#[derive(Default)]
+pub struct ShutdownState {
+    started: AtomicBool,
+    done: AtomicBool,
+}
+
+impl ShutdownState {
+    pub fn is_done(&self) -> bool {
+        self.done.load(Ordering::SeqCst)
+    }
+}
+
+/// Stop dispatching, wait up to `wait_secs` for running jobs, interrupt the
+/// rest and flush their logs
+pub async fn shutdown(app: &AppHandle, wait_secs: u64) -> ShutdownReport {
+    let started = Instant::now();
+    let queue = app.state::<JobQueue>();
+    queue.begin_interrupting();
+    queue.set_mode(app, QueueMode::Draining).await;
+
+    let running = queue.running_jobs().await;
+    let deadline = started + Duration::from_secs(wait_secs);
+    let mut still_running = running;
+    while still_running > 0 && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(POLL_MS)).await;
+        still_running = queue.running_jobs().await;
+    }
+
+    let interrupted = queue.interrupt_jobs().await;
+
+    // Interrupted jobs settle after SIGTERM, the grace period and draining
+    // their output
+    let limits = {
+        let db_state = app.state::<DbState>();
+        let conn = db_state.conn.lock();
+        conn.ok()
+            .and_then(|conn| crate::db::get_settings(&conn).ok())
+            .map(|s| s.job_limits)
+            .unwrap_or_default()
+    };
+    let settle_deadline = Instant::now() + settle_time(&limits);
+    while queue.pending_tasks() > 0 && Instant::now() < settle_deadline {
+        tokio::time::sleep(Duration::from_millis(POLL_MS)).await;
+    }
+
+    // Anything still streaming gets its buffered log lines written now
+    let flushed = queue.flush_logs().await;
+    if flushed > 0 {
+        eprintln!("[shutdown] Flushed logs of {} unsettled jobs", flushed);
+    }
+
+    let report = ShutdownReport {
+        finished: running.saturating_sub(still_running),
+        interrupted,
+        unsettled: queue.pending_tasks(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    };
+    eprintln!(
+        "[shutdown] {} jobs finished, {} interrupted, {} unsettled after {}ms",
+        report.finished, report.interrupted, report.unsettled, report.elapsed_ms
+    );
+    report
+}
+
+fn settle_time(limits: &JobLimits) -> Duration {
+    Duration::from_secs(
+        limits.graceful_shutdown_secs as u64
+            + limits.stream_drain_timeout_secs as u64
+            + SETTLE_MARGIN_SECS,
+    )
+}
+
+/// Run the shutdown in the background and exit once it is done. Further
+/// requests while it runs are ignored.
+pub fn request_exit(app: &AppHandle, wait_secs: u64) {
+    let state = app.state::<ShutdownState>();
+    if state.started.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        shutdown(&app, wait_secs).await;
+        app.state::<ShutdownState>()
+            .done
+            .store(true, Ordering::SeqCst);
+        app.exit(0);
+    });
+}
@@ -0,0 +1,413 @@
+//! Typed model of Claude stream-json events and the job timeline built from them
+//!
+//! Each stdout line of a job is a stream-json message that may carry several
+//! content blocks (text, thinking, tool calls, tool results). The stream
+//! processor stores them as `job_events` rows next to the raw `job_logs` line,
+//! pairing every tool result with the call that produced it, and the timeline
+//! turns those rows into readable steps ("Searched …", "Fetched …").
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::db::{BatchJobEvent, JobEvent, JobLog};
+
+/// Maximum characters kept from a tool result; the full line stays in job_logs
+const TOOL_RESULT_PREVIEW_CHARS: usize = 2000;
+
+/// Maximum characters of assistant text shown in a timeline summary
+const SUMMARY_CHARS: usize = 160;
+
+/// One typed event from a stream-json line
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamJsonEvent {
+    Text {
+        text: String,
+    },
+    Thinking {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        is_error: bool,
+    },
+    Result {
+        subtype: String,
+        is_error: bool,
+        text: Option<String>,
+        duration_ms: Option<i64>,
+    },
+}
+
+impl StreamJsonEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Text { .. } => "text",
+            Self::Thinking { .. } => "thinking",
+            Self::ToolUse { .. } => "tool_use",
+            Self::ToolResult { .. } => "tool_result",
+            Self::Result { .. } => "result",
+        }
+    }
+
+    /// Row for the job_events table
+    pub fn to_batch_event(&self, job_id: &str, sequence: i64, timestamp: i64) -> BatchJobEvent {
+        let mut event = BatchJobEvent {
+            job_id: job_id.to_string(),
+            sequence,
+            kind: self.kind().to_string(),
+            tool_use_id: None,
+            tool_name: None,
+            content: None,
+            input: None,
+            is_error: false,
+            timestamp,
+            duration_ms: None,
+        };
+        match self {
+            Self::Text { text } | Self::Thinking { text } => event.content = Some(text.clone()),
+            Self::ToolUse { id, name, input } => {
+                event.tool_use_id = Some(id.clone());
+                event.tool_name = Some(name.clone());
+                event.input = Some(input.to_string());
+            }
+            Self::ToolResult {
+                tool_use_id,
+                content,
+                is_error,
+            } => {
+                event.tool_use_id = Some(tool_use_id.clone());
+                event.content = Some(truncate_chars(content, TOOL_RESULT_PREVIEW_CHARS));
+                event.is_error = *is_error;
+            }
+            Self::Result {
+                subtype,
+                is_error,
+                text,
+                duration_ms,
+            } => {
+                event.tool_name = Some(subtype.clone());
+                event.content = text.clone();
+                event.is_error = *is_error;
+                event.duration_ms = *duration_ms;
+            }
+        }
+        event
+    }
+}
+
+/// Parse every typed event carried by one stream-json line.
+/// System messages and unparseable lines yield nothing.
+pub fn parse_line(line: &str) -> Vec<StreamJsonEvent> {
+    let Ok(json) = serde_json::from_str::<Value>(line) else {
+        return Vec::new();
+    };
+    let str_field = |v: &Value, key: &str| v.get(key).and_then(|s| s.as_str()).map(String::from);
+
+    match json.get("type").and_then(|t| t.as_str()) {
+        Some("assistant") | Some("user") => json
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array())
+            .map(|blocks| blocks.iter().filter_map(parse_block).collect())
+            .unwrap_or_default(),
+        Some("result") => vec![StreamJsonEvent::Result {
+            subtype: str_field(&json, "subtype").unwrap_or_else(|| "success".to_string()),
+            is_error: json
+                .get("is_error")
+                .and_then(|e| e.as_bool())
+                .unwrap_or(false),
+            text: str_field(&json, "result"),
+            duration_ms: json.get("duration_ms").and_then(|d| d.as_i64()),
+        }],
+        _ => Vec::new(),
+    }
+}
+
+fn parse_block(block: &Value) -> Option<StreamJsonEvent> {
+    let str_field = |key: &str| block.get(key).and_then(|s| s.as_str()).map(String::from);
+    match block.get("type")?.as_str()? {
+        "text" => Some(StreamJsonEvent::Text {
+            text: str_field("text")?,
+        }),
+        "thinking" => Some(StreamJsonEvent::Thinking {
+            text: str_field("thinking")?,
+        }),
+        "tool_use" => Some(StreamJsonEvent::ToolUse {
+            id: str_field("id")?,
+            name: str_field("name")?,
+            input: block.get("input").cloned().unwrap_or(Value::Null),
+        }),
+        "tool_result" => Some(StreamJsonEvent::ToolResult {
+            tool_use_id: str_field("tool_use_id")?,
+            content: tool_result_text(block.get("content")),
+            is_error: block
+                .get("is_error")
+                .and_then(|e| e.as_bool())
+                .unwrap_or(false),
+        }),
+        _ => None,
+    }
+}
+
+/// Tool result content is either a string or a list of content blocks
+fn tool_result_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Rebuild events from raw logs, for jobs that ran before events were stored
+pub fn events_from_logs(logs: &[JobLog]) -> Vec<JobEvent> {
+    logs.iter()
+        .filter(|log| log.source == "stdout")
+        .flat_map(|log| {
+            parse_line(&log.content).into_iter().map(|event| {
+                let row = event.to_batch_event(&log.job_id, log.sequence, log.timestamp);
+                JobEvent {
+                    id: 0,
+                    job_id: row.job_id,
+                    sequence: row.sequence,
+                    kind: row.kind,
+                    tool_use_id: row.tool_use_id,
+                    tool_name: row.tool_name,
+                    content: row.content,
+                    input: row.input,
+                    is_error: row.is_error,
+                    timestamp: row.timestamp,
+                    duration_ms: row.duration_ms,
+                }
+            })
+        })
+        .collect()
+}
+
+// ============================================================================
+// Timeline
+// ============================================================================
+
+/// One readable step of what the agent did
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineStep {
+    pub sequence: i64,
+    /// "tool" | "message" | "thinking" | "result"
+    pub kind: String,
+    pub tool_name: Option<String>,
+    pub summary: String,
+    pub started_at: i64,
+    pub duration_ms: Option<i64>,
+    pub is_error: bool,
+    /// Tool call that never got a result (still running, or the job died)
+    pub pending: bool,
+    /// Tool call input
+    pub input: Option<Value>,
+    pub result_preview: Option<String>,
+}
+
+/// Build the ordered timeline from a job's events
+pub fn build_timeline(events: &[JobEvent]) -> Vec<TimelineStep> {
+    let results: HashMap<&str, &JobEvent> = events
+        .iter()
+        .filter(|e| e.kind == "tool_result")
+        .filter_map(|e| Some((e.tool_use_id.as_deref()?, e)))
+        .collect();
+
+    events
+        .iter()
+        .filter_map(|event| {
+            let mut step = TimelineStep {
+                sequence: event.sequence,
+                kind: String::new(),
+                tool_name: None,
+                summary: String::new(),
+                started_at: event.timestamp,
+                duration_ms: event.duration_ms,
+                is_error: event.is_error,
+                pending: false,
+                input: None,
+                result_preview: None,
+            };
+            match event.kind.as_str() {
+                "tool_use" => {
+                    let name = event.tool_name.clone().unwrap_or_default();
+                    let input = event
+                        .input
+                        .as_deref()
+                        .and_then(|i| serde_json::from_str(i).ok())
+                        .unwrap_or(Value::Null);
+                    let result = event.tool_use_id.as_deref().and_then(|id| results.get(id));
+                    step.kind = "tool".to_string();
+                    step.summary = describe_tool_call(&name, &input);
+                    step.tool_name = Some(name);
+                    step.input = Some(input);
+                    step.pending = result.is_none();
+                    if let Some(result) = result {
+                        step.is_error = result.is_error;
+                        step.duration_ms = event
+                            .duration_ms
+                            .or(Some(result.timestamp - event.timestamp));
+                        step.result_preview = result.content.clone();
+                    }
+                }
+                "text" => {
+                    step.kind = "message".to_string();
+                    step.summary = first_line(event.content.as_deref()?, SUMMARY_CHARS);
+                }
+                "thinking" => {
+                    step.kind = "thinking".to_string();
+                    step.summary = first_line(event.content.as_deref()?, SUMMARY_CHARS);
+                }
+                "result" => {
+                    step.kind = "result".to_string();
+                    step.summary = if event.is_error {
+                        format!("Failed ({})", event.tool_name.as_deref().unwrap_or("error"))
+                    } else {
+                        "Finished".to_string()
+                    };
+                    step.result_preview = event.content.clone();
+                }
+                _ => return None,
+            }
+            (!step.summary.is_empty()).then_some(step)
+        })
+        .collect()
+}
+
+/// Short description of a tool call, e.g. `Searched "acme gmbh ceo"`
+pub fn describe_tool_call(name: &str, input: &Value) -> String {
+    let field = |key: &str| input.get(key).and_then(|v| v.as_str()).unwrap_or("");
+    match name {
+        "WebSearch" => format!("Searched \"{}\"", field("query")),
+        "WebFetch" => format!("Fetched {}", field("url")),
+        "Read" => format!("Read {}", field("file_path")),
+        "Write" => format!("Wrote file {}", field("file_path")),
+        "Edit" | "MultiEdit" => format!("Edited {}", field("file_path")),
+        "Glob" => format!("Listed files matching {}", field("pattern")),
+        "Grep" => format!("Searched files for \"{}\"", field("pattern")),
+        "Bash" => match field("description") {
+            "" => format!("Ran `{}`", first_line(field("command"), SUMMARY_CHARS)),
+            description => format!("Ran command: {}", description),
+        },
+        "Task" => format!("Delegated: {}", field("description")),
+        "TodoWrite" => "Updated task list".to_string(),
+        _ => match name.strip_prefix("mcp__") {
+            Some(rest) => {
+                let (server, tool) = rest.split_once("__").unwrap_or(("", rest));
+                match (server, tool) {
+                    ("claude-in-chrome", "navigate") => format!("Opened {}", field("url")),
+                    ("claude-in-chrome", "get_page_text") => "Read page text".to_string(),
+                    ("claude-in-chrome", tool) => format!("Browser: {}", tool.replace('_', " ")),
+                    (_, tool) => format!("Used {}", tool.replace('_', " ")),
+                }
+            }
+            None => format!("Used {}", name),
+        },
+    }
+}
+
+fn first_line(text: &str, max_chars: usize) -> String {
+    let line = text.trim().lines().next().unwrap_or("");
+    truncate_chars(line, max_chars)
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_timeline, events_from_logs, parse_line, StreamJsonEvent};
+    use crate::db::{JobEvent, JobLog};
+
+    fn stored(lines: &[(&str, i64)]) -> Vec<JobEvent> {
+        let logs: Vec<JobLog> = lines
+            .iter()
+            .enumerate()
+            .map(|(i, (line, ts))| JobLog {
+                id: i as i64,
+                job_id: "job-1".to_string(),
+                log_type: "assistant".to_string(),
+                content: line.to_string(),
+                tool_name: None,
+                timestamp: *ts,
+                sequence: i as i64,
+                source: "stdout".to_string(),
+            })
+            .collect();
+        events_from_logs(&logs)
+    }
+
+    #[test]
+    fn parses_every_block_of_an_assistant_message() {
+        let line = r#"{"type":"assistant","message":{"content":[{"type":"thinking","thinking":"Find the CEO"},{"type":"text","text":"Searching now"},{"type":"tool_use","id":"t1","name":"WebSearch","input":{"query":"acme ceo"}}]}}"#;
+        let events = parse_line(line);
+        assert_eq!(
+            events.iter().map(|e| e.kind()).collect::<Vec<_>>(),
+            ["thinking", "text", "tool_use"]
+        );
+
+        let result = r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":[{"type":"text","text":"Jane Doe"}]}]}}"#;
+        assert_eq!(
+            parse_line(result),
+            [StreamJsonEvent::ToolResult {
+                tool_use_id: "t1".to_string(),
+                content: "Jane Doe".to_string(),
+                is_error: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn timeline_pairs_tool_calls_with_their_results() {
+        let events = stored(&[
+            (
+                r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t1","name":"WebSearch","input":{"query":"acme ceo"}},{"type":"tool_use","id":"t2","name":"WebFetch","input":{"url":"https://acme.test"}}]}}"#,
+                1_000,
+            ),
+            (
+                r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"Jane Doe"}]}}"#,
+                2_500,
+            ),
+            (
+                r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t3","name":"Write","input":{"file_path":"/tmp/out.md"}}]}}"#,
+                3_000,
+            ),
+            (
+                r#"{"type":"result","subtype":"success","is_error":false,"duration_ms":4000,"result":"done"}"#,
+                4_000,
+            ),
+        ]);
+
+        let timeline = build_timeline(&events);
+        let summaries: Vec<&str> = timeline.iter().map(|s| s.summary.as_str()).collect();
+        assert_eq!(
+            summaries,
+            [
+                "Searched \"acme ceo\"",
+                "Fetched https://acme.test",
+                "Wrote file /tmp/out.md",
+                "Finished"
+            ]
+        );
+        assert_eq!(timeline[0].duration_ms, Some(1_500));
+        assert_eq!(timeline[0].result_preview.as_deref(), Some("Jane Doe"));
+        assert!(timeline[1].pending);
+        assert_eq!(timeline[3].duration_ms, Some(4_000));
+    }
+}
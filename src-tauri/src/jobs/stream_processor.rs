@@ -7,20 +7,23 @@
 //! - Truncation tracking when output exceeds limits
 //! - Accumulation for callback processing
 //! - Event emission for real-time frontend updates
+//! - Redaction of each line before it is stored or emitted
+//! - Progress tracking, stored on the job and emitted as `job-progress`
 
-use crate::db::BatchLogEntry;
+use crate::db::{BatchJobEvent, BatchLogEntry, JobLimits, RawCopy};
 use crate::events;
+use crate::redaction::Redaction;
+use std::borrow::Cow;
 use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 use std::sync::Arc;
 use tauri::ipc::Channel;
 use tauri::AppHandle;
 use tokio::sync::Mutex;
 
+use super::progress::ProgressTracker;
+use super::stream_events::{self, StreamJsonEvent};
 use super::StreamEvent;
 
-/// Maximum accumulated output size (10MB)
-const MAX_ACCUMULATED_OUTPUT_SIZE: usize = 10 * 1024 * 1024;
-
 /// Number of logs to buffer before flushing to database
 /// Lower value = more frequent DB writes but faster hydration on reload
 const LOG_BUFFER_FLUSH_SIZE: usize = 3;
@@ -42,6 +45,11 @@ pub struct BufferedLogEntry {
     pub content: String,
     pub tool_name: Option<String>,
     pub sequence: i64,
+    /// Typed events parsed from the line, with the time it was read
+    pub events: Vec<StreamJsonEvent>,
+    pub timestamp: i64,
+    /// Encrypted original when redaction changed the line
+    pub raw_copy: Option<RawCopy>,
 }
 
 impl BufferedLogEntry {
@@ -55,6 +63,13 @@ impl BufferedLogEntry {
             source: self.source.clone(),
         }
     }
+
+    pub fn to_event_entries(&self) -> Vec<BatchJobEvent> {
+        self.events
+            .iter()
+            .map(|e| e.to_batch_event(&self.job_id, self.sequence, self.timestamp))
+            .collect()
+    }
 }
 
 /// Context passed to completion handler after job finishes
@@ -91,9 +106,16 @@ pub struct StreamProcessor {
     stdout_stats: Arc<Mutex<StreamStats>>,
     stderr_stats: Arc<Mutex<StreamStats>>,
 
-    // Truncation flags
+    // Truncation flags and the size output is truncated at
     stdout_truncated: Arc<AtomicBool>,
     stderr_truncated: Arc<AtomicBool>,
+    max_output_bytes: usize,
+
+    // Redaction applied to lines before they are stored or emitted
+    redaction: Option<Arc<Redaction>>,
+
+    // Progress signals derived from stdout, when tracked
+    progress: Option<Arc<Mutex<ProgressTracker>>>,
 }
 
 impl StreamProcessor {
@@ -102,6 +124,16 @@ impl StreamProcessor {
         db_conn: Arc<std::sync::Mutex<rusqlite::Connection>>,
         app_handle: AppHandle,
     ) -> Self {
+        let redaction = match db_conn.lock() {
+            Ok(conn) => Redaction::load(&conn).unwrap_or_else(|e| {
+                eprintln!(
+                    "[stream_processor] job_id={} Failed to load redaction rules: {}",
+                    job_id, e
+                );
+                None
+            }),
+            Err(_) => None,
+        };
         Self {
             job_id,
             db_conn,
@@ -114,9 +146,24 @@ impl StreamProcessor {
             stderr_stats: Arc::new(Mutex::new(StreamStats::default())),
             stdout_truncated: Arc::new(AtomicBool::new(false)),
             stderr_truncated: Arc::new(AtomicBool::new(false)),
+            max_output_bytes: JobLimits::default().max_accumulated_output_mb as usize * 1024 * 1024,
+            redaction: redaction.map(Arc::new),
+            progress: None,
         }
     }
 
+    /// Truncate the accumulated stdout and stderr at `bytes` each
+    pub fn with_output_limit(mut self, bytes: usize) -> Self {
+        self.max_output_bytes = bytes;
+        self
+    }
+
+    /// Track progress from stdout and report it as the job runs
+    pub fn with_progress(mut self, tracker: ProgressTracker) -> Self {
+        self.progress = Some(Arc::new(Mutex::new(tracker)));
+        self
+    }
+
     /// Flush the log buffer to database
     async fn flush_buffer(&self) {
         let logs_to_insert: Vec<BufferedLogEntry> = {
@@ -133,6 +180,10 @@ impl StreamProcessor {
 
         let batch_entries: Vec<BatchLogEntry> =
             logs_to_insert.iter().map(|e| e.to_batch_entry()).collect();
+        let event_entries: Vec<BatchJobEvent> = logs_to_insert
+            .iter()
+            .flat_map(|e| e.to_event_entries())
+            .collect();
 
         let last_seq = logs_to_insert.last().map(|l| l.sequence).unwrap_or(0);
         let count = logs_to_insert.len() as i64;
@@ -145,6 +196,20 @@ impl StreamProcessor {
                     self.job_id, e
                 );
             }
+            if let Err(e) = crate::db::insert_job_events(&conn, &event_entries) {
+                eprintln!(
+                    "[stream_processor] job_id={} Failed to insert events batch: {}",
+                    self.job_id, e
+                );
+            }
+            for copy in logs_to_insert.iter().filter_map(|e| e.raw_copy.as_ref()) {
+                if let Err(e) = crate::db::insert_raw_copy(&conn, &self.job_id, copy) {
+                    eprintln!(
+                        "[stream_processor] job_id={} Failed to insert raw copy: {}",
+                        self.job_id, e
+                    );
+                }
+            }
         }
 
         // Emit event for frontend
@@ -160,7 +225,15 @@ impl StreamProcessor {
         let stdout_stats = self.stdout_stats.lock().await.clone();
         let stderr_stats = self.stderr_stats.lock().await.clone();
 
+        let progress = match &self.progress {
+            Some(tracker) => Some(tracker.lock().await.snapshot()),
+            None => None,
+        };
+
         if let Ok(conn) = self.db_conn.lock() {
+            if let Some(progress) = &progress {
+                let _ = crate::db::update_job_progress(&conn, &self.job_id, progress);
+            }
             let _ = crate::db::update_job_stream_stats(
                 &conn,
                 &self.job_id,
@@ -197,6 +270,9 @@ impl StreamProcessor {
             stderr_stats: self.stderr_stats.clone(),
             stdout_truncated: self.stdout_truncated.clone(),
             stderr_truncated: self.stderr_truncated.clone(),
+            max_output_bytes: self.max_output_bytes,
+            redaction: self.redaction.clone(),
+            progress: self.progress.clone(),
         }
     }
 }
@@ -214,6 +290,9 @@ pub struct StreamProcessorHandle {
     stderr_stats: Arc<Mutex<StreamStats>>,
     stdout_truncated: Arc<AtomicBool>,
     stderr_truncated: Arc<AtomicBool>,
+    max_output_bytes: usize,
+    redaction: Option<Arc<Redaction>>,
+    progress: Option<Arc<Mutex<ProgressTracker>>>,
 }
 
 impl StreamProcessorHandle {
@@ -252,7 +331,7 @@ impl StreamProcessorHandle {
         // Accumulate output with bounded buffer
         {
             let mut acc = accumulated.lock().await;
-            if acc.len() < MAX_ACCUMULATED_OUTPUT_SIZE {
+            if acc.len() < self.max_output_bytes {
                 acc.push_str(&line);
                 acc.push('\n');
             } else if !truncated_flag.load(Ordering::Relaxed) {
@@ -263,16 +342,22 @@ impl StreamProcessorHandle {
                     "[stream_processor] job_id={} {} buffer exceeded {}MB limit, truncating",
                     self.job_id,
                     source,
-                    MAX_ACCUMULATED_OUTPUT_SIZE / 1024 / 1024
+                    self.max_output_bytes / 1024 / 1024
                 );
             }
         }
 
-        // Parse log type and tool name
-        let (log_type, tool_name) = if source == "stdout" {
-            parse_stream_json_type(&line)
+        // Redact before the line is stored or emitted; the accumulated output
+        // above stays raw since it only feeds the completion callback
+        let seq = self.sequence.fetch_add(1, Ordering::SeqCst);
+        let (line, raw_copy) = redact_line(self.redaction.as_deref(), seq, line);
+
+        // Parse log type, tool name and typed events
+        let (log_type, tool_name, events) = if source == "stdout" {
+            let (log_type, tool_name) = parse_stream_json_type(&line);
+            (log_type, tool_name, stream_events::parse_line(&line))
         } else {
-            ("stderr".to_string(), None)
+            ("stderr".to_string(), None, Vec::new())
         };
 
         // Check for Claude session info
@@ -300,7 +385,10 @@ impl StreamProcessorHandle {
             }
         }
 
-        let seq = self.sequence.fetch_add(1, Ordering::SeqCst);
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        if source == "stdout" {
+            self.update_progress(&line, &events, timestamp).await;
+        }
 
         let entry = BufferedLogEntry {
             job_id: self.job_id.clone(),
@@ -309,6 +397,9 @@ impl StreamProcessorHandle {
             content: line.clone(),
             tool_name: tool_name.clone(),
             sequence: seq,
+            events,
+            timestamp,
+            raw_copy,
         };
 
         let should_flush = {
@@ -334,6 +425,24 @@ impl StreamProcessorHandle {
         }
     }
 
+    async fn update_progress(&self, line: &str, events: &[StreamJsonEvent], timestamp: i64) {
+        let Some(tracker) = &self.progress else {
+            return;
+        };
+        let Some(progress) = tracker.lock().await.observe(line, events, timestamp) else {
+            return;
+        };
+        if let Ok(conn) = self.db_conn.lock() {
+            if let Err(e) = crate::db::update_job_progress(&conn, &self.job_id, &progress) {
+                eprintln!(
+                    "[stream_processor] job_id={} Failed to store progress: {}",
+                    self.job_id, e
+                );
+            }
+        }
+        events::emit_job_progress(&self.app_handle, self.job_id.clone(), progress);
+    }
+
     pub async fn flush_buffer(&self) {
         let logs_to_insert: Vec<BufferedLogEntry> = {
             let mut buffer = self.log_buffer.lock().await;
@@ -349,6 +458,10 @@ impl StreamProcessorHandle {
 
         let batch_entries: Vec<BatchLogEntry> =
             logs_to_insert.iter().map(|e| e.to_batch_entry()).collect();
+        let event_entries: Vec<BatchJobEvent> = logs_to_insert
+            .iter()
+            .flat_map(|e| e.to_event_entries())
+            .collect();
 
         let last_seq = logs_to_insert.last().map(|l| l.sequence).unwrap_or(0);
         let count = logs_to_insert.len() as i64;
@@ -360,6 +473,20 @@ impl StreamProcessorHandle {
                     self.job_id, e
                 );
             }
+            if let Err(e) = crate::db::insert_job_events(&conn, &event_entries) {
+                eprintln!(
+                    "[stream_processor] job_id={} Failed to insert events batch: {}",
+                    self.job_id, e
+                );
+            }
+            for copy in logs_to_insert.iter().filter_map(|e| e.raw_copy.as_ref()) {
+                if let Err(e) = crate::db::insert_raw_copy(&conn, &self.job_id, copy) {
+                    eprintln!(
+                        "[stream_processor] job_id={} Failed to insert raw copy: {}",
+                        self.job_id, e
+                    );
+                }
+            }
         }
 
         events::emit_job_logs_appended(&self.app_handle, self.job_id.clone(), count, last_seq);
@@ -378,16 +505,13 @@ fn parse_stream_json_type(line: &str) -> (String, Option<String>) {
             "system" => "system",
             "assistant" => "assistant",
             "user" => "tool_result",
-            "result" => {
+            "result"
                 if json
                     .get("is_error")
                     .and_then(|e| e.as_bool())
-                    .unwrap_or(false)
-                {
-                    "error"
-                } else {
-                    "info"
-                }
+                    .unwrap_or(false) =>
+            {
+                "error"
             }
             "error" => "error",
             "content_block_start" | "content_block_delta" => "assistant",
@@ -422,9 +546,72 @@ fn parse_stream_json_type(line: &str) -> (String, Option<String>) {
     }
 }
 
+/// The line as stored and emitted, with the sealed original when redaction
+/// changed it
+fn redact_line(
+    redaction: Option<&Redaction>,
+    sequence: i64,
+    line: String,
+) -> (String, Option<RawCopy>) {
+    let Some(redaction) = redaction else {
+        return (line, None);
+    };
+    match redaction.redact(&line) {
+        Cow::Owned(redacted) => {
+            let raw_copy = redaction.seal("log", sequence, &line);
+            (redacted, raw_copy)
+        }
+        Cow::Borrowed(_) => (line, None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_stream_json_type;
+    use super::{parse_stream_json_type, redact_line};
+    use crate::redaction::{RawVault, Redaction, Redactor, BUILTIN_RULES};
+    use std::sync::Arc;
+
+    fn builtin_redaction(keep_raw: bool) -> Redaction {
+        let redactor = Redactor::new(BUILTIN_RULES.iter().map(|(n, p, _)| (*n, *p)));
+        Redaction::new(Arc::new(redactor), keep_raw.then(RawVault::ephemeral))
+    }
+
+    #[test]
+    fn redacted_lines_stay_parseable_and_keep_a_sealed_original() {
+        let line = r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"tool-1","name":"Bash","input":{"command":"curl -d '{\"password\":\"hunter2hunter2\"}' https://crm.acme.test"}}]}}"#;
+        let vault = RawVault::ephemeral();
+        let redactor = Redactor::new(BUILTIN_RULES.iter().map(|(n, p, _)| (*n, *p)));
+        let redaction = Redaction::new(Arc::new(redactor), Some(vault.clone()));
+
+        let (stored, raw_copy) = redact_line(Some(&redaction), 7, line.to_string());
+        assert!(stored.contains(r#"\"password\":\"[REDACTED:secret]\""#));
+        assert_eq!(
+            parse_stream_json_type(&stored),
+            ("assistant".to_string(), Some("Bash".to_string()))
+        );
+        let raw_copy = raw_copy.unwrap();
+        assert_eq!((raw_copy.kind.as_str(), raw_copy.sequence), ("log", 7));
+        assert_eq!(vault.unseal(&raw_copy).as_deref(), Some(line));
+    }
+
+    #[test]
+    fn lines_without_matches_or_redaction_pass_through() {
+        let line = r#"{"type":"system","subtype":"init","session_id":"abc"}"#;
+        for redaction in [None, Some(builtin_redaction(true))] {
+            let (stored, raw_copy) = redact_line(redaction.as_ref(), 0, line.to_string());
+            assert_eq!(stored, line);
+            assert!(raw_copy.is_none());
+        }
+
+        // With raw copies discarded a redacted line keeps no original
+        let (stored, raw_copy) = redact_line(
+            Some(&builtin_redaction(false)),
+            1,
+            "contact jane@acme.test".to_string(),
+        );
+        assert_eq!(stored, "contact [REDACTED:email]");
+        assert!(raw_copy.is_none());
+    }
 
     #[test]
     fn classifies_claude_tool_commands() {
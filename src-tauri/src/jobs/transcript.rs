@@ -0,0 +1,484 @@
+//! Self-contained transcript of a job run
+//!
+//! Combines the job row, its timeline (tool calls with inputs and result
+//! previews), stderr and the output files it produced into one Markdown or
+//! HTML document that can be shared or kept for audits.
+
+use rusqlite::Connection;
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::archive::{self, ArchivedFile};
+use super::log_retention;
+use super::result_parser::JobMetadata;
+use super::stream_events::{self, TimelineStep};
+use crate::db::{self, Job};
+use crate::redaction::redact_secrets;
+
+/// Maximum characters of a tool result shown in the transcript
+const RESULT_SUMMARY_CHARS: usize = 600;
+
+/// Maximum stderr lines included
+const MAX_STDERR_LINES: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptFormat {
+    Markdown,
+    Html,
+}
+
+/// One step of the transcript, with times relative to the job start
+struct TranscriptStep {
+    offset_ms: i64,
+    summary: String,
+    input: Option<String>,
+    result: Option<String>,
+    duration_ms: Option<i64>,
+    is_error: bool,
+    pending: bool,
+}
+
+pub struct Transcript {
+    title: String,
+    details: Vec<(&'static str, String)>,
+    prompt: String,
+    steps: Vec<TranscriptStep>,
+    stderr: Vec<String>,
+    outputs: Vec<ArchivedFile>,
+}
+
+/// Load everything known about a job into a transcript. With `redact`,
+/// credentials are masked in every piece of text.
+pub fn load_transcript(conn: &Connection, job_id: &str, redact: bool) -> io::Result<Transcript> {
+    let job = db::get_job(conn, job_id)
+        .map_err(io::Error::other)?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Job not found"))?;
+
+    let logs = log_retention::job_logs(conn, job_id, None, None)?;
+    let mut events = db::get_job_events(conn, job_id).map_err(io::Error::other)?;
+    if events.is_empty() {
+        events = stream_events::events_from_logs(&logs);
+    }
+    let stderr = logs
+        .iter()
+        .filter(|l| l.source != "stdout")
+        .take(MAX_STDERR_LINES)
+        .map(|l| l.content.clone())
+        .collect();
+
+    Ok(build_transcript(
+        &job,
+        &stream_events::build_timeline(&events),
+        stderr,
+        job_outputs(&job),
+        redact,
+    ))
+}
+
+/// Output files from the job's archive, or from disk if they were never archived
+fn job_outputs(job: &Job) -> Vec<ArchivedFile> {
+    if let Some(archive) = job
+        .output_archive_path
+        .as_deref()
+        .and_then(|p| archive::read_archive(Path::new(p)).ok())
+    {
+        return archive.files;
+    }
+    let Some(metadata) = job
+        .output_path
+        .as_deref()
+        .and_then(|p| JobMetadata::from_job_row(&job.job_type, job.entity_id, p))
+    else {
+        return Vec::new();
+    };
+    [
+        ("primary", Some(metadata.primary_output_path)),
+        ("secondary", metadata.secondary_output_path),
+        ("enrichment", metadata.enrichment_output_path),
+        ("sources", metadata.sources_output_path),
+    ]
+    .into_iter()
+    .filter_map(|(role, path)| {
+        let path = path?;
+        let content = fs::read_to_string(&path).ok()?;
+        Some(ArchivedFile {
+            role: role.to_string(),
+            path,
+            content,
+            redacted: false,
+            sealed: None,
+        })
+    })
+    .collect()
+}
+
+fn build_transcript(
+    job: &Job,
+    steps: &[TimelineStep],
+    stderr: Vec<String>,
+    outputs: Vec<ArchivedFile>,
+    redact: bool,
+) -> Transcript {
+    let clean = |text: &str| {
+        if redact {
+            redact_secrets(text).into_owned()
+        } else {
+            text.to_string()
+        }
+    };
+
+    let mut details = vec![("Job", job.id.clone()), ("Status", job.status.clone())];
+    if let Some(model) = job.claude_model.as_ref().or(job.model.as_ref()) {
+        details.push(("Model", model.clone()));
+    }
+    if let Some(session) = &job.claude_session_id {
+        details.push(("Session", session.clone()));
+    }
+    details.push(("Created", format_time(job.created_at)));
+    if let Some(started) = job.started_at {
+        details.push(("Started", format_time(started)));
+    }
+    if let Some(completed) = job.completed_at {
+        details.push(("Completed", format_time(completed)));
+    }
+    if let (Some(started), Some(completed)) = (job.started_at, job.completed_at) {
+        details.push(("Duration", format_duration((completed - started) * 1000)));
+    }
+    if let Some(code) = job.exit_code {
+        details.push(("Exit code", code.to_string()));
+    }
+    if let Some(state) = &job.completion_state {
+        details.push(("Completion", state.clone()));
+    }
+    if let Some(error) = &job.error_message {
+        details.push(("Error", clean(error)));
+    }
+
+    let start_ms = job
+        .started_at
+        .map(|s| s * 1000)
+        .or(steps.first().map(|s| s.started_at))
+        .unwrap_or(0);
+
+    Transcript {
+        title: format!("{} — {}", job.entity_label, job.job_type),
+        details,
+        prompt: clean(&job.prompt),
+        steps: steps
+            .iter()
+            .filter(|s| s.kind != "thinking")
+            .map(|s| TranscriptStep {
+                offset_ms: (s.started_at - start_ms).max(0),
+                summary: clean(&s.summary),
+                input: s
+                    .input
+                    .as_ref()
+                    .and_then(|i| serde_json::to_string_pretty(i).ok())
+                    .map(|i| clean(&i)),
+                result: s
+                    .result_preview
+                    .as_deref()
+                    .map(|r| clean(&summarize(r, RESULT_SUMMARY_CHARS))),
+                duration_ms: s.duration_ms,
+                is_error: s.is_error,
+                pending: s.pending,
+            })
+            .collect(),
+        stderr: stderr.iter().map(|l| clean(l)).collect(),
+        outputs: outputs
+            .into_iter()
+            .map(|f| ArchivedFile {
+                content: clean(&f.content),
+                ..f
+            })
+            .collect(),
+    }
+}
+
+pub fn render(transcript: &Transcript, format: TranscriptFormat) -> String {
+    match format {
+        TranscriptFormat::Markdown => to_markdown(transcript),
+        TranscriptFormat::Html => to_html(transcript),
+    }
+}
+
+fn format_time(secs: i64) -> String {
+    chrono::DateTime::from_timestamp(secs, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| secs.to_string())
+}
+
+fn format_duration(ms: i64) -> String {
+    let secs = ms / 1000;
+    match secs {
+        0 => format!("{}ms", ms.max(0)),
+        1..=59 => format!("{:.1}s", ms as f64 / 1000.0),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+fn format_offset(ms: i64) -> String {
+    let secs = ms / 1000;
+    format!("+{}:{:02}", secs / 60, secs % 60)
+}
+
+fn summarize(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+fn step_note(step: &TranscriptStep) -> String {
+    let mut notes = Vec::new();
+    if let Some(ms) = step.duration_ms {
+        notes.push(format_duration(ms));
+    }
+    if step.is_error {
+        notes.push("error".to_string());
+    }
+    if step.pending {
+        notes.push("no result".to_string());
+    }
+    notes.join(", ")
+}
+
+// ============================================================================
+// Markdown
+// ============================================================================
+
+/// Code fence longer than any backtick run in the content
+fn fence(content: &str) -> String {
+    let longest = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn code_block(out: &mut String, lang: &str, content: &str) {
+    let fence = fence(content);
+    out.push_str(&format!(
+        "{}{}\n{}\n{}\n",
+        fence,
+        lang,
+        content.trim_end(),
+        fence
+    ));
+}
+
+fn to_markdown(t: &Transcript) -> String {
+    let mut out = format!("# Job transcript — {}\n\n| | |\n|---|---|\n", t.title);
+    for (label, value) in &t.details {
+        out.push_str(&format!("| {} | {} |\n", label, value.replace('|', "\\|")));
+    }
+
+    out.push_str("\n## Prompt\n\n");
+    code_block(&mut out, "text", &t.prompt);
+
+    out.push_str("\n## Steps\n\n");
+    if t.steps.is_empty() {
+        out.push_str("No steps recorded.\n");
+    }
+    for (i, step) in t.steps.iter().enumerate() {
+        out.push_str(&format!(
+            "{}. `{}` {}",
+            i + 1,
+            format_offset(step.offset_ms),
+            step.summary
+        ));
+        let note = step_note(step);
+        if !note.is_empty() {
+            out.push_str(&format!(" _({})_", note));
+        }
+        out.push('\n');
+        if let Some(input) = &step.input {
+            // Indented so the block belongs to the list item
+            let mut block = String::new();
+            code_block(&mut block, "json", input);
+            out.push('\n');
+            for line in block.lines() {
+                out.push_str(&format!("   {}\n", line));
+            }
+        }
+        if let Some(result) = &step.result {
+            out.push('\n');
+            for line in result.lines() {
+                out.push_str(&format!("   > {}\n", line));
+            }
+        }
+        if step.input.is_some() || step.result.is_some() {
+            out.push('\n');
+        }
+    }
+
+    if !t.stderr.is_empty() {
+        out.push_str("\n## Stderr\n\n");
+        code_block(&mut out, "text", &t.stderr.join("\n"));
+    }
+
+    out.push_str("\n## Outputs\n\n");
+    if t.outputs.is_empty() {
+        out.push_str("No output files found.\n");
+    }
+    for file in &t.outputs {
+        out.push_str(&format!(
+            "### {} — `{}`\n\n",
+            file.role,
+            file.path.display()
+        ));
+        let lang = file
+            .path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("text");
+        code_block(&mut out, lang, &file.content);
+        out.push('\n');
+    }
+
+    out
+}
+
+// ============================================================================
+// HTML
+// ============================================================================
+
+const HTML_STYLE: &str = "body{font-family:-apple-system,system-ui,sans-serif;max-width:960px;margin:2rem auto;padding:0 1rem;color:#1f2328}\
+table{border-collapse:collapse}td{border:1px solid #d0d7de;padding:4px 8px;vertical-align:top}\
+pre{background:#f6f8fa;padding:8px;overflow-x:auto;white-space:pre-wrap}\
+li{margin-bottom:8px}.time{color:#656d76;font-family:monospace}.note{color:#656d76}.error{color:#cf222e}";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn to_html(t: &Transcript) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Job transcript — {}</title><style>{}</style></head><body>\n",
+        escape(&t.title),
+        HTML_STYLE
+    );
+    out.push_str(&format!(
+        "<h1>Job transcript — {}</h1>\n<table>\n",
+        escape(&t.title)
+    ));
+    for (label, value) in &t.details {
+        out.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td></tr>\n",
+            label,
+            escape(value)
+        ));
+    }
+    out.push_str("</table>\n<h2>Prompt</h2>\n");
+    out.push_str(&format!("<pre>{}</pre>\n", escape(&t.prompt)));
+
+    out.push_str("<h2>Steps</h2>\n");
+    if t.steps.is_empty() {
+        out.push_str("<p>No steps recorded.</p>\n");
+    } else {
+        out.push_str("<ol>\n");
+        for step in &t.steps {
+            let class = if step.is_error {
+                " class=\"error\""
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "<li{}><span class=\"time\">{}</span> {}",
+                class,
+                format_offset(step.offset_ms),
+                escape(&step.summary)
+            ));
+            let note = step_note(step);
+            if !note.is_empty() {
+                out.push_str(&format!(" <span class=\"note\">({})</span>", escape(&note)));
+            }
+            if let Some(input) = &step.input {
+                out.push_str(&format!(
+                    "<details><summary>Input</summary><pre>{}</pre></details>",
+                    escape(input)
+                ));
+            }
+            if let Some(result) = &step.result {
+                out.push_str(&format!(
+                    "<details><summary>Result</summary><pre>{}</pre></details>",
+                    escape(result)
+                ));
+            }
+            out.push_str("</li>\n");
+        }
+        out.push_str("</ol>\n");
+    }
+
+    if !t.stderr.is_empty() {
+        out.push_str(&format!(
+            "<h2>Stderr</h2>\n<pre>{}</pre>\n",
+            escape(&t.stderr.join("\n"))
+        ));
+    }
+
+    out.push_str("<h2>Outputs</h2>\n");
+    if t.outputs.is_empty() {
+        out.push_str("<p>No output files found.</p>\n");
+    }
+    for file in &t.outputs {
+        out.push_str(&format!(
+            "<h3>{} — <code>{}</code></h3>\n<pre>{}</pre>\n",
+            escape(&file.role),
+            escape(&file.path.display().to_string()),
+            escape(&file.content)
+        ));
+    }
+
+    out.push_str("</body></html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_transcript, render, TranscriptFormat};
+    use crate::db::test_support::{memory_db, new_job};
+    use crate::db::{insert_job, insert_job_events, update_job_status, NewJob};
+    use crate::jobs::stream_events::parse_line;
+
+    #[test]
+    fn job_transcript_pairs_tool_calls_and_redacts_secrets() {
+        let conn = memory_db();
+        let job = NewJob {
+            prompt: "Research Acme. Use key sk-ant-REDACTED".to_string(),
+            model: Some("claude-sonnet-5".to_string()),
+            ..new_job("job-1", "company", 1)
+        };
+        insert_job(&conn, &job).unwrap();
+        update_job_status(&conn, "job-1", "completed", Some(0), None).unwrap();
+        for (sequence, line) in [
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t1","name":"WebFetch","input":{"url":"https://acme.test/<team>"}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"Team page"}]}}"#,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let events: Vec<_> = parse_line(line)
+                .iter()
+                .map(|e| e.to_batch_event("job-1", sequence as i64, 1_000 * sequence as i64))
+                .collect();
+            insert_job_events(&conn, &events).unwrap();
+        }
+
+        let transcript = load_transcript(&conn, "job-1", true).unwrap();
+        let markdown = render(&transcript, TranscriptFormat::Markdown);
+        assert!(markdown.contains("Fetched https://acme.test/<team>"));
+        assert!(markdown.contains("   > Team page"));
+        assert!(markdown.contains("| Exit code | 0 |"));
+        assert!(markdown.contains("[REDACTED:api_key]"));
+        assert!(!markdown.contains("sk-ant"));
+
+        let html = render(&transcript, TranscriptFormat::Html);
+        assert!(html.contains("Fetched https://acme.test/&lt;team&gt;"));
+    }
+}
@@ -3,13 +3,15 @@ mod db;
 mod events;
 mod jobs;
 mod model_config;
+mod notifications;
 mod prompts;
+mod redaction;
+mod tray;
 
 use db::{get_db_path, DbState};
 use jobs::JobQueue;
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     Manager,
 };
@@ -18,6 +20,7 @@ use tauri::{
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
             // Initialize database
             let db_path = get_db_path();
@@ -28,54 +31,53 @@ pub fn run() {
 
             app.manage(db_state);
 
-            // Initialize job queue
+            // Initialize job queue, sized from settings
             let job_queue = JobQueue::new();
+            if let Ok(conn) = conn_for_recovery.lock() {
+                if let Ok(settings) = db::get_settings(&conn) {
+                    job_queue.resize(settings.job_limits.max_concurrent_jobs as usize);
+                }
+            }
             app.manage(job_queue);
+            app.manage(jobs::recovery::HarvestReportState::default());
+            app.manage(jobs::shutdown::ShutdownState::default());
+            app.manage(notifications::NotificationState::default());
+            app.manage(jobs::log_retention::LogMaintenanceState::default());
 
             // Run startup recovery for stale jobs and stuck entities
             jobs::recovery::recover_on_startup(&conn_for_recovery, app.handle());
 
+            // Compact and cap job logs and drop expired output archives, now
+            // and every few hours
+            jobs::log_retention::spawn_scheduler(conn_for_recovery.clone(), app.handle().clone());
+
             // Setup system tray
-            let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let show_item = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+            let menu = tray::build_menu(app.handle())?;
 
             let tray_icon = Image::from_bytes(include_bytes!("../icons/menubar.png"))?;
-            TrayIconBuilder::new()
+            TrayIconBuilder::with_id(tray::TRAY_ID)
                 .icon(tray_icon)
                 .menu(&menu)
                 .show_menu_on_left_click(false)
-                .on_tray_icon_event(|tray, event| {
-                    if let TrayIconEvent::Click {
+                .on_tray_icon_event(|tray, event| match event {
+                    TrayIconEvent::Click {
                         button: MouseButton::Left,
                         button_state: MouseButtonState::Up,
                         ..
-                    } = event
-                    {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.unminimize();
-                            let _ = window.set_focus();
-                        }
-                    }
-                })
-                .on_menu_event(|app, event| match event.id.as_ref() {
-                    "quit" => app.exit(0),
-                    "show" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.unminimize();
-                            let _ = window.set_focus();
-                        }
-                    }
+                    } => tray::show_main_window(tray.app_handle()),
+                    // Refresh saved view counts before the menu is opened
+                    TrayIconEvent::Enter { .. } => tray::refresh_menu(tray.app_handle()),
                     _ => {}
                 })
+                .on_menu_event(tray::handle_menu_event)
                 .build(app)?;
 
             Ok(())
         })
         .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Focused(true) = event {
+                notifications::window_activated(window.app_handle());
+            }
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 // Hide the window and remove focus instead of closing
                 let _ = window.hide();
@@ -92,6 +94,7 @@ pub fn run() {
             commands::get_lead,
             commands::get_all_leads,
             commands::get_adjacent_leads,
+            commands::query_leads,
             commands::insert_lead,
             commands::update_lead_user_status,
             commands::update_lead_notes,
@@ -102,9 +105,60 @@ pub fn run() {
             commands::get_people_for_lead,
             commands::get_all_people,
             commands::get_adjacent_people,
+            commands::query_people,
             commands::insert_person,
             commands::update_person_user_status,
             commands::delete_people,
+            // Deduplication commands
+            commands::find_duplicate_leads,
+            commands::find_duplicate_people,
+            commands::merge_leads,
+            commands::merge_people,
+            commands::get_merge_history,
+            // Research history commands
+            commands::get_research_runs,
+            commands::restore_research_run,
+            commands::diff_research_runs,
+            commands::get_sources,
+            commands::get_field_sources,
+            // Change digest commands
+            commands::get_change_digests,
+            commands::get_lead_changes,
+            commands::export_change_digest_markdown,
+            // Search commands
+            commands::search,
+            commands::search_job_logs,
+            commands::get_jobs_without_tool,
+            // Saved view commands
+            commands::get_saved_views,
+            commands::get_saved_view,
+            commands::create_saved_view,
+            commands::update_saved_view,
+            commands::set_saved_view_pinned,
+            commands::reorder_saved_views,
+            commands::delete_saved_view,
+            commands::get_saved_view_lead_ids,
+            commands::export_saved_view_csv,
+            // Tag commands
+            commands::get_tags,
+            commands::create_tag,
+            commands::update_tag,
+            commands::delete_tag,
+            commands::get_entity_tags,
+            commands::set_entity_tags,
+            commands::tag_entities,
+            // Custom field commands
+            commands::get_custom_fields,
+            commands::create_custom_field,
+            commands::update_custom_field,
+            commands::delete_custom_field,
+            commands::get_custom_field_values,
+            commands::set_custom_field_value,
+            // Field provenance commands
+            commands::get_field_provenance,
+            commands::update_entity_field,
+            commands::get_merge_policies,
+            commands::set_merge_policy,
             // Prompt commands
             commands::get_prompt_by_type,
             commands::save_prompt_by_type,
@@ -120,6 +174,12 @@ pub fn run() {
             commands::start_research,
             commands::start_person_research,
             commands::kill_job,
+            commands::cancel_jobs,
+            commands::get_job_queue_status,
+            commands::set_job_queue_mode,
+            commands::quit_app,
+            commands::get_resumable_jobs,
+            commands::resume_job,
             commands::get_active_jobs,
             // Scoring commands
             commands::start_scoring,
@@ -134,19 +194,61 @@ pub fn run() {
             commands::get_jobs_recent,
             commands::get_job_by_id,
             commands::get_job_logs_cmd,
+            commands::get_job_timeline,
+            commands::export_job_transcript,
             commands::cleanup_old_jobs_cmd,
             commands::delete_job_cmd,
+            commands::reprocess_job_output,
+            commands::purge_output_archives,
+            commands::get_log_storage_report,
+            commands::run_log_maintenance,
             // Recovery commands
             commands::get_stuck_entities,
             commands::reset_entity_status,
             commands::recover_all_stuck,
+            commands::get_harvest_report,
+            commands::harvest_orphaned_outputs,
             // Settings commands
             commands::get_settings,
             commands::update_settings,
+            commands::update_output_retention,
+            commands::update_log_retention,
+            commands::update_orphan_process_policy,
+            commands::update_job_limits,
+            commands::get_job_limit_overrides,
+            commands::set_job_limit_overrides,
+            commands::update_notification_settings,
+            // Model routing commands
+            commands::get_job_type_models,
+            commands::set_job_type_model,
+            commands::get_model_routing_rules,
+            commands::add_model_routing_rule,
+            commands::set_model_routing_rule_enabled,
+            commands::delete_model_routing_rule,
+            commands::resolve_job_model,
+            // Redaction commands
+            commands::get_redaction_rules,
+            commands::add_redaction_rule,
+            commands::set_redaction_rule_enabled,
+            commands::delete_redaction_rule,
+            commands::update_redaction_settings,
+            commands::get_raw_job_text,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app_handle, event| {
+            // Stop the job queue before exiting; the coordinator exits again when done
+            if let tauri::RunEvent::ExitRequested { api, .. } = &event {
+                if !app_handle
+                    .state::<jobs::shutdown::ShutdownState>()
+                    .is_done()
+                {
+                    api.prevent_exit();
+                    jobs::shutdown::request_exit(app_handle, jobs::shutdown::SHUTDOWN_WAIT_SECS);
+                }
+                return;
+            }
+
             // Handle macOS dock icon click
             if let tauri::RunEvent::Reopen {
                 has_visible_windows,
@@ -0,0 +1,386 @@
+//! Desktop notifications for finished jobs and hot leads
+//!
+//! Job events only reach the webview, which nobody sees while the window is
+//! hidden to the tray. Finished jobs, failures and leads newly scored hot raise
+//! a native notification instead, when their event type is enabled in the
+//! settings and the window is not in front. Failures close together are
+//! grouped into one notification. Desktop notifications report no clicks, so
+//! the subject of the latest one is offered in the tray menu instead, which
+//! opens it in the app. The tray tooltip shows running and unseen failed job
+//! counts.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::db::{self, DbState, NotificationSettings};
+use crate::events::{self, OpenEntityPayload};
+use crate::jobs::{JobMetadata, JobType};
+
+/// Failures within this window are reported in one notification
+const FAILURE_BATCH_SECS: u64 = 5;
+
+/// Failed jobs named in a grouped notification before "and N more"
+const FAILURES_NAMED: usize = 3;
+
+/// Longest tray menu label for the latest notification, in characters
+const MENU_LABEL_CHARS: usize = 48;
+
+const HOT_TIER: &str = "hot";
+
+struct FailedJob {
+    label: String,
+    target: Option<OpenEntityPayload>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Message {
+    title: String,
+    body: String,
+    /// What the notification is about, offered in the tray menu
+    target: Option<OpenEntityPayload>,
+}
+
+/// Subject of the latest notification, as offered in the tray menu
+#[derive(Clone)]
+pub struct LatestNotification {
+    pub label: String,
+    target: OpenEntityPayload,
+}
+
+#[derive(Default)]
+pub struct NotificationState {
+    latest: Mutex<Option<LatestNotification>>,
+    /// Failures waiting to be reported together
+    failures: Mutex<Vec<FailedJob>>,
+    running: AtomicUsize,
+    /// Jobs that failed since the window was last in front
+    unseen_failed: AtomicUsize,
+}
+
+fn notification_settings(app: &AppHandle) -> NotificationSettings {
+    app.try_state::<DbState>()
+        .and_then(|state| {
+            let conn = state.conn.lock().ok()?;
+            db::get_settings(&conn).ok()
+        })
+        .map(|s| s.notifications)
+        .unwrap_or_default()
+}
+
+fn window_in_front(app: &AppHandle) -> bool {
+    app.get_webview_window("main").is_some_and(|window| {
+        window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false)
+    })
+}
+
+fn show(app: &AppHandle, message: Message) {
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(&message.title)
+        .body(&message.body)
+        .show()
+    {
+        eprintln!(
+            "[notifications] Failed to show \"{}\": {}",
+            message.title, e
+        );
+    }
+    if let Some(target) = message.target {
+        if let Ok(mut latest) = app.state::<NotificationState>().latest.lock() {
+            *latest = Some(LatestNotification {
+                label: menu_label(&message.title, &message.body),
+                target,
+            });
+        }
+        crate::tray::refresh_menu(app);
+    }
+}
+
+/// "Title: body", shortened to fit a menu item
+fn menu_label(title: &str, body: &str) -> String {
+    let label = format!("{}: {}", title, body);
+    if label.chars().count() <= MENU_LABEL_CHARS {
+        return label;
+    }
+    let mut short: String = label.chars().take(MENU_LABEL_CHARS - 1).collect();
+    short.push('…');
+    short
+}
+
+/// Lead or person a job worked on
+fn job_target(app: &AppHandle, metadata: &JobMetadata) -> Option<OpenEntityPayload> {
+    match metadata.job_type {
+        JobType::CompanyResearch | JobType::Scoring => {
+            Some(OpenEntityPayload::lead(metadata.entity_id))
+        }
+        JobType::PersonResearch | JobType::Conversation => {
+            let lead_id = app.try_state::<DbState>().and_then(|state| {
+                let conn = state.conn.lock().ok()?;
+                db::get_person_raw(&conn, metadata.entity_id).ok()??.lead_id
+            });
+            Some(OpenEntityPayload::person(metadata.entity_id, lead_id))
+        }
+        JobType::LeadFinder => None,
+    }
+}
+
+/// Notify about a job that reached its final status. `previous_tier` is the
+/// lead's tier before a scoring job, to tell a newly hot lead from a re-score.
+pub fn job_finished(
+    app: &AppHandle,
+    metadata: &JobMetadata,
+    entity_label: &str,
+    status: &str,
+    previous_tier: Option<&str>,
+) {
+    match status {
+        "completed" => job_completed(app, metadata, entity_label, previous_tier),
+        "error" | "timeout" => job_failed(app, metadata, entity_label),
+        _ => {}
+    }
+}
+
+fn job_completed(
+    app: &AppHandle,
+    metadata: &JobMetadata,
+    entity_label: &str,
+    previous_tier: Option<&str>,
+) {
+    if window_in_front(app) {
+        return;
+    }
+    let settings = notification_settings(app);
+
+    if matches!(metadata.job_type, JobType::Scoring) && previous_tier != Some(HOT_TIER) {
+        let score = app.try_state::<DbState>().and_then(|state| {
+            let conn = state.conn.lock().ok()?;
+            db::get_lead_score(&conn, metadata.entity_id).ok()?
+        });
+        if let Some(score) = score.filter(|s| s.tier == HOT_TIER) {
+            if settings.hot_lead {
+                show(
+                    app,
+                    Message {
+                        title: "New HOT lead".to_string(),
+                        body: format!("{} ({})", entity_label, score.total_score),
+                        target: Some(OpenEntityPayload::lead(metadata.entity_id)),
+                    },
+                );
+            }
+            return;
+        }
+    }
+
+    if !settings.job_completed {
+        return;
+    }
+    show(
+        app,
+        Message {
+            title: completed_title(metadata.job_type).to_string(),
+            body: entity_label.to_string(),
+            target: job_target(app, metadata),
+        },
+    );
+}
+
+fn completed_title(job_type: JobType) -> &'static str {
+    match job_type {
+        JobType::CompanyResearch | JobType::PersonResearch => "Research finished",
+        JobType::Scoring => "Scoring finished",
+        JobType::Conversation => "Conversation topics ready",
+        JobType::LeadFinder => "Lead search finished",
+    }
+}
+
+fn job_failed(app: &AppHandle, metadata: &JobMetadata, entity_label: &str) {
+    if window_in_front(app) {
+        return;
+    }
+    let state = app.state::<NotificationState>();
+    state.unseen_failed.fetch_add(1, Ordering::SeqCst);
+    refresh_tray(app);
+
+    let Ok(mut failures) = state.failures.lock() else {
+        return;
+    };
+    failures.push(FailedJob {
+        label: entity_label.to_string(),
+        target: job_target(app, metadata),
+    });
+    // The first failure of a batch reports the whole batch once it settles
+    if failures.len() == 1 {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(FAILURE_BATCH_SECS)).await;
+            report_failures(&app);
+        });
+    }
+}
+
+fn report_failures(app: &AppHandle) {
+    let failures = match app.state::<NotificationState>().failures.lock() {
+        Ok(mut failures) => std::mem::take(&mut *failures),
+        Err(_) => return,
+    };
+    if !notification_settings(app).job_failed || window_in_front(app) {
+        return;
+    }
+    if let Some(message) = failure_message(failures) {
+        show(app, message);
+    }
+}
+
+/// One notification for a batch of failures: the job itself when only one
+/// failed, otherwise a count naming the first few
+fn failure_message(mut failures: Vec<FailedJob>) -> Option<Message> {
+    if failures.len() <= 1 {
+        let failure = failures.pop()?;
+        return Some(Message {
+            title: "Job failed".to_string(),
+            body: failure.label,
+            target: failure.target,
+        });
+    }
+    let mut body = failures
+        .iter()
+        .take(FAILURES_NAMED)
+        .map(|f| f.label.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if failures.len() > FAILURES_NAMED {
+        body.push_str(&format!(" and {} more", failures.len() - FAILURES_NAMED));
+    }
+    Some(Message {
+        title: format!("{} jobs failed", failures.len()),
+        body,
+        target: Some(OpenEntityPayload::jobs()),
+    })
+}
+
+/// Record how many jobs are running for the tray tooltip
+pub fn set_running(app: &AppHandle, running: usize) {
+    if let Some(state) = app.try_state::<NotificationState>() {
+        state.running.store(running, Ordering::SeqCst);
+        refresh_tray(app);
+    }
+}
+
+fn refresh_tray(app: &AppHandle) {
+    let state = app.state::<NotificationState>();
+    crate::tray::set_job_counts(
+        app,
+        state.running.load(Ordering::SeqCst),
+        state.unseen_failed.load(Ordering::SeqCst),
+    );
+}
+
+/// The window came to the front: failures count as seen
+pub fn window_activated(app: &AppHandle) {
+    let Some(state) = app.try_state::<NotificationState>() else {
+        return;
+    };
+    if state.unseen_failed.swap(0, Ordering::SeqCst) > 0 {
+        refresh_tray(app);
+    }
+}
+
+/// Subject of the latest notification not opened yet
+pub fn latest(app: &AppHandle) -> Option<LatestNotification> {
+    let state = app.try_state::<NotificationState>()?;
+    let latest = state.latest.lock().ok()?;
+    latest.clone()
+}
+
+/// Open the subject of the latest notification, chosen from the tray menu
+pub fn open_latest(app: &AppHandle) {
+    let Some(state) = app.try_state::<NotificationState>() else {
+        return;
+    };
+    let latest = state.latest.lock().ok().and_then(|mut l| l.take());
+    if let Some(latest) = latest {
+        crate::tray::show_main_window(app);
+        events::emit_open_entity(app, latest.target);
+        crate::tray::refresh_menu(app);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{failure_message, menu_label, FailedJob, Message};
+    use crate::db::test_support::memory_db;
+    use crate::db::{get_settings, update_notification_settings, NotificationSettings};
+    use crate::events::OpenEntityPayload;
+
+    fn failed(label: &str, lead_id: i64) -> FailedJob {
+        FailedJob {
+            label: label.to_string(),
+            target: Some(OpenEntityPayload::lead(lead_id)),
+        }
+    }
+
+    #[test]
+    fn a_single_failure_names_the_job_and_opens_its_entity() {
+        assert_eq!(failure_message(Vec::new()), None);
+        assert_eq!(
+            failure_message(vec![failed("Acme", 1)]),
+            Some(Message {
+                title: "Job failed".to_string(),
+                body: "Acme".to_string(),
+                target: Some(OpenEntityPayload::lead(1)),
+            })
+        );
+    }
+
+    #[test]
+    fn grouped_failures_name_the_first_few_and_open_the_job_list() {
+        let two = failure_message(vec![failed("Acme", 1), failed("Globex", 2)]).unwrap();
+        assert_eq!(
+            (two.title.as_str(), two.body.as_str()),
+            ("2 jobs failed", "Acme, Globex")
+        );
+        assert_eq!(two.target, Some(OpenEntityPayload::jobs()));
+
+        let five = ["Acme", "Globex", "Initech", "Umbrella", "Hooli"]
+            .iter()
+            .enumerate()
+            .map(|(i, label)| failed(label, i as i64))
+            .collect();
+        let five = failure_message(five).unwrap();
+        assert_eq!(five.title, "5 jobs failed");
+        assert_eq!(five.body, "Acme, Globex, Initech and 2 more");
+    }
+
+    #[test]
+    fn menu_labels_are_shortened_to_fit() {
+        assert_eq!(
+            menu_label("Research finished", "Acme"),
+            "Research finished: Acme"
+        );
+        let long = menu_label("5 jobs failed", &"Acme Corporation, ".repeat(5));
+        assert_eq!(long.chars().count(), 48);
+        assert!(long.starts_with("5 jobs failed: Acme Corporation") && long.ends_with('…'));
+    }
+
+    #[test]
+    fn notification_toggles_are_stored_per_event_type() {
+        let conn = memory_db();
+        assert_eq!(
+            get_settings(&conn).unwrap().notifications,
+            NotificationSettings::default()
+        );
+
+        let notifications = NotificationSettings {
+            job_completed: false,
+            ..NotificationSettings::default()
+        };
+        update_notification_settings(&conn, &notifications).unwrap();
+        let stored = get_settings(&conn).unwrap().notifications;
+        assert_eq!(stored, notifications);
+        assert!(stored.job_failed && stored.hot_lead);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::db::{self, DbState};
+use crate::events;
+use crate::jobs::{JobQueue, QueueMode};
+use tauri::{
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+    AppHandle, Manager, Wry,
+};
+
+pub const TRAY_ID: &str = "main";
+
+/// Menu item id prefix for pinned saved views ("view:<id>")
+const VIEW_ITEM_PREFIX: &str = "view:";
+
+/// Build the tray menu: pinned saved views with live counts, then the app items
+pub fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+
+    let pinned = app
+        .try_state::<DbState>()
+        .and_then(|state| {
+            let conn = state.conn.lock().ok()?;
+            match db::get_pinned_saved_views(&conn) {
+                Ok(views) => Some(views),
+                Err(e) => {
+                    eprintln!("[tray] Failed to load pinned saved views: {}", e);
+                    None
+                }
+            }
+        })
+        .unwrap_or_default();
+
+    for view in &pinned {
+        let text = match (view.count, &view.error) {
+            (Some(count), _) => format!("{} ({})", view.name, count),
+            (None, error) => {
+                eprintln!(
+                    "[tray] Saved view {} \"{}\" can't be applied: {}",
+                    view.id,
+                    view.name,
+                    error.as_deref().unwrap_or("unknown error")
+                );
+                format!("{} (needs fixing)", view.name)
+            }
+        };
+        let item = MenuItem::with_id(
+            app,
+            format!("{}{}", VIEW_ITEM_PREFIX, view.id),
+            text,
+            true,
+            None::<&str>,
+        )?;
+        menu.append(&item)?;
+    }
+    if !pinned.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    if let Some(latest) = crate::notifications::latest(app) {
+        menu.append(&MenuItem::with_id(
+            app,
+            "notification:open",
+            format!("Open {}", latest.label),
+            true,
+            None::<&str>,
+        )?)?;
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    if let Some(queue) = app.try_state::<JobQueue>() {
+        let mode = queue.mode();
+        let status = MenuItem::with_id(
+            app,
+            "queue:status",
+            format!("Job Queue: {}", mode.as_str()),
+            false,
+            None::<&str>,
+        )?;
+        menu.append(&status)?;
+        if mode == QueueMode::Running {
+            menu.append(&MenuItem::with_id(
+                app,
+                "queue:pause",
+                "Pause Queue",
+                true,
+                None::<&str>,
+            )?)?;
+        } else {
+            menu.append(&MenuItem::with_id(
+                app,
+                "queue:resume",
+                "Resume Queue",
+                true,
+                None::<&str>,
+            )?)?;
+        }
+        if mode != QueueMode::Draining {
+            menu.append(&MenuItem::with_id(
+                app,
+                "queue:drain",
+                "Drain Queue",
+                true,
+                None::<&str>,
+            )?)?;
+        }
+        menu.append(&MenuItem::with_id(
+            app,
+            "queue:cancel_all",
+            "Cancel All Jobs",
+            true,
+            None::<&str>,
+        )?)?;
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    let show_item = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    menu.append(&show_item)?;
+    menu.append(&quit_item)?;
+
+    Ok(menu)
+}
+
+/// Rebuild the tray menu so pinned views and their counts are current
+pub fn refresh_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => eprintln!("[tray] Failed to rebuild menu: {}", e),
+    }
+}
+
+/// Show running and unseen failed job counts in the tray tooltip
+pub fn set_job_counts(app: &AppHandle, running: usize, failed: usize) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let mut counts = Vec::new();
+    if running > 0 {
+        counts.push(format!("{} running", running));
+    }
+    if failed > 0 {
+        counts.push(format!("{} failed", failed));
+    }
+    let tooltip = if counts.is_empty() {
+        "Qualify".to_string()
+    } else {
+        format!("Qualify: {}", counts.join(", "))
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+pub fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id.as_ref() {
+        "quit" => {
+            crate::jobs::shutdown::request_exit(app, crate::jobs::shutdown::SHUTDOWN_WAIT_SECS)
+        }
+        "show" => show_main_window(app),
+        "notification:open" => crate::notifications::open_latest(app),
+        "queue:pause" => set_queue_mode(app, QueueMode::Paused),
+        "queue:resume" => set_queue_mode(app, QueueMode::Running),
+        "queue:drain" => set_queue_mode(app, QueueMode::Draining),
+        "queue:cancel_all" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                app.state::<JobQueue>().cancel_jobs(&app, None, None).await;
+            });
+        }
+        id => {
+            if let Some(view_id) = id
+                .strip_prefix(VIEW_ITEM_PREFIX)
+                .and_then(|v| v.parse::<i64>().ok())
+            {
+                show_main_window(app);
+                events::emit_open_saved_view(app, view_id);
+            }
+        }
+    }
+}
+
+fn set_queue_mode(app: &AppHandle, mode: QueueMode) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        app.state::<JobQueue>().set_mode(&app, mode).await;
+    });
+}
@@ -6,6 +6,7 @@ import { Sidebar } from "@/components/layout/sidebar";
 import { StreamPanelWrapper } from "@/components/stream-panel/stream-panel-wrapper";
 import { Toaster } from "@/components/ui/sonner";
 import { useEventBridge } from "@/lib/tauri/use-event-bridge";
+import { useOpenEntity } from "@/lib/tauri/use-open-entity";
 import { queryClient } from "@/lib/query/query-client";
 import { CompanyOverviewDialog } from "@/components/onboarding/company-overview-dialog";
 import { useOnboardingStatus } from "@/lib/query";
@@ -21,6 +22,7 @@ const ScoringPage = lazy(() => import("@/pages/scoring"));
 
 function AppContent() {
   const { data: onboardingStatus, isLoading } = useOnboardingStatus();
+  useOpenEntity();
 
   if (isLoading) {
     return (
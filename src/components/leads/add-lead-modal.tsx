@@ -1,6 +1,7 @@
 "use client";
 
 import { useState } from "react";
+import { useNavigate } from "react-router-dom";
 import {
   Dialog,
   DialogContent,
@@ -13,50 +14,77 @@ import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { IconPlus, IconLoader2 } from "@tabler/icons-react";
-import { insertLead } from "@/lib/tauri/commands";
+import { insertLead, isInsertLeadError } from "@/lib/tauri/commands";
 import { toast } from "sonner";
 
 interface AddLeadModalProps {
   onSuccess?: () => void;
 }
 
+const EMPTY_FORM = { companyName: "", website: "", city: "", state: "", country: "" };
+
 export function AddLeadModal({ onSuccess }: AddLeadModalProps) {
+  const navigate = useNavigate();
   const [open, setOpen] = useState(false);
   const [loading, setLoading] = useState(false);
-  const [formData, setFormData] = useState({
-    companyName: "",
-    website: "",
-    city: "",
-    state: "",
-    country: "",
-  });
+  // Existing lead for the same company, found when adding without `force`
+  const [existingId, setExistingId] = useState<number | null>(null);
+  const [formData, setFormData] = useState(EMPTY_FORM);
 
-  const handleSubmit = async (e: React.FormEvent) => {
-    e.preventDefault();
-    if (!formData.companyName.trim()) return;
+  // Editing the company after a duplicate warning checks it again
+  const setField = (field: keyof typeof EMPTY_FORM, value: string) => {
+    setFormData((prev) => ({ ...prev, [field]: value }));
+    setExistingId(null);
+  };
+
+  const close = () => {
+    setOpen(false);
+    setExistingId(null);
+    setFormData(EMPTY_FORM);
+  };
 
+  const addLead = async (force: boolean) => {
     setLoading(true);
     try {
-      await insertLead({
-        companyName: formData.companyName.trim(),
-        website: formData.website.trim() || undefined,
-        city: formData.city.trim() || undefined,
-        state: formData.state.trim() || undefined,
-        country: formData.country.trim() || undefined,
-      });
-      setOpen(false);
-      setFormData({ companyName: "", website: "", city: "", state: "", country: "" });
+      await insertLead(
+        {
+          companyName: formData.companyName.trim(),
+          website: formData.website.trim() || undefined,
+          city: formData.city.trim() || undefined,
+          state: formData.state.trim() || undefined,
+          country: formData.country.trim() || undefined,
+        },
+        force
+      );
+      close();
       onSuccess?.();
       toast.success("Lead added successfully");
-    } catch {
-      toast.error("Failed to add lead");
+    } catch (error) {
+      if (isInsertLeadError(error) && error.existingId !== null) {
+        setExistingId(error.existingId);
+      } else {
+        toast.error("Failed to add lead");
+      }
     } finally {
       setLoading(false);
     }
   };
 
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!formData.companyName.trim()) return;
+    await addLead(false);
+  };
+
+  const openExisting = () => {
+    if (existingId === null) return;
+    const id = existingId;
+    close();
+    navigate(`/lead/${id}`);
+  };
+
   return (
-    <Dialog open={open} onOpenChange={setOpen}>
+    <Dialog open={open} onOpenChange={(next) => (next ? setOpen(true) : close())}>
       <DialogTrigger asChild>
         <Button variant="outline" size="sm">
           <IconPlus className="size-3.5" />
@@ -73,7 +101,7 @@ export function AddLeadModal({ onSuccess }: AddLeadModalProps) {
             <Input
               id="companyName"
               value={formData.companyName}
-              onChange={(e) => setFormData((prev) => ({ ...prev, companyName: e.target.value }))}
+              onChange={(e) => setField("companyName", e.target.value)}
               placeholder="Acme Inc."
               required
             />
@@ -83,7 +111,7 @@ export function AddLeadModal({ onSuccess }: AddLeadModalProps) {
             <Input
               id="website"
               value={formData.website}
-              onChange={(e) => setFormData((prev) => ({ ...prev, website: e.target.value }))}
+              onChange={(e) => setField("website", e.target.value)}
               placeholder="https://example.com"
             />
           </div>
@@ -93,7 +121,7 @@ export function AddLeadModal({ onSuccess }: AddLeadModalProps) {
               <Input
                 id="city"
                 value={formData.city}
-                onChange={(e) => setFormData((prev) => ({ ...prev, city: e.target.value }))}
+                onChange={(e) => setField("city", e.target.value)}
                 placeholder="San Francisco"
               />
             </div>
@@ -102,7 +130,7 @@ export function AddLeadModal({ onSuccess }: AddLeadModalProps) {
               <Input
                 id="state"
                 value={formData.state}
-                onChange={(e) => setFormData((prev) => ({ ...prev, state: e.target.value }))}
+                onChange={(e) => setField("state", e.target.value)}
                 placeholder="CA"
               />
             </div>
@@ -112,16 +140,33 @@ export function AddLeadModal({ onSuccess }: AddLeadModalProps) {
             <Input
               id="country"
               value={formData.country}
-              onChange={(e) => setFormData((prev) => ({ ...prev, country: e.target.value }))}
+              onChange={(e) => setField("country", e.target.value)}
               placeholder="United States"
             />
           </div>
-          <DialogFooter>
-            <Button type="submit" disabled={loading || !formData.companyName.trim()}>
-              {loading && <IconLoader2 className="size-3.5 animate-spin" />}
-              {loading ? "Adding..." : "Add Lead"}
-            </Button>
-          </DialogFooter>
+          {existingId !== null ? (
+            <>
+              <p className="text-sm text-muted-foreground">
+                A lead for this company already exists.
+              </p>
+              <DialogFooter>
+                <Button type="button" variant="outline" onClick={openExisting}>
+                  Open existing
+                </Button>
+                <Button type="button" disabled={loading} onClick={() => addLead(true)}>
+                  {loading && <IconLoader2 className="size-3.5 animate-spin" />}
+                  Add anyway
+                </Button>
+              </DialogFooter>
+            </>
+          ) : (
+            <DialogFooter>
+              <Button type="submit" disabled={loading || !formData.companyName.trim()}>
+                {loading && <IconLoader2 className="size-3.5 animate-spin" />}
+                {loading ? "Adding..." : "Add Lead"}
+              </Button>
+            </DialogFooter>
+          )}
         </form>
       </DialogContent>
     </Dialog>
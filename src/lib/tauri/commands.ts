@@ -34,8 +34,19 @@ export async function getAdjacentLeads(currentId: number): Promise<AdjacentResul
   return invoke("get_adjacent_leads", { currentId });
 }
 
-export async function insertLead(data: NewLead): Promise<number> {
-  return invoke("insert_lead", { data });
+// Rejected with an InsertLeadError naming the existing lead when the company
+// already exists, unless `force` is set
+export async function insertLead(data: NewLead, force = false): Promise<number> {
+  return invoke("insert_lead", { data, force });
+}
+
+export interface InsertLeadError {
+  message: string;
+  existingId: number | null;
+}
+
+export function isInsertLeadError(error: unknown): error is InsertLeadError {
+  return typeof error === "object" && error !== null && "existingId" in error;
 }
 
 export async function updateLeadUserStatus(leadId: number, status: string): Promise<void> {
@@ -201,6 +212,22 @@ export async function killJob(jobId: string): Promise<void> {
   return invoke("kill_job", { jobId });
 }
 
+// Jobs interrupted by an app shutdown whose agent session can be resumed
+export async function getResumableJobs(): Promise<Job[]> {
+  return invoke("get_resumable_jobs");
+}
+
+// Queue a run that continues the agent session of an interrupted job
+export async function resumeJob(
+  jobId: string,
+  onEvent: (event: StreamEvent) => void
+): Promise<ResearchResult> {
+  const channel = new Channel<StreamEvent>();
+  channel.onmessage = onEvent;
+
+  return invoke("resume_job", { jobId, onEvent: channel });
+}
+
 // ============================================================================
 // Scoring Commands
 // ============================================================================
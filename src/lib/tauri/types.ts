@@ -217,7 +217,8 @@ export interface OnboardingStatus {
 
 export type JobType =
   "company_research" | "person_research" | "scoring" | "conversation" | "lead_finder";
-export type JobStatus = "queued" | "running" | "completed" | "error" | "timeout" | "cancelled";
+export type JobStatus =
+  "queued" | "running" | "completed" | "error" | "timeout" | "cancelled" | "interrupted";
 
 export interface Job {
   id: string;
@@ -0,0 +1,36 @@
+import { useEffect } from "react";
+import { useNavigate } from "react-router-dom";
+import { listen } from "@tauri-apps/api/event";
+import { useStreamPanelStore } from "@/lib/store/stream-panel-store";
+
+// What the backend asks to open, e.g. the subject of the latest notification
+interface OpenEntityPayload {
+  entityType: "lead" | "person" | "jobs";
+  id: number | null;
+  leadId: number | null;
+}
+
+/**
+ * Hook that navigates to the entity named by `open-entity` events.
+ * Must be called inside the router.
+ */
+export function useOpenEntity() {
+  const navigate = useNavigate();
+
+  useEffect(() => {
+    const unlisten = listen<OpenEntityPayload>("open-entity", (event) => {
+      const { entityType, id } = event.payload;
+      if (entityType === "lead" && id !== null) {
+        navigate(`/lead/${id}`);
+      } else if (entityType === "person" && id !== null) {
+        navigate(`/people/${id}`);
+      } else if (entityType === "jobs") {
+        useStreamPanelStore.getState().setOpen(true);
+      }
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [navigate]);
+}